@@ -140,7 +140,7 @@ object Examples {
                                 window.setFullscreen(fullscreen)
                             }
 
-                            "g" -> window.setCursorGrab(state)
+                            "g" -> window.setCursorGrab(if (state) CursorGrabMode.CONFINED else CursorGrabMode.NONE)
                             "h" -> window.setCursorVisible(!state)
                             "i" -> {
                                 println("Info:")
@@ -152,29 +152,29 @@ object Examples {
                             }
 
                             "l" -> window.setMinInnerSize(
-                                if (state) PhysicalSizeU32(baseWidth, baseHeight) else null,
+                                if (state) Size.Physical(baseWidth, baseHeight) else null,
                             )
 
                             "m" -> window.setMaximized(state)
                             "p" -> {
                                 val pos = runCatching { window.outerPosition() }.getOrNull() ?: return
                                 val sign = if (state) 1 else -1
-                                window.setOuterPosition(PhysicalPositionI32(pos.x + 10 * sign, pos.y + 10 * sign))
+                                window.setOuterPosition(Position.Physical(pos.x + 10 * sign, pos.y + 10 * sign))
                             }
 
                             "q" -> window.requestRedraw()
                             "r" -> window.setResizable(state)
                             "s" -> {
                                 val size = if (state) {
-                                    PhysicalSizeU32(baseWidth + 100u, baseHeight + 100u)
+                                    Size.Physical(baseWidth + 100u, baseHeight + 100u)
                                 } else {
-                                    PhysicalSizeU32(baseWidth, baseHeight)
+                                    Size.Physical(baseWidth, baseHeight)
                                 }
                                 window.setInnerSize(size)
                             }
 
                             "w" -> window.setCursorPosition(
-                                PhysicalPositionI32((baseWidth / 2u).toInt(), (baseHeight / 2u).toInt()),
+                                Position.Physical((baseWidth / 2u).toInt(), (baseHeight / 2u).toInt()),
                             )
 
                             "z" -> {
@@ -215,8 +215,8 @@ object Examples {
                             if (event.cause == TaoStartCause.Init) {
                                 val builder = WindowBuilder().apply {
                                     setTitle("A fantastic window!")
-                                    setInnerSize(LogicalSize(300.0, 300.0))
-                                    setMinInnerSize(LogicalSize(200.0, 200.0))
+                                    setInnerSize(Size.Logical(300.0, 300.0))
+                                    setMinInnerSize(Size.Logical(200.0, 200.0))
                                 }
                                 window = app.createWindow(builder)
                             }
@@ -384,7 +384,7 @@ object Examples {
                                     when (val k = keyEvent.logicalKey) {
                                         Key.Escape -> return ControlFlow.Exit
                                         is Key.Character -> when (k.value.lowercase()) {
-                                            "g" -> window?.setCursorGrab(!modifiers.shift)
+                                            "g" -> window?.setCursorGrab(if (modifiers.shift) CursorGrabMode.NONE else CursorGrabMode.LOCKED)
                                             "h" -> window?.setCursorVisible(modifiers.shift)
                                         }
 
@@ -430,7 +430,7 @@ object Examples {
                                 while (true) {
                                     Thread.sleep(1_000)
                                     try {
-                                        proxy.sendEvent(TaoUserEvent.Timer)
+                                        proxy.sendEvent(TaoUserEvent.Timer(id = 0uL))
                                     } catch (_: TaoException) {
                                         return@thread
                                     }
@@ -460,7 +460,7 @@ object Examples {
                             window = app.createWindow(
                                 WindowBuilder().apply {
                                     setTitle("Hit space to toggle decorations.")
-                                    setInnerSize(LogicalSize(400.0, 200.0))
+                                    setInnerSize(Size.Logical(400.0, 200.0))
                                     setDecorations(decorations)
                                 },
                             )
@@ -508,7 +508,7 @@ object Examples {
 
                         is TaoEvent.WindowEvent -> when (val we = event.event) {
                             TaoWindowEvent.CloseRequested -> return ControlFlow.Exit
-                            TaoWindowEvent.CursorEntered -> {
+                            is TaoWindowEvent.CursorEntered -> {
                                 enteredId = event.windowId
                                 nameWindows()
                             }
@@ -821,7 +821,7 @@ object Examples {
                                             try {
                                                 val pos = w.outerPosition()
                                                 w.setOuterPosition(
-                                                    PhysicalPositionI32(
+                                                    Position.Physical(
                                                         pos.x - (delta.x * pixelsPerLine).toInt(),
                                                         pos.y - (delta.y * pixelsPerLine).toInt(),
                                                     ),
@@ -835,7 +835,7 @@ object Examples {
                                             try {
                                                 val pos = w.outerPosition()
                                                 w.setOuterPosition(
-                                                    PhysicalPositionI32(
+                                                    Position.Physical(
                                                         pos.x - delta.x.toInt(),
                                                         pos.y - delta.y.toInt(),
                                                     ),
@@ -913,7 +913,7 @@ object Examples {
                         repeat(windowCount) {
                             val w = app.createWindow(
                                 WindowBuilder().apply {
-                                    setInnerSize(LogicalSize(baseWidth.toDouble(), baseHeight.toDouble()))
+                                    setInnerSize(Size.Logical(baseWidth.toDouble(), baseHeight.toDouble()))
                                 },
                             )
                             val worker = MultithreadedWorker(w, baseWidth, baseHeight)
@@ -1041,7 +1041,7 @@ object Examples {
 
                             val mainWindow = app.createWindowDefault()
                             val childBuilder = WindowBuilder().apply {
-                                setInnerSize(LogicalSize(200.0, 200.0))
+                                setInnerSize(Size.Logical(200.0, 200.0))
                                 setParentWindow(mainWindow)
                             }
                             val childWindow = app.createWindow(childBuilder)
@@ -1252,7 +1252,7 @@ object Examples {
                             window = app.createWindow(
                                 WindowBuilder().apply {
                                     setTitle("Hit space to toggle resizability.")
-                                    setInnerSize(LogicalSize(400.0, 200.0))
+                                    setInnerSize(Size.Logical(400.0, 200.0))
                                     setResizable(resizable)
                                 },
                             )
@@ -1300,7 +1300,7 @@ object Examples {
                             is TaoWindowEvent.CursorMoved -> cursorPos = we.position
                             is TaoWindowEvent.MouseInput -> if (we.state == ElementState.RELEASED) {
                                 println("Setting ime position to ${cursorPos.x}, ${cursorPos.y}")
-                                window?.setImePosition(cursorPos)
+                                window?.setImePosition(Position.Physical(cursorPos.x.toInt(), cursorPos.y.toInt()))
                             }
 
                             TaoWindowEvent.CloseRequested -> return ControlFlow.Exit
@@ -1469,7 +1469,7 @@ object Examples {
                             window = app.createWindow(
                                 WindowBuilder().apply {
                                     setTitle("A fantastic window!")
-                                    setInnerSize(LogicalSize(100.0, 100.0))
+                                    setInnerSize(Size.Logical(100.0, 100.0))
                                 },
                             )
                         }
@@ -1632,7 +1632,7 @@ object Examples {
                     return ControlFlow.Wait
                 }
 
-                override fun render() {
+                override fun render(frame: FrameInfo) {
                     println("rendering")
                     Thread.sleep(16)
                 }
@@ -6,6 +6,7 @@ import org.jetbrains.kotlin.konan.target.HostManager
 import org.jetbrains.kotlin.konan.target.KonanTarget
 
 plugins {
+    alias(libs.plugins.androidLibrary)
     alias(libs.plugins.gobleyCargo)
     alias(libs.plugins.gobleyUniffi)
     alias(libs.plugins.kotlinMultiplatform)
@@ -35,11 +36,15 @@ uniffi {
 
 kotlin {
     jvm()
+    androidTarget()
     linuxX64()
     linuxArm64()
     macosX64()
     macosArm64()
     mingwX64()
+    iosArm64()
+    iosSimulatorArm64()
+    iosX64()
 
     jvmToolchain(17)
 
@@ -49,6 +54,19 @@ kotlin {
         }
     }
 
+    androidTarget {
+        compilerOptions {
+            jvmTarget = JvmTarget.JVM_17
+        }
+    }
+
+    sourceSets {
+        androidMain.dependencies {
+            implementation(libs.androidx.activity)
+            implementation(libs.androidx.core)
+        }
+    }
+
     targets.withType<KotlinNativeTarget>().configureEach {
         val isHostTarget = (konanTarget == HostManager.host)
         if (!enableAllNativeTargets.get() && !isHostTarget) {
@@ -62,6 +80,21 @@ kotlin {
     }
 }
 
+android {
+    namespace = "io.github.kdroidfilter.taokt"
+    compileSdk = 35
+    defaultConfig {
+        minSdk = 26
+        // The ABIs tao supports; Gobley builds the Rust library for each of them.
+        ndk { abiFilters += listOf("arm64-v8a", "x86_64") }
+        consumerProguardFiles("consumer-rules.pro")
+    }
+    compileOptions {
+        sourceCompatibility = JavaVersion.VERSION_17
+        targetCompatibility = JavaVersion.VERSION_17
+    }
+}
+
 if (!enableAllNativeTargets.get()) {
     val hostKonanTarget = HostManager.host
 
@@ -73,6 +106,9 @@ if (!enableAllNativeTargets.get()) {
             KonanTarget.MACOS_X64 -> "MacOSX64"
             KonanTarget.MACOS_ARM64 -> "MacOSArm64"
             KonanTarget.MINGW_X64 -> "MinGWX64"
+            KonanTarget.IOS_ARM64 -> "IOSArm64"
+            KonanTarget.IOS_SIMULATOR_ARM64 -> "IOSSimulatorArm64"
+            KonanTarget.IOS_X64 -> "IOSX64"
             else -> null
         }
         return buildSet {
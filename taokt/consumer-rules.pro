@@ -0,0 +1,9 @@
+# JNI entry points generated by tao::android_binding! and the methods TaoKt calls through JNI.
+-keep class io.github.kdroidfilter.taokt.TaoActivity {
+    native <methods>;
+    public void showSoftKeyboard();
+    public void hideSoftKeyboard();
+}
+# UniFFI bindings go through JNA.
+-keep class com.sun.jna.** { *; }
+-keep class io.github.kdroidfilter.taokt.tao.** { *; }
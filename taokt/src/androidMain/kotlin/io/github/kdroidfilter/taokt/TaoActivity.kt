@@ -0,0 +1,155 @@
+package io.github.kdroidfilter.taokt
+
+import android.content.res.Configuration
+import android.os.Bundle
+import android.view.Surface
+import android.view.View
+import androidx.activity.ComponentActivity
+import androidx.activity.OnBackPressedCallback
+import androidx.core.content.ContextCompat
+import androidx.core.graphics.Insets
+import androidx.core.view.ViewCompat
+import androidx.core.view.WindowCompat
+import androidx.core.view.WindowInsetsCompat
+import io.github.kdroidfilter.taokt.tao.PhysicalInsets
+import io.github.kdroidfilter.taokt.tao.PhysicalSizeU32
+import io.github.kdroidfilter.taokt.tao.androidBackPressed
+import io.github.kdroidfilter.taokt.tao.androidConfigurationChanged
+import io.github.kdroidfilter.taokt.tao.androidInsetsChanged
+
+/**
+ * The activity hosting the TaoKt event loop.
+ *
+ * The `create` … `focus` natives are generated by `tao::android_binding!` in `android.rs`; creating the activity
+ * starts the loop registered with `runAndroid`. The activity also forwards what tao does not see (back presses,
+ * window insets, rotations) and implements the soft keyboard calls TaoKt makes through JNI.
+ *
+ * It is declared in the library manifest with `configChanges="orientation|screenSize"`; add a launcher
+ * intent filter to it, or subclass it, in the application manifest.
+ */
+open class TaoActivity : ComponentActivity() {
+    override fun onCreate(savedInstanceState: Bundle?) {
+        super.onCreate(savedInstanceState)
+        create(this)
+
+        onBackPressedDispatcher.addCallback(this, object : OnBackPressedCallback(true) {
+            override fun handleOnBackPressed() {
+                if (androidBackPressed()) return
+                // Not consumed by the application: run the default action.
+                isEnabled = false
+                onBackPressedDispatcher.onBackPressed()
+                isEnabled = true
+            }
+        })
+
+        ViewCompat.setOnApplyWindowInsetsListener(window.decorView) { view, insets ->
+            androidInsetsChanged(
+                insets.getInsets(WindowInsetsCompat.Type.statusBars()).toPhysical(),
+                insets.getInsets(WindowInsetsCompat.Type.navigationBars()).toPhysical(),
+                insets.getInsets(WindowInsetsCompat.Type.displayCutout()).toPhysical(),
+                insets.getInsets(WindowInsetsCompat.Type.ime()).toPhysical(),
+            )
+            ViewCompat.onApplyWindowInsets(view, insets)
+        }
+    }
+
+    override fun onStart() {
+        super.onStart()
+        start()
+    }
+
+    override fun onResume() {
+        super.onResume()
+        resume()
+    }
+
+    override fun onPause() {
+        super.onPause()
+        pause()
+    }
+
+    override fun onStop() {
+        super.onStop()
+        stop()
+    }
+
+    override fun onSaveInstanceState(outState: Bundle) {
+        super.onSaveInstanceState(outState)
+        save()
+    }
+
+    override fun onDestroy() {
+        super.onDestroy()
+        destroy()
+    }
+
+    @Deprecated("Deprecated in Java")
+    override fun onLowMemory() {
+        super.onLowMemory()
+        memory()
+    }
+
+    override fun onWindowFocusChanged(hasFocus: Boolean) {
+        super.onWindowFocusChanged(hasFocus)
+        focus(hasFocus)
+    }
+
+    override fun onConfigurationChanged(newConfig: Configuration) {
+        super.onConfigurationChanged(newConfig)
+        // The decor view has the new size once it is laid out again.
+        val decorView = window.decorView
+        decorView.addOnLayoutChangeListener(object : View.OnLayoutChangeListener {
+            override fun onLayoutChange(
+                view: View, left: Int, top: Int, right: Int, bottom: Int,
+                oldLeft: Int, oldTop: Int, oldRight: Int, oldBottom: Int,
+            ) {
+                view.removeOnLayoutChangeListener(this)
+                val size = PhysicalSizeU32(view.width.toUInt(), view.height.toUInt())
+                androidConfigurationChanged(size, rotationDegrees())
+            }
+        })
+        decorView.requestLayout()
+    }
+
+    /** Called by TaoKt through JNI, from any thread. */
+    fun showSoftKeyboard() {
+        runOnUiThread {
+            val view = window.decorView
+            view.isFocusableInTouchMode = true
+            view.requestFocus()
+            WindowCompat.getInsetsController(window, view).show(WindowInsetsCompat.Type.ime())
+        }
+    }
+
+    /** Called by TaoKt through JNI, from any thread. */
+    fun hideSoftKeyboard() {
+        runOnUiThread {
+            WindowCompat.getInsetsController(window, window.decorView).hide(WindowInsetsCompat.Type.ime())
+        }
+    }
+
+    private fun rotationDegrees(): UInt = when (ContextCompat.getDisplayOrDefault(this).rotation) {
+        Surface.ROTATION_90 -> 90u
+        Surface.ROTATION_180 -> 180u
+        Surface.ROTATION_270 -> 270u
+        else -> 0u
+    }
+
+    private fun Insets.toPhysical() = PhysicalInsets(left.toUInt(), top.toUInt(), right.toUInt(), bottom.toUInt())
+
+    private external fun create(activity: TaoActivity)
+    private external fun start()
+    private external fun resume()
+    private external fun pause()
+    private external fun stop()
+    private external fun save()
+    private external fun destroy()
+    private external fun memory()
+    private external fun focus(focus: Boolean)
+
+    companion object {
+        init {
+            System.loadLibrary("taokt")
+        }
+    }
+}
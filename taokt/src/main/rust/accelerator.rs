@@ -0,0 +1,210 @@
+//! Keyboard accelerators such as `"CmdOrCtrl+Shift+P"`.
+//!
+//! The same syntax is accepted by menu items and `GlobalShortcutManager`. Modifiers are
+//! case-insensitive: `Shift`, `Ctrl`/`Control`, `Alt`/`Option`, `Super`/`Cmd`/`Command`/`Meta`,
+//! and `CmdOrCtrl`, which means Cmd on macOS and Ctrl elsewhere. The key is a letter, digit,
+//! punctuation character, `F1`-`F24`, a name like `Enter` or `Up`, or a W3C key code like `KeyP`.
+
+use std::fmt;
+
+use crate::{KeyCode, ModifiersState, TaoError};
+
+/// Named keys as (W3C code, accepted aliases, macOS symbol, label elsewhere).
+const NAMED_KEYS: &[(&str, &[&str], &str, &str)] = &[
+    ("Space", &["space"], "Space", "Space"),
+    ("Enter", &["enter", "return"], "↩", "Enter"),
+    ("Tab", &["tab"], "⇥", "Tab"),
+    ("Escape", &["escape", "esc"], "⎋", "Esc"),
+    ("Backspace", &["backspace"], "⌫", "Backspace"),
+    ("Delete", &["delete", "del"], "⌦", "Del"),
+    ("Insert", &["insert", "ins"], "Insert", "Ins"),
+    ("Home", &["home"], "↖", "Home"),
+    ("End", &["end"], "↘", "End"),
+    ("PageUp", &["pageup"], "⇞", "PgUp"),
+    ("PageDown", &["pagedown"], "⇟", "PgDn"),
+    ("ArrowUp", &["up", "arrowup"], "↑", "Up"),
+    ("ArrowDown", &["down", "arrowdown"], "↓", "Down"),
+    ("ArrowLeft", &["left", "arrowleft"], "←", "Left"),
+    ("ArrowRight", &["right", "arrowright"], "→", "Right"),
+    ("Comma", &[",", "comma"], ",", ","),
+    ("Period", &[".", "period"], ".", "."),
+    ("Slash", &["/", "slash"], "/", "/"),
+    ("Backslash", &["\\", "backslash"], "\\", "\\"),
+    ("Semicolon", &[";", "semicolon"], ";", ";"),
+    ("Quote", &["'", "quote"], "'", "'"),
+    ("BracketLeft", &["[", "bracketleft"], "[", "["),
+    ("BracketRight", &["]", "bracketright"], "]", "]"),
+    ("Backquote", &["`", "backquote"], "`", "`"),
+    ("Minus", &["-", "minus"], "-", "-"),
+    ("Equal", &["=", "equal"], "=", "="),
+];
+
+/// Resolves a key token to its W3C code.
+fn parse_key(token: &str) -> Option<String> {
+    let lower = token.to_ascii_lowercase();
+    if let [c] = lower.as_bytes() {
+        if c.is_ascii_lowercase() {
+            return Some(format!("Key{}", c.to_ascii_uppercase() as char));
+        }
+        if c.is_ascii_digit() {
+            return Some(format!("Digit{}", *c as char));
+        }
+    }
+    if let Some(letter) = lower.strip_prefix("key").filter(|rest| rest.len() == 1) {
+        return parse_key(letter).filter(|code| code.starts_with("Key"));
+    }
+    if let Some(digit) = lower.strip_prefix("digit").filter(|rest| rest.len() == 1) {
+        return parse_key(digit).filter(|code| code.starts_with("Digit"));
+    }
+    if let Some(n) = lower.strip_prefix('f').and_then(|n| n.parse::<u8>().ok()) {
+        return (1..=24).contains(&n).then(|| format!("F{n}"));
+    }
+    NAMED_KEYS
+        .iter()
+        .find(|(code, aliases, ..)| aliases.contains(&lower.as_str()) || code.eq_ignore_ascii_case(&lower))
+        .map(|(code, ..)| code.to_string())
+}
+
+/// Key code and modifiers, e.g. Ctrl+Shift+P.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, uniffi::Object)]
+#[uniffi::export(Display, Eq, Hash)]
+pub struct Accelerator {
+    shift: bool,
+    control: bool,
+    alt: bool,
+    super_key: bool,
+    /// W3C key code, e.g. `KeyP`.
+    code: String,
+}
+
+impl Accelerator {
+    /// Parses `text`, resolving `CmdOrCtrl` to Cmd when `macos` is true.
+    pub(crate) fn parse_for(text: &str, macos: bool) -> Result<Self, TaoError> {
+        let invalid = |reason: &str| TaoError::message(format!("Invalid accelerator {text:?}: {reason}"));
+        // "Ctrl++" names the plus key, which shares the Equal key code.
+        let (modifiers, key) = match text.strip_suffix("++") {
+            Some(modifiers) => (modifiers, "="),
+            None => text.rsplit_once('+').unwrap_or(("", text)),
+        };
+        let mut accelerator = Self {
+            shift: false,
+            control: false,
+            alt: false,
+            super_key: false,
+            code: parse_key(key.trim()).ok_or_else(|| invalid("unknown key"))?,
+        };
+        for token in modifiers.split('+').map(str::trim).filter(|token| !token.is_empty()) {
+            let flag = match token.to_ascii_lowercase().as_str() {
+                "shift" => &mut accelerator.shift,
+                "ctrl" | "control" => &mut accelerator.control,
+                "alt" | "option" => &mut accelerator.alt,
+                "super" | "cmd" | "command" | "meta" => &mut accelerator.super_key,
+                "cmdorctrl" | "cmdorcontrol" | "commandorctrl" | "commandorcontrol" if macos => {
+                    &mut accelerator.super_key
+                }
+                "cmdorctrl" | "cmdorcontrol" | "commandorctrl" | "commandorcontrol" => &mut accelerator.control,
+                _ => return Err(invalid("unknown modifier")),
+            };
+            *flag = true;
+        }
+        Ok(accelerator)
+    }
+
+    /// Display text in the style of `macos` or of Windows and Linux.
+    pub(crate) fn label_for(&self, macos: bool) -> String {
+        let named = NAMED_KEYS.iter().find(|(code, ..)| *code == self.code);
+        let key = match named {
+            Some((_, _, symbol, _)) if macos => symbol.to_string(),
+            Some((_, _, _, label)) => label.to_string(),
+            None => self
+                .code
+                .strip_prefix("Key")
+                .or_else(|| self.code.strip_prefix("Digit"))
+                .unwrap_or(&self.code)
+                .to_string(),
+        };
+        if macos {
+            // Apple's order: Control, Option, Shift, Command.
+            let symbols = [(self.control, "⌃"), (self.alt, "⌥"), (self.shift, "⇧"), (self.super_key, "⌘")];
+            let mut label: String = symbols.iter().filter(|(on, _)| *on).map(|(_, symbol)| *symbol).collect();
+            label.push_str(&key);
+            return label;
+        }
+        let super_name = if cfg!(target_os = "windows") { "Win" } else { "Super" };
+        let names = [(self.control, "Ctrl"), (self.alt, "Alt"), (self.shift, "Shift"), (self.super_key, super_name)];
+        let mut parts: Vec<&str> = names.iter().filter(|(on, _)| *on).map(|(_, name)| *name).collect();
+        parts.push(&key);
+        parts.join("+")
+    }
+
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    pub(crate) fn to_muda(&self) -> Result<muda::accelerator::Accelerator, TaoError> {
+        self.to_string()
+            .parse()
+            .map_err(|e| TaoError::message(format!("Unsupported accelerator {self}: {e}")))
+    }
+
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    pub(crate) fn to_hotkey(&self) -> Result<global_hotkey::hotkey::HotKey, TaoError> {
+        self.to_string()
+            .parse()
+            .map_err(|e| TaoError::message(format!("Unsupported accelerator {self}: {e}")))
+    }
+}
+
+/// Canonical form, e.g. `Control+Shift+KeyP`, which `parse` accepts on every platform.
+impl fmt::Display for Accelerator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let modifiers = [
+            (self.control, "Control"),
+            (self.alt, "Alt"),
+            (self.shift, "Shift"),
+            (self.super_key, "Super"),
+        ];
+        for (_, name) in modifiers.iter().filter(|(on, _)| *on) {
+            write!(f, "{name}+")?;
+        }
+        f.write_str(&self.code)
+    }
+}
+
+#[uniffi::export]
+impl Accelerator {
+    #[uniffi::constructor]
+    pub fn parse(text: String) -> Result<Self, TaoError> {
+        Self::parse_for(&text, cfg!(target_os = "macos"))
+    }
+
+    pub fn modifiers(&self) -> ModifiersState {
+        ModifiersState {
+            shift: self.shift,
+            control: self.control,
+            alt: self.alt,
+            super_key: self.super_key,
+        }
+    }
+
+    /// W3C key code, matching the `KeyCode` names, e.g. `KeyP` or `ArrowUp`.
+    pub fn key_code(&self) -> String {
+        self.code.clone()
+    }
+
+    /// Text for menus and tooltips in the current platform's style, e.g. `⇧⌘P` or `Ctrl+Shift+P`.
+    pub fn label(&self) -> String {
+        self.label_for(cfg!(target_os = "macos"))
+    }
+
+    /// True if a key event with `physical_key` and exactly `modifiers` triggers this accelerator.
+    pub fn matches(&self, physical_key: KeyCode, modifiers: ModifiersState) -> bool {
+        let code = match &physical_key {
+            KeyCode::Space => "Space",
+            KeyCode::KeyA => "KeyA",
+            KeyCode::KeyD => "KeyD",
+            KeyCode::KeyL => "KeyL",
+            KeyCode::KeyM => "KeyM",
+            KeyCode::KeyV => "KeyV",
+            KeyCode::Other { value } => value,
+        };
+        code == self.code && modifiers == self.modifiers()
+    }
+}
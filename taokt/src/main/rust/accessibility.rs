@@ -0,0 +1,161 @@
+//! The user's accessibility display preferences.
+
+use std::{
+    sync::{atomic::AtomicBool, Arc},
+    time::Duration,
+};
+
+use crate::TaoError;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, uniffi::Record)]
+pub struct AccessibilityPreferences {
+    /// High-contrast theme (Windows) or increased contrast (macOS, Linux).
+    pub high_contrast: bool,
+    /// Animations are turned off or should be kept to a minimum.
+    pub reduce_motion: bool,
+    /// Translucent surfaces should be drawn opaque (Windows, macOS).
+    pub reduce_transparency: bool,
+}
+
+/// Remembers `current` and returns it if it differs from the previous reading. The first
+/// reading only sets the baseline.
+pub(crate) fn update(
+    last: &mut Option<AccessibilityPreferences>,
+    current: AccessibilityPreferences,
+) -> Option<AccessibilityPreferences> {
+    let changed = last.is_some_and(|last| last != current);
+    *last = Some(current);
+    changed.then_some(current)
+}
+
+/// Polls the preferences until `stop` is set or `on_change` returns false.
+pub(crate) fn watch(stop: Arc<AtomicBool>, on_change: impl Fn(AccessibilityPreferences) -> bool + Send + 'static) {
+    let mut last = None;
+    crate::poll::poll("taokt-accessibility", POLL_INTERVAL, stop, accessibility_preferences, move |current| {
+        match update(&mut last, current) {
+            Some(preferences) => on_change(preferences),
+            None => true,
+        }
+    });
+}
+
+pub(crate) fn accessibility_preferences() -> Result<AccessibilityPreferences, TaoError> {
+    #[cfg(target_os = "windows")]
+    {
+        return win32::preferences();
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        use objc2::{class, msg_send, runtime::AnyObject};
+        let workspace: *mut AnyObject = unsafe { msg_send![class!(NSWorkspace), sharedWorkspace] };
+        let workspace = unsafe { &*workspace };
+        return Ok(AccessibilityPreferences {
+            high_contrast: unsafe { msg_send![workspace, accessibilityDisplayShouldIncreaseContrast] },
+            reduce_motion: unsafe { msg_send![workspace, accessibilityDisplayShouldReduceMotion] },
+            reduce_transparency: unsafe { msg_send![workspace, accessibilityDisplayShouldReduceTransparency] },
+        });
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    {
+        return portal::preferences();
+    }
+
+    #[allow(unreachable_code)]
+    Err(TaoError::Unsupported)
+}
+
+#[cfg(target_os = "windows")]
+mod win32 {
+    use windows_sys::Win32::{
+        Foundation::ERROR_SUCCESS,
+        System::Registry::{RegGetValueW, HKEY_CURRENT_USER, RRF_RT_REG_DWORD},
+        UI::{
+            Accessibility::{HCF_HIGHCONTRASTON, HIGHCONTRASTW},
+            WindowsAndMessaging::{SystemParametersInfoW, SPI_GETCLIENTAREAANIMATION, SPI_GETHIGHCONTRAST},
+        },
+    };
+
+    use super::AccessibilityPreferences;
+    use crate::TaoError;
+
+    fn wide(value: &str) -> Vec<u16> {
+        value.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    /// "Transparency effects" in Settings > Personalization > Colors.
+    fn transparency_enabled() -> bool {
+        let path = wide("Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize");
+        let name = wide("EnableTransparency");
+        let mut value: u32 = 1;
+        let mut size = std::mem::size_of::<u32>() as u32;
+        let status = unsafe {
+            RegGetValueW(
+                HKEY_CURRENT_USER,
+                path.as_ptr(),
+                name.as_ptr(),
+                RRF_RT_REG_DWORD,
+                std::ptr::null_mut(),
+                &mut value as *mut u32 as *mut _,
+                &mut size,
+            )
+        };
+        status != ERROR_SUCCESS || value != 0
+    }
+
+    pub(super) fn preferences() -> Result<AccessibilityPreferences, TaoError> {
+        let mut contrast = HIGHCONTRASTW {
+            cbSize: std::mem::size_of::<HIGHCONTRASTW>() as u32,
+            dwFlags: 0,
+            lpszDefaultScheme: std::ptr::null_mut(),
+        };
+        let mut animations = 1i32;
+        unsafe {
+            if SystemParametersInfoW(SPI_GETHIGHCONTRAST, contrast.cbSize, &mut contrast as *mut _ as *mut _, 0) == 0
+                || SystemParametersInfoW(SPI_GETCLIENTAREAANIMATION, 0, &mut animations as *mut _ as *mut _, 0) == 0
+            {
+                return Err(std::io::Error::last_os_error().into());
+            }
+        }
+        Ok(AccessibilityPreferences {
+            high_contrast: contrast.dwFlags & HCF_HIGHCONTRASTON != 0,
+            reduce_motion: animations == 0,
+            reduce_transparency: !transparency_enabled(),
+        })
+    }
+}
+
+/// Read through the settings portal, which GNOME and KDE both implement. There is no desktop-wide
+/// transparency setting, so `reduce_transparency` is always false.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+mod portal {
+    use super::AccessibilityPreferences;
+    use crate::{dbus::read_setting, TaoError};
+
+    pub(super) fn preferences() -> Result<AccessibilityPreferences, TaoError> {
+        crate::dbus::session()?;
+        // 0: no preference, 1: higher contrast.
+        let contrast = read_setting("org.freedesktop.appearance", "contrast").and_then(|v| v.get::<u32>());
+        let animations = read_setting("org.gnome.desktop.interface", "enable-animations").and_then(|v| v.get::<bool>());
+        Ok(AccessibilityPreferences {
+            high_contrast: contrast == Some(1),
+            reduce_motion: animations == Some(false),
+            reduce_transparency: false,
+        })
+    }
+}
@@ -0,0 +1,169 @@
+//! Android entry point.
+//!
+//! On Android tao owns the event loop thread: it starts it when the activity is created, through
+//! the JNI functions generated by `tao::android_binding!` below. The activity is
+//! `io.github.kdroidfilter.taokt.TaoActivity` and declares them as external methods (`create`,
+//! `start`, `resume`, `pause`, `stop`, `save`, `destroy`, `memory` and `focus`).
+//!
+//! Call `run_android` before the activity is created, e.g. from `Application.onCreate`; the loop
+//! then runs with that handler. There is a single window, the activity's: its surface exists
+//! between `TaoEvent::Resumed` and `TaoEvent::Suspended`.
+//!
+//! tao does not see back gestures, window insets or rotations, so `TaoActivity` forwards them
+//! with `android_back_pressed` (from an always-enabled `OnBackPressedCallback`),
+//! `android_insets_changed` (from its `OnApplyWindowInsetsListener`) and
+//! `android_configuration_changed`. The activity handles `orientation|screenSize` configuration
+//! changes itself so that rotating does not recreate it. It also implements `showSoftKeyboard`
+//! and `hideSoftKeyboard`, which TaoKt calls through JNI from any thread.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Condvar, Mutex,
+};
+
+use jni::{
+    objects::{GlobalRef, JValue},
+    JNIEnv, JavaVM,
+};
+use ndk::looper::ThreadLooper;
+
+use crate::{
+    LoopMessage, LoopProxy, Orientation, PhysicalInsets, PhysicalSizeU32, RunConfig, TaoError, TaoEventHandler,
+    WindowInsets,
+};
+
+static PENDING: Mutex<Option<(RunConfig, Box<dyn TaoEventHandler>)>> = Mutex::new(None);
+static REGISTERED: Condvar = Condvar::new();
+static PROXY: Mutex<Option<LoopProxy>> = Mutex::new(None);
+static HANDLE_BACK: AtomicBool = AtomicBool::new(false);
+static INSETS: Mutex<Option<WindowInsets>> = Mutex::new(None);
+/// The running `TaoActivity`, replaced when Android recreates it.
+static ACTIVITY: Mutex<Option<(JavaVM, GlobalRef)>> = Mutex::new(None);
+
+impl From<jni::errors::Error> for TaoError {
+    fn from(value: jni::errors::Error) -> Self {
+        TaoError::message(format!("JNI: {value}"))
+    }
+}
+
+/// Calls `method` with `signature` on the activity from the current thread.
+fn call_activity(method: &str, signature: &str, args: &[JValue]) -> Result<(), TaoError> {
+    let activity = ACTIVITY.lock().unwrap();
+    let Some((vm, activity)) = activity.as_ref() else {
+        return Err(TaoError::message("TaoActivity is not running"));
+    };
+    let mut env = vm.attach_current_thread()?;
+    env.call_method(activity, method, signature, args)?;
+    Ok(())
+}
+
+pub(crate) fn install(proxy: &LoopProxy) {
+    *PROXY.lock().unwrap() = Some(proxy.clone());
+    // Insets reported before the loop started.
+    if let Some(insets) = *INSETS.lock().unwrap() {
+        send(LoopMessage::InsetsChanged(insets));
+    }
+}
+
+pub(crate) fn set_handle_back(handle: bool) {
+    HANDLE_BACK.store(handle, Ordering::SeqCst);
+}
+
+pub(crate) fn window_insets() -> WindowInsets {
+    INSETS.lock().unwrap().unwrap_or_default()
+}
+
+pub(crate) fn set_orientation_lock(orientation: Orientation) -> Result<(), TaoError> {
+    // ActivityInfo.SCREEN_ORIENTATION_* values.
+    let requested = match orientation {
+        Orientation::Unlocked => -1,
+        Orientation::Landscape => 0,
+        Orientation::Portrait => 1,
+        Orientation::AnyLandscape => 6,
+        Orientation::AnyPortrait => 7,
+        Orientation::Current => 14,
+    };
+    call_activity("setRequestedOrientation", "(I)V", &[JValue::Int(requested)])
+}
+
+fn send(message: LoopMessage) -> bool {
+    PROXY.lock().unwrap().as_ref().is_some_and(|proxy| proxy.send_event(message).is_ok())
+}
+
+/// Called by `TaoActivity` for the back button or gesture. Returns true if the application
+/// consumed it (see `App::set_handle_back`); otherwise the activity runs the default action.
+#[uniffi::export]
+pub fn android_back_pressed() -> bool {
+    HANDLE_BACK.load(Ordering::SeqCst) && send(LoopMessage::BackPressed)
+}
+
+/// Called by `TaoActivity` whenever the window insets change, in physical pixels.
+#[uniffi::export]
+pub fn android_insets_changed(
+    status_bar: PhysicalInsets,
+    navigation_bar: PhysicalInsets,
+    display_cutout: PhysicalInsets,
+    ime: PhysicalInsets,
+) {
+    let insets = WindowInsets::new(status_bar, navigation_bar, display_cutout, ime);
+    let previous = INSETS.lock().unwrap().replace(insets);
+    if previous == Some(insets) {
+        return;
+    }
+    send(LoopMessage::InsetsChanged(insets));
+    if previous.map_or(0, |previous| previous.ime.bottom) != ime.bottom {
+        send(LoopMessage::SoftKeyboardChanged {
+            visible: ime.bottom > 0,
+            height: ime.bottom,
+        });
+    }
+}
+
+/// `TaoActivity` shows the keyboard for its content view on the UI thread.
+pub(crate) fn show_soft_keyboard() -> Result<(), TaoError> {
+    call_activity("showSoftKeyboard", "()V", &[])
+}
+
+pub(crate) fn hide_soft_keyboard() -> Result<(), TaoError> {
+    call_activity("hideSoftKeyboard", "()V", &[])
+}
+
+/// Called by `TaoActivity.onConfigurationChanged` with the new window size and the display
+/// rotation in degrees clockwise from the natural orientation (0, 90, 180 or 270).
+#[uniffi::export]
+pub fn android_configuration_changed(size: PhysicalSizeU32, rotation_degrees: u32) {
+    send(LoopMessage::ConfigurationChanged { size, rotation_degrees });
+}
+
+/// Runs the event loop with `handler` once `TaoActivity` is created. Returns immediately.
+#[uniffi::export]
+pub fn run_android(config: RunConfig, handler: Box<dyn TaoEventHandler>) -> Result<(), TaoError> {
+    let mut pending = PENDING.lock().unwrap();
+    if pending.is_some() {
+        return Err(TaoError::message("run_android was already called"));
+    }
+    *pending = Some((config, handler));
+    REGISTERED.notify_all();
+    Ok(())
+}
+
+unsafe fn setup(_package: &str, env: JNIEnv, _looper: &ThreadLooper, activity: GlobalRef) {
+    if let Ok(vm) = env.get_java_vm() {
+        *ACTIVITY.lock().unwrap() = Some((vm, activity));
+    }
+}
+
+/// Runs on the event loop thread started by tao.
+fn main() {
+    let mut pending = PENDING.lock().unwrap();
+    let (config, handler) = loop {
+        if let Some(run) = pending.take() {
+            break run;
+        }
+        pending = REGISTERED.wait(pending).unwrap();
+    };
+    drop(pending);
+    crate::run_with_config(config, handler);
+}
+
+tao::android_binding!(io_github_kdroidfilter, taokt, TaoActivity, setup, main, tao);
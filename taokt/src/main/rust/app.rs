@@ -1,44 +1,149 @@
 use std::{
-    cell::Cell,
-    collections::HashMap,
+    cell::{Cell, RefCell},
+    collections::{HashMap, VecDeque},
+    future::Future,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
     sync::{
-        atomic::{AtomicU64, Ordering},
-        Arc, Mutex,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc, Mutex, Weak,
     },
+    task::{Context, Poll, Wake, Waker},
+    time::{Duration, Instant},
 };
 
 use crate::{
-    convert_event, ControlFlow, DeviceEventFilter, TaoError, TaoEvent, TaoUserEvent, Window, WindowBuilder,
+    click::ClickTracker, coalesce::MotionCoalescer, configure_current_thread, convert_event, describe_other,
+    frame::{FrameClock, FrameGroups, FramePacer, TimerResolution},
+    grab::GrabInput,
+    metrics::{event_key, Metrics},
+    monitor::{monitor_key, ScaleTracker},
+    timer::Timers,
+    ActivationPolicy, ControlFlow, DeviceEventFilter, DispatchMetrics, DpiAwareness, EventMask, FrameInfo, IdMap,
+    LockKeysState, LoopMessage, TaoError, TaoEvent, TaoUserEvent, TaoWindowEvent, ThreadConfig, UserEventQueue,
+    UserEventQueueConfig, Window, WindowBuilder,
 };
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+use crate::Menu;
 
 thread_local! {
-    static CURRENT_TARGET: Cell<*const tao::event_loop::EventLoopWindowTarget<TaoUserEvent>> = const { Cell::new(std::ptr::null()) };
+    static CURRENT_TARGET: Cell<*const tao::event_loop::EventLoopWindowTarget<LoopMessage>> = const { Cell::new(std::ptr::null()) };
 }
 
-struct TargetGuard;
+/// Makes a target current for its lifetime, restoring the previous one on drop.
+struct TargetGuard {
+    previous: *const tao::event_loop::EventLoopWindowTarget<LoopMessage>,
+}
 
 impl TargetGuard {
-    fn set(ptr: *const tao::event_loop::EventLoopWindowTarget<TaoUserEvent>) -> Self {
-        CURRENT_TARGET.with(|cell| cell.set(ptr));
-        Self
+    fn set(ptr: *const tao::event_loop::EventLoopWindowTarget<LoopMessage>) -> Self {
+        Self {
+            previous: CURRENT_TARGET.with(|cell| cell.replace(ptr)),
+        }
     }
 }
 
 impl Drop for TargetGuard {
     fn drop(&mut self) {
-        CURRENT_TARGET.with(|cell| cell.set(std::ptr::null()));
+        CURRENT_TARGET.with(|cell| cell.set(self.previous));
+    }
+}
+
+/// Receives raw Win32 messages before tao translates them (Windows only): queued messages, and
+/// messages sent to TaoKt windows such as `WM_POWERBROADCAST` or `WM_DEVICECHANGE`.
+#[uniffi::export(with_foreign)]
+pub trait WindowsMessageHook: Send + Sync {
+    /// Return true to mark the message as handled and skip tao's processing. Handled sent messages
+    /// return 0 to their sender.
+    fn on_message(&self, hwnd: u64, msg: u32, wparam: u64, lparam: i64) -> bool;
+}
+
+impl std::fmt::Debug for dyn WindowsMessageHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("WindowsMessageHook")
     }
 }
 
 #[derive(uniffi::Record, Debug, Clone)]
 pub struct RunConfig {
     pub device_event_filter: DeviceEventFilter,
+    /// Allow creating the event loop off the main thread (Linux and Windows).
+    #[uniffi(default = true)]
+    pub allow_any_thread: bool,
+    /// Control flow in effect until the handler returns something other than `Keep`.
+    #[uniffi(default = None)]
+    pub default_control_flow: Option<ControlFlow>,
+    /// Raw message hook installed on the event loop (Windows only; ignored elsewhere).
+    #[uniffi(default = None)]
+    pub windows_message_hook: Option<Arc<dyn WindowsMessageHook>>,
+    /// Initial event mask; can be changed later with `App::set_event_mask`.
+    #[uniffi(default = None)]
+    pub event_mask: Option<EventMask>,
+    /// Merge `CursorMoved` and `MouseMotion` events delivered within one loop iteration.
+    #[uniffi(default = false)]
+    pub coalesce_pointer_motion: bool,
+    /// Frame rate for `Window::request_redraw_at_next_vblank`; follows the fastest display showing
+    /// a window when unset.
+    #[uniffi(default = None)]
+    pub target_fps: Option<u32>,
+    /// Start collecting dispatch metrics right away; see `App::metrics`.
+    #[uniffi(default = false)]
+    pub collect_metrics: bool,
+    /// Report handler calls taking longer than this with `TaoEvent::HandlerStall`.
+    #[uniffi(default = None)]
+    pub stall_threshold_ms: Option<u64>,
+    /// Let handler panics propagate instead of reporting them as `TaoEvent::HandlerError`.
+    #[uniffi(default = false)]
+    pub fail_fast: bool,
+    /// Bounds the user-event queue fed by `EventLoopProxy::send_event`. `None` keeps it unbounded.
+    #[uniffi(default = None)]
+    pub user_event_queue: Option<UserEventQueueConfig>,
+    /// Name and priority applied to the thread that runs the event loop.
+    #[uniffi(default = None)]
+    pub event_loop_thread: Option<ThreadConfig>,
+    /// Process DPI awareness to request (Windows only). `None` keeps tao's per-monitor v2.
+    ///
+    /// Ignored when the host already set one; check `dpi_awareness()` for the effective value.
+    #[uniffi(default = None)]
+    pub dpi_awareness: Option<DpiAwareness>,
+    /// Activation policy applied before the loop starts (macOS only). `None` keeps `Regular`.
+    #[uniffi(default = None)]
+    pub activation_policy: Option<ActivationPolicy>,
+    /// Force `X11` (Xwayland under a Wayland session) or `Wayland` (Linux only; other values and
+    /// other platforms are ignored). `None` lets GTK choose, honoring `GDK_BACKEND`.
+    #[uniffi(default = None)]
+    pub display_backend: Option<crate::DisplayBackend>,
+    /// Raise the system timer resolution to 1 ms while waiting with `WaitUntil`, for steadier frame
+    /// pacing (Windows only; ignored elsewhere). Costs some battery while active.
+    #[uniffi(default = false)]
+    pub high_resolution_timers: bool,
+    /// Pace `Window::request_redraw_at_next_vblank` per monitor: the windows on one monitor redraw
+    /// in a single batch at its refresh rate, instead of all windows sharing one rate. Cuts
+    /// wake-ups for apps with many animated windows spread over displays with different rates.
+    #[uniffi(default = false)]
+    pub vsync_groups: bool,
 }
 
 impl Default for RunConfig {
     fn default() -> Self {
         Self {
             device_event_filter: DeviceEventFilter::Unfocused,
+            allow_any_thread: true,
+            default_control_flow: None,
+            windows_message_hook: None,
+            event_mask: None,
+            coalesce_pointer_motion: false,
+            target_fps: None,
+            collect_metrics: false,
+            stall_threshold_ms: None,
+            fail_fast: false,
+            user_event_queue: None,
+            event_loop_thread: None,
+            dpi_awareness: None,
+            activation_policy: None,
+            display_backend: None,
+            high_resolution_timers: false,
+            vsync_groups: false,
         }
     }
 }
@@ -51,31 +156,779 @@ pub trait TaoEventHandler {
 #[uniffi::export(callback_interface)]
 pub trait TaoRunReturnHandler {
     fn handle_event(&self, event: TaoEvent, app: Arc<App>) -> ControlFlow;
-    fn render(&self);
+    fn render(&self, frame: FrameInfo);
     fn should_quit(&self) -> bool;
 }
 
+/// Like `TaoEventHandler`, but window and redraw events come with their `Window`.
+///
+/// `window` is `None` for other events and for windows that were already dropped.
+#[uniffi::export(callback_interface)]
+pub trait TaoWindowEventHandler {
+    fn handle_event(&self, event: TaoEvent, window: Option<Arc<Window>>, app: Arc<App>) -> ControlFlow;
+}
+
+/// Receives events in batches: everything from `NewEvents` up to and including
+/// `MainEventsCleared` arrives in one call. Events outside that span (redraws,
+/// `LoopDestroyed`) are delivered as soon as they occur.
+#[uniffi::export(callback_interface)]
+pub trait TaoBatchEventHandler {
+    fn handle_events(&self, events: Vec<TaoEvent>, app: Arc<App>) -> ControlFlow;
+}
+
+/// Cleanup registered with `App::on_exit`.
+#[uniffi::export(callback_interface)]
+pub trait ExitHook: Send + Sync {
+    fn on_exit(&self);
+}
+
+/// Work queued from another thread with `App::run_on_event_loop`.
+#[uniffi::export(callback_interface)]
+pub trait MainThreadTask: Send + Sync {
+    fn run(&self);
+}
+
+/// Observes events next to the main handler, e.g. from a library module.
+#[uniffi::export(with_foreign)]
+pub trait TaoEventListener: Send + Sync {
+    fn on_event(&self, event: TaoEvent);
+}
+
+struct Listener {
+    id: u64,
+    window_id: Option<u64>,
+    listener: Arc<dyn TaoEventListener>,
+}
+
+impl Listener {
+    fn accepts(&self, event: &TaoEvent) -> bool {
+        self.window_id.is_none() || self.window_id == event.window_id()
+    }
+}
+
+/// Event handler that may suspend, e.g. to await a confirmation dialog on `CloseRequested`.
+///
+/// The loop keeps running while a call is suspended, but further events are held back
+/// and delivered in order once it completes, so calls never overlap.
+#[uniffi::export(with_foreign)]
+#[async_trait::async_trait]
+pub trait TaoAsyncEventHandler: Send + Sync {
+    async fn handle_event(&self, event: TaoEvent, app: Arc<App>) -> ControlFlow;
+}
+
+/// Carries messages to the event loop: tao's proxy, or the queue of a `TestEventLoop`.
+#[derive(Clone)]
+pub(crate) enum LoopProxy {
+    Native(tao::event_loop::EventLoopProxy<LoopMessage>),
+    #[cfg(feature = "headless")]
+    Headless(Arc<Mutex<VecDeque<LoopMessage>>>),
+}
+
+impl LoopProxy {
+    pub(crate) fn send_event(&self, message: LoopMessage) -> Result<(), TaoError> {
+        match self {
+            LoopProxy::Native(proxy) => proxy
+                .send_event(message)
+                .map_err(|e| TaoError::message(format!("{e}"))),
+            #[cfg(feature = "headless")]
+            LoopProxy::Headless(queue) => {
+                queue.lock().unwrap().push_back(message);
+                Ok(())
+            }
+        }
+    }
+}
+
 #[derive(uniffi::Object)]
 pub struct EventLoopProxy {
-    inner: tao::event_loop::EventLoopProxy<TaoUserEvent>,
+    inner: LoopProxy,
+    user_events: Arc<UserEventQueue>,
 }
 
 #[uniffi::export]
 impl EventLoopProxy {
+    /// Queues a user event for the event loop.
+    ///
+    /// When the queue is bounded and full, the configured `UserEventOverflow` policy applies.
     pub fn send_event(&self, event: TaoUserEvent) -> Result<(), TaoError> {
-        self.inner.send_event(event).map_err(|e| TaoError::message(format!("{e}")))?;
+        if self.user_events.push(event)? {
+            self.inner.send_event(LoopMessage::UserEvents)?;
+        }
         Ok(())
     }
+
+    /// Number of user events dropped or rejected because the queue was full.
+    pub fn dropped_event_count(&self) -> u64 {
+        self.user_events.dropped()
+    }
+
+    /// Delivers `urls` to the event loop as a `TaoEvent::Opened`, e.g. deep links
+    /// received by a single-instance mechanism.
+    pub fn open_urls(&self, urls: Vec<String>) -> Result<(), TaoError> {
+        self.inner.send_event(LoopMessage::OpenUrls(urls))
+    }
+
+    /// Delivers a jump list item id to the event loop as a `TaoEvent::JumpListTask`, e.g. one
+    /// received by a single-instance mechanism from a process started by the jump list.
+    pub fn activate_jump_list_task(&self, id: String) -> Result<(), TaoError> {
+        self.inner.send_event(LoopMessage::JumpListTask(id))
+    }
+
+    /// Asks the event loop to exit as soon as it processes this request. Callable from any thread.
+    pub fn request_exit(&self) -> Result<(), TaoError> {
+        self.inner.send_event(LoopMessage::Exit)
+    }
+}
+
+/// Window bookkeeping that runs after the handler has seen a native window event.
+enum WindowFollowUp {
+    Resized,
+    DisplayChanged,
+    Cursor(GrabInput),
+    Destroyed,
+    /// Lock keys may have toggled without a `ModifiersChanged` because a key was pressed.
+    LockKeys,
+    /// Lock keys may have been toggled in another window, and tao reset the cursor grab.
+    Focused,
+}
+
+impl WindowFollowUp {
+    fn of(event: &tao::event::WindowEvent<'_>) -> Option<Self> {
+        use tao::event::WindowEvent as NativeWindowEvent;
+        match event {
+            NativeWindowEvent::Resized(_) => Some(Self::Resized),
+            NativeWindowEvent::ScaleFactorChanged { .. } | NativeWindowEvent::Moved(_) => Some(Self::DisplayChanged),
+            NativeWindowEvent::CursorMoved { position, .. } => Some(Self::Cursor(GrabInput::Moved((*position).into()))),
+            NativeWindowEvent::CursorLeft { .. } => Some(Self::Cursor(GrabInput::Left)),
+            NativeWindowEvent::Destroyed => Some(Self::Destroyed),
+            NativeWindowEvent::KeyboardInput { .. } => Some(Self::LockKeys),
+            NativeWindowEvent::Focused(true) => Some(Self::Focused),
+            _ => None,
+        }
+    }
 }
 
 #[derive(uniffi::Object)]
 pub struct App {
-    proxy: tao::event_loop::EventLoopProxy<TaoUserEvent>,
+    proxy: LoopProxy,
+    user_events: Arc<UserEventQueue>,
     next_window_id: AtomicU64,
     window_ids: Mutex<HashMap<tao::window::WindowId, u64>>,
+    next_device_id: AtomicU64,
+    device_ids: Mutex<HashMap<tao::event::DeviceId, u64>>,
+    windows: Mutex<HashMap<u64, Weak<Window>>>,
+    clicks: Mutex<ClickTracker>,
+    /// Last lock key state reported, with `ModifiersChanged` or `LockKeysChanged`.
+    lock_keys: Mutex<LockKeysState>,
+    /// Kept alive while set as the application menu.
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    app_menu: Mutex<Option<Arc<Menu>>>,
+    monitor_scales: Mutex<ScaleTracker>,
+    /// Stops the watcher started by `set_idle_threshold`.
+    idle_watch: Mutex<Option<Arc<AtomicBool>>>,
+    /// Stops the watcher started by `set_watch_accessibility`.
+    accessibility_watch: Mutex<Option<Arc<AtomicBool>>>,
+    /// Set with `set_application_id`, for windows whose builder has none.
+    application_id: Mutex<Option<String>>,
+    /// Set once an exit was requested; handler control flows no longer override it.
+    exit_requested: AtomicBool,
+    /// Whether `LoopDestroyed` ends the application. `run_return_loop` runs the loop once per
+    /// frame and runs the exit hooks itself.
+    exit_hooks_on_destroy: AtomicBool,
+    default_control_flow: Option<ControlFlow>,
+    event_mask: Mutex<EventMask>,
+    coalesce_pointer_motion: AtomicBool,
+    motion: Mutex<MotionCoalescer>,
+    next_listener_id: AtomicU64,
+    listeners: Mutex<Vec<Listener>>,
+    tasks: Mutex<Vec<Box<dyn MainThreadTask>>>,
+    timers: Mutex<Timers>,
+    /// Deadline last written into the control flow for timers and frames.
+    timer_wait: Mutex<Option<Instant>>,
+    frames: Mutex<FramePacer>,
+    /// Per-monitor pacing that replaces `frames` for windows with `RunConfig::vsync_groups`.
+    frame_groups: Option<Mutex<FrameGroups>>,
+    timer_resolution: Mutex<TimerResolution>,
+    metrics: Mutex<Metrics>,
+    /// Zero disables the stall watchdog.
+    stall_threshold_ms: AtomicU64,
+    fail_fast: bool,
+    exit_hooks: Mutex<Vec<Box<dyn ExitHook>>>,
 }
 
 impl App {
+    fn new(event_loop: &tao::event_loop::EventLoop<LoopMessage>, config: &RunConfig) -> Arc<Self> {
+        #[cfg(not(any(target_os = "android", target_os = "ios")))]
+        {
+            let menu_proxy = Mutex::new(event_loop.create_proxy());
+            muda::MenuEvent::set_event_handler(Some(move |event: muda::MenuEvent| {
+                let _ = menu_proxy.lock().unwrap().send_event(LoopMessage::Menu(event.id.0));
+            }));
+            let shortcut_proxy = Mutex::new(event_loop.create_proxy());
+            global_hotkey::GlobalHotKeyEvent::set_event_handler(Some(move |event: global_hotkey::GlobalHotKeyEvent| {
+                let message = LoopMessage::GlobalShortcut(event.id, event.state.into());
+                let _ = shortcut_proxy.lock().unwrap().send_event(message);
+            }));
+        }
+        let proxy = LoopProxy::Native(event_loop.create_proxy());
+        crate::session::watch(&proxy);
+        crate::quit::install(&proxy);
+        crate::color_scheme::watch(&proxy);
+        #[cfg(target_os = "android")]
+        crate::android::install(&proxy);
+        #[cfg(target_os = "ios")]
+        crate::ios::install(&proxy);
+        Self::with_proxy(proxy, config)
+    }
+
+    pub(crate) fn with_proxy(proxy: LoopProxy, config: &RunConfig) -> Arc<Self> {
+        Arc::new(Self {
+            proxy,
+            user_events: Arc::new(UserEventQueue::new(config.user_event_queue)),
+            next_window_id: AtomicU64::new(1),
+            window_ids: Mutex::new(HashMap::new()),
+            next_device_id: AtomicU64::new(1),
+            device_ids: Mutex::new(HashMap::new()),
+            windows: Mutex::new(HashMap::new()),
+            clicks: Mutex::new(ClickTracker::from_platform()),
+            lock_keys: Mutex::new(LockKeysState::default()),
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            app_menu: Mutex::new(None),
+            monitor_scales: Mutex::new(ScaleTracker::default()),
+            idle_watch: Mutex::new(None),
+            accessibility_watch: Mutex::new(None),
+            application_id: Mutex::new(None),
+            exit_requested: AtomicBool::new(false),
+            exit_hooks_on_destroy: AtomicBool::new(true),
+            default_control_flow: config.default_control_flow,
+            event_mask: Mutex::new(config.event_mask.unwrap_or_default()),
+            coalesce_pointer_motion: AtomicBool::new(config.coalesce_pointer_motion),
+            motion: Mutex::new(MotionCoalescer::default()),
+            next_listener_id: AtomicU64::new(1),
+            listeners: Mutex::new(Vec::new()),
+            tasks: Mutex::new(Vec::new()),
+            timers: Mutex::new(Timers::new()),
+            timer_wait: Mutex::new(None),
+            frames: Mutex::new(FramePacer::new(config.target_fps)),
+            frame_groups: config.vsync_groups.then(|| Mutex::new(FrameGroups::new(config.target_fps))),
+            timer_resolution: Mutex::new(TimerResolution::new(config.high_resolution_timers)),
+            metrics: Mutex::new(Metrics::new(config.collect_metrics)),
+            stall_threshold_ms: AtomicU64::new(config.stall_threshold_ms.unwrap_or(0)),
+            fail_fast: config.fail_fast,
+            exit_hooks: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Converts a native event and hands the result to `deliver`, applying the returned control flow.
+    ///
+    /// A single wake-up message from the proxy expands into every queued user event.
+    pub(crate) fn dispatch(
+        &self,
+        event: tao::event::Event<'_, LoopMessage>,
+        control_flow: &mut tao::event_loop::ControlFlow,
+        mut deliver: impl FnMut(TaoEvent) -> ControlFlow,
+    ) {
+        if let tao::event::Event::NewEvents(tao::event::StartCause::Init) = event {
+            if let Some(cf) = self.default_control_flow.and_then(ControlFlow::to_tao) {
+                *control_flow = cf;
+            }
+        }
+
+        let coalesce = self.coalesce_pointer_motion.load(Ordering::Relaxed);
+        let measure = self.metrics.lock().unwrap().enabled();
+        let stall_threshold = match self.stall_threshold_ms.load(Ordering::Relaxed) {
+            0 => None,
+            ms => Some(Duration::from_millis(ms)),
+        };
+        let mut send = |event: TaoEvent| {
+            let (ready, merged) = {
+                let mut motion = self.motion.lock().unwrap();
+                let ready = motion.push(event, coalesce);
+                (ready, motion.take_merged())
+            };
+            if measure && merged > 0 {
+                self.metrics.lock().unwrap().record_coalesced(merged);
+            }
+            for event in ready {
+                for message in self.notify_listeners(&event) {
+                    if let Some(cf) = self.contain(&mut deliver, TaoEvent::HandlerError { message }).to_tao() {
+                        *control_flow = cf;
+                    }
+                }
+                let key = (measure || stall_threshold.is_some()).then(|| event_key(&event));
+                let started = Instant::now();
+                let cf = self.contain(&mut deliver, event);
+                let elapsed = started.elapsed();
+                if let Some(cf) = cf.to_tao() {
+                    *control_flow = cf;
+                }
+
+                let Some(key) = key else {
+                    continue;
+                };
+                if measure {
+                    self.metrics.lock().unwrap().record_delivery(key, elapsed);
+                }
+                if stall_threshold.is_some_and(|threshold| elapsed > threshold) {
+                    let stall = TaoEvent::HandlerStall {
+                        event_kind: describe_other(key).unwrap_or_default(),
+                        duration_ms: elapsed.as_millis() as u64,
+                    };
+                    if let Some(cf) = self.contain(&mut deliver, stall).to_tao() {
+                        *control_flow = cf;
+                    }
+                }
+            }
+        };
+
+        match event {
+            tao::event::Event::UserEvent(LoopMessage::UserEvents) => {
+                for event in self.user_events.drain() {
+                    send(TaoEvent::UserEvent { event });
+                }
+            }
+            tao::event::Event::UserEvent(LoopMessage::OpenUrls(urls)) => {
+                send(TaoEvent::Opened { urls });
+            }
+            tao::event::Event::UserEvent(LoopMessage::Menu(id)) => {
+                send(TaoEvent::MenuEvent { id });
+            }
+            tao::event::Event::UserEvent(LoopMessage::JumpListTask(id)) => {
+                send(TaoEvent::JumpListTask { id });
+            }
+            tao::event::Event::UserEvent(LoopMessage::GlobalShortcut(id, state)) => {
+                send(TaoEvent::GlobalShortcut { id, state });
+            }
+            tao::event::Event::UserEvent(LoopMessage::NotificationActivated { id, action, window_id }) => {
+                send(TaoEvent::NotificationActivated { id, action, window_id });
+            }
+            tao::event::Event::UserEvent(LoopMessage::IdleChanged { idle, idle_seconds }) => {
+                send(TaoEvent::IdleChanged { idle, idle_seconds });
+            }
+            tao::event::Event::UserEvent(LoopMessage::AccessibilityChanged(preferences)) => {
+                send(TaoEvent::AccessibilityChanged { preferences });
+            }
+            tao::event::Event::UserEvent(LoopMessage::SessionEnding(reason)) => {
+                send(TaoEvent::SessionEnding { reason });
+            }
+            tao::event::Event::UserEvent(LoopMessage::SessionBlocksChanged) => crate::session::apply_blocks(),
+            tao::event::Event::UserEvent(LoopMessage::QuitRequested) => send(TaoEvent::QuitRequested),
+            tao::event::Event::UserEvent(LoopMessage::BackPressed) => send(TaoEvent::BackPressed),
+            tao::event::Event::UserEvent(LoopMessage::InsetsChanged(insets)) => {
+                send(TaoEvent::InsetsChanged { insets });
+            }
+            tao::event::Event::UserEvent(LoopMessage::ConfigurationChanged { size, rotation_degrees }) => {
+                send(TaoEvent::ConfigurationChanged { size, rotation_degrees });
+            }
+            tao::event::Event::UserEvent(LoopMessage::SoftKeyboardChanged { visible, height }) => {
+                send(TaoEvent::SoftKeyboardChanged { visible, height });
+            }
+            tao::event::Event::UserEvent(LoopMessage::IpcMessage(data)) => {
+                match crate::deep_link::decode_urls_message(&data) {
+                    Some(urls) => send(TaoEvent::Opened { urls }),
+                    None => send(TaoEvent::IpcMessage { data }),
+                }
+            }
+            tao::event::Event::UserEvent(LoopMessage::DisplayLinkFrame {
+                window_id,
+                timestamp_ns,
+                target_timestamp_ns,
+            }) => {
+                #[cfg(target_os = "macos")]
+                if let Some(window) = self.window(window_id) {
+                    if window.reports_display_link_frames() {
+                        send(TaoEvent::DisplayLinkFrame {
+                            window_id,
+                            timestamp_ns,
+                            target_timestamp_ns,
+                        });
+                    }
+                    window.display_link_frame();
+                }
+                #[cfg(not(target_os = "macos"))]
+                let _ = (window_id, timestamp_ns, target_timestamp_ns);
+            }
+            tao::event::Event::UserEvent(LoopMessage::DisplaySettingsChanged) => {
+                for window in self.live_windows() {
+                    self.window_follow_up(window.id(), WindowFollowUp::DisplayChanged, &mut send);
+                }
+            }
+            tao::event::Event::UserEvent(LoopMessage::ColorSchemeChanged(theme)) => {
+                for window in self.live_windows().into_iter().filter(|window| window.follows_system_theme()) {
+                    send(TaoEvent::WindowEvent {
+                        window_id: window.id(),
+                        event: TaoWindowEvent::ThemeChanged { theme },
+                    });
+                }
+            }
+            tao::event::Event::UserEvent(LoopMessage::Exit) => {
+                self.exit_requested.store(true, Ordering::SeqCst);
+            }
+            tao::event::Event::UserEvent(LoopMessage::Resume) => {}
+            tao::event::Event::UserEvent(LoopMessage::Inject(event)) => send(*event),
+            tao::event::Event::UserEvent(LoopMessage::WindowCommands) => {
+                for window in self.live_windows() {
+                    window.drain_commands();
+                }
+            }
+            tao::event::Event::UserEvent(LoopMessage::RunTasks) => {
+                let tasks = std::mem::take(&mut *self.tasks.lock().unwrap());
+                for task in tasks {
+                    if let Err(message) = self.catch(|| task.run()) {
+                        send(TaoEvent::HandlerError { message });
+                    }
+                }
+            }
+            event => {
+                let follow_up = match &event {
+                    tao::event::Event::WindowEvent { window_id, event, .. } => {
+                        WindowFollowUp::of(event).map(|f| (self.map_window_id(*window_id), f))
+                    }
+                    _ => None,
+                };
+                let mut converted = convert_event(event, self);
+                if let TaoEvent::WindowEvent { window_id, event } = &mut converted {
+                    self.clicks.lock().unwrap().track(*window_id, event, Instant::now());
+                    if let TaoWindowEvent::ModifiersChanged { lock_keys, .. } = event {
+                        *self.lock_keys.lock().unwrap() = *lock_keys;
+                    }
+                }
+                if let TaoEvent::RedrawRequested { window_id, dirty_rects } = &mut converted {
+                    if let Some(window) = self.window(*window_id) {
+                        *dirty_rects = window.take_dirty_rects();
+                    }
+                }
+                if matches!(converted, TaoEvent::MainEventsCleared) {
+                    // Pen and gesture input gathered from native events during this iteration.
+                    for window in self.live_windows() {
+                        for event in window.take_native_events() {
+                            send(TaoEvent::WindowEvent {
+                                window_id: window.id(),
+                                event,
+                            });
+                        }
+                    }
+                }
+                let new_events = matches!(converted, TaoEvent::NewEvents { .. });
+                let destroyed = matches!(converted, TaoEvent::LoopDestroyed);
+                // tao guesses the theme from the GTK theme name; while the portal states one, its
+                // `ColorSchemeChanged` is the only source of theme changes.
+                let superseded = crate::color_scheme::system_theme().is_some()
+                    && matches!(
+                        converted,
+                        TaoEvent::WindowEvent {
+                            event: TaoWindowEvent::ThemeChanged { .. },
+                            ..
+                        }
+                    );
+                let suppressed = self.event_mask.lock().unwrap().suppresses(&converted);
+                if !suppressed && !superseded {
+                    send(converted);
+                } else if suppressed && measure {
+                    self.metrics.lock().unwrap().record_suppressed();
+                }
+                if new_events {
+                    let now = Instant::now();
+                    let due = self.timers.lock().unwrap().take_due(now);
+                    for id in due {
+                        send(TaoEvent::UserEvent {
+                            event: TaoUserEvent::Timer { id },
+                        });
+                    }
+                    // Frame callbacks that fired release the next paced frames.
+                    for window in self.live_windows() {
+                        for timestamp_ms in window.take_presented_frames() {
+                            send(TaoEvent::FramePresented {
+                                window_id: window.id(),
+                                timestamp_ms,
+                            });
+                        }
+                    }
+                    self.start_frame(now);
+                    self.check_monitor_scales(now, &mut send);
+                }
+                if destroyed && self.exit_hooks_on_destroy.load(Ordering::Relaxed) {
+                    self.run_exit_hooks();
+                }
+
+                if let Some((window_id, follow_up)) = follow_up {
+                    self.window_follow_up(window_id, follow_up, &mut send);
+                }
+            }
+        }
+
+        self.enforce_exit(control_flow);
+    }
+
+    /// Calls into application code, turning a panic (including a foreign exception surfaced by
+    /// uniffi) into its message unless fail-fast is configured.
+    fn catch<R>(&self, call: impl FnOnce() -> R) -> Result<R, String> {
+        let payload = match panic::catch_unwind(AssertUnwindSafe(call)) {
+            Ok(result) => return Ok(result),
+            Err(payload) => payload,
+        };
+        if self.fail_fast {
+            panic::resume_unwind(payload);
+        }
+        Err(payload
+            .downcast_ref::<&str>()
+            .map(|s| s.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "event handler panicked".to_string()))
+    }
+
+    /// Delivers `event`, reporting a failure of the handler as `TaoEvent::HandlerError`.
+    fn contain(&self, deliver: &mut impl FnMut(TaoEvent) -> ControlFlow, event: TaoEvent) -> ControlFlow {
+        match self.catch(|| deliver(event)) {
+            Ok(cf) => cf,
+            // A handler that also fails on the error report is not retried.
+            Err(message) => {
+                panic::catch_unwind(AssertUnwindSafe(|| deliver(TaoEvent::HandlerError { message })))
+                    .unwrap_or(ControlFlow::Keep)
+            }
+        }
+    }
+
+    /// Runs the registered exit hooks in registration order; later calls find none left.
+    pub(crate) fn run_exit_hooks(&self) {
+        let hooks = std::mem::take(&mut *self.exit_hooks.lock().unwrap());
+        for hook in hooks {
+            // One failing hook must not keep the others from running.
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| hook.on_exit()));
+        }
+    }
+
+    /// Passes `event` to the listeners and returns the messages of those that failed.
+    fn notify_listeners(&self, event: &TaoEvent) -> Vec<String> {
+        let listeners: Vec<_> = {
+            let listeners = self.listeners.lock().unwrap();
+            if listeners.is_empty() {
+                return Vec::new();
+            }
+            listeners
+                .iter()
+                .filter(|l| l.accepts(event))
+                .map(|l| l.listener.clone())
+                .collect()
+        };
+        listeners
+            .into_iter()
+            .filter_map(|listener| self.catch(|| listener.on_event(event.clone())).err())
+            .collect()
+    }
+
+    /// Applies a control flow returned outside of `dispatch`, e.g. by a batch handler.
+    fn apply_control_flow(&self, cf: ControlFlow, control_flow: &mut tao::event_loop::ControlFlow) {
+        if let Some(cf) = cf.to_tao() {
+            *control_flow = cf;
+        }
+        self.enforce_exit(control_flow);
+    }
+
+    fn enforce_exit(&self, control_flow: &mut tao::event_loop::ControlFlow) {
+        if self.exit_requested.load(Ordering::SeqCst)
+            && !matches!(control_flow, tao::event_loop::ControlFlow::ExitWithCode(_))
+        {
+            *control_flow = tao::event_loop::ControlFlow::Exit;
+        }
+        self.schedule_wakeups(control_flow);
+        self.timer_resolution.lock().unwrap().update(control_flow);
+    }
+
+    /// Paces frames at the highest refresh rate among the displays showing a window, so that a
+    /// window opened or moved onto a slower display does not slow down the others.
+    fn follow_refresh_rates(&self) {
+        let rate = self.live_windows().iter().map(|window| window.refresh_rate_millihertz()).max();
+        self.frames.lock().unwrap().set_refresh_rate(rate.unwrap_or(0));
+    }
+
+    /// Issues paced redraws from vblank signals, and from the frame timer once the next frame slot
+    /// is reached.
+    fn start_frame(&self, now: Instant) {
+        let windows = self.live_windows();
+        for window in &windows {
+            window.start_vblank_frame(now);
+        }
+        let windows: Vec<_> = windows.into_iter().filter(|w| w.frame_paced_by_timer()).collect();
+        if windows.is_empty() {
+            return;
+        }
+        if let Some(groups) = &self.frame_groups {
+            let monitors: Vec<_> = windows.iter().map(|window| window.vsync_group()).collect();
+            let due = groups.lock().unwrap().start_due(now, &monitors);
+            for (window, (monitor, _)) in windows.iter().zip(&monitors) {
+                if due.contains(monitor) {
+                    window.start_frame();
+                }
+            }
+            return;
+        }
+        let mut frames = self.frames.lock().unwrap();
+        if !frames.is_due(now) {
+            return;
+        }
+        frames.start_frame(now);
+        if let Some(vblank) = crate::frame::last_vblank(now) {
+            frames.align(now, vblank);
+        }
+        drop(frames);
+        for window in windows {
+            window.start_frame();
+        }
+    }
+
+    /// Shortens `Wait`/`WaitUntil` so the loop wakes up for the next timer, frame or monitor scale
+    /// check.
+    fn schedule_wakeups(&self, control_flow: &mut tao::event_loop::ControlFlow) {
+        use tao::event_loop::ControlFlow as Native;
+
+        let mut timer_wait = self.timer_wait.lock().unwrap();
+        // Undo our own previous adjustment unless the handler replaced it.
+        if let Some(deadline) = timer_wait.take() {
+            if *control_flow == Native::WaitUntil(deadline) {
+                *control_flow = Native::Wait;
+            }
+        }
+
+        let mut next = self.timers.lock().unwrap().next_deadline();
+        if let Some(check) = self.monitor_scales.lock().unwrap().next_check() {
+            next = Some(next.map_or(check, |next| next.min(check)));
+        }
+        let now = Instant::now();
+        let paced: Vec<_> = self.live_windows().into_iter().filter(|w| w.frame_paced_by_timer()).collect();
+        let frame = match &self.frame_groups {
+            _ if paced.is_empty() => None,
+            Some(groups) => {
+                let monitors: Vec<_> = paced.iter().map(|window| window.vsync_group().0).collect();
+                groups.lock().unwrap().next_frame(now, monitors.iter().map(String::as_str))
+            }
+            None => Some(self.frames.lock().unwrap().next_frame(now)),
+        };
+        let vblank_frame = self.live_windows().iter().filter_map(|window| window.next_vblank_frame(now)).min();
+        for frame in [frame, vblank_frame].into_iter().flatten() {
+            next = Some(next.map_or(frame, |next| next.min(frame)));
+        }
+        let Some(next) = next else {
+            return;
+        };
+        let adjusted = match *control_flow {
+            Native::Wait => true,
+            Native::WaitUntil(deadline) => next < deadline,
+            _ => false,
+        };
+        if adjusted {
+            *control_flow = Native::WaitUntil(next);
+            *timer_wait = Some(next);
+        }
+    }
+
+    fn window_follow_up(
+        &self,
+        window_id: u64,
+        follow_up: WindowFollowUp,
+        send: &mut impl FnMut(TaoEvent),
+    ) {
+        let Some(window) = self.window(window_id) else {
+            return;
+        };
+        match follow_up {
+            WindowFollowUp::Cursor(input) => {
+                window.enforce_cursor_grab(input);
+                return;
+            }
+            WindowFollowUp::Destroyed => {
+                window.destroyed();
+                self.follow_refresh_rates();
+                return;
+            }
+            WindowFollowUp::LockKeys | WindowFollowUp::Focused => {
+                if matches!(follow_up, WindowFollowUp::Focused) {
+                    window.restore_cursor_grab();
+                }
+                let lock_keys = crate::lock_keys_state();
+                if std::mem::replace(&mut *self.lock_keys.lock().unwrap(), lock_keys) != lock_keys {
+                    send(TaoEvent::WindowEvent {
+                        window_id,
+                        event: TaoWindowEvent::LockKeysChanged { lock_keys },
+                    });
+                }
+                return;
+            }
+            _ => {}
+        }
+        // Wayland reports scale changes on its own queue; check whenever the surface changes.
+        if let Some(event) = window.take_fractional_scale_change() {
+            send(TaoEvent::WindowEvent { window_id, event });
+        }
+        match follow_up {
+            WindowFollowUp::Resized => {
+                window.restore_cursor_grab();
+                window.invalidate_all();
+                if let Some(event) = window.take_maximized_change() {
+                    send(TaoEvent::WindowEvent { window_id, event });
+                }
+            }
+            WindowFollowUp::DisplayChanged => {
+                window.restore_cursor_grab();
+                window.refresh_icon_set();
+                if let Some(event) = window.take_color_info_change() {
+                    send(TaoEvent::WindowEvent { window_id, event });
+                }
+                if let Some(event) = window.take_refresh_rate_change() {
+                    self.follow_refresh_rates();
+                    send(TaoEvent::WindowEvent { window_id, event });
+                }
+            }
+            WindowFollowUp::Cursor(_)
+            | WindowFollowUp::Destroyed
+            | WindowFollowUp::LockKeys
+            | WindowFollowUp::Focused => {}
+        }
+    }
+
+    /// Sends `MonitorScaleChanged` for monitors whose scale factor changed since the last check,
+    /// checking at most once per `ScaleTracker` interval.
+    fn check_monitor_scales(&self, now: Instant, send: &mut impl FnMut(TaoEvent)) {
+        let mut tracker = self.monitor_scales.lock().unwrap();
+        if !tracker.is_due(now) {
+            return;
+        }
+        let Ok(monitors) = self.with_target(|target| {
+            target
+                .available_monitors()
+                .map(|monitor| (monitor_key(&monitor), monitor.scale_factor(), monitor))
+                .collect::<Vec<_>>()
+        }) else {
+            return;
+        };
+        let changed = tracker.update(now, monitors);
+        drop(tracker);
+        for (monitor, previous_scale_factor) in changed {
+            send(TaoEvent::MonitorScaleChanged {
+                monitor_name: monitor.name(),
+                position: monitor.position().into(),
+                scale_factor: monitor.scale_factor(),
+                previous_scale_factor,
+            });
+        }
+    }
+
+    /// Windows created through this app that are still alive.
+    pub(crate) fn live_windows(&self) -> Vec<Arc<Window>> {
+        let mut windows = self.windows.lock().unwrap();
+        windows.retain(|_, window| window.strong_count() > 0);
+        windows.values().filter_map(Weak::upgrade).collect()
+    }
+
+    pub(crate) fn window(&self, id: u64) -> Option<Arc<Window>> {
+        let mut windows = self.windows.lock().unwrap();
+        let window = windows.get(&id).and_then(Weak::upgrade);
+        if window.is_none() {
+            windows.remove(&id);
+        }
+        window
+    }
+
     fn map_window_id(&self, id: tao::window::WindowId) -> u64 {
         let mut map = self.window_ids.lock().unwrap();
         if let Some(existing) = map.get(&id) {
@@ -86,9 +939,19 @@ impl App {
         next
     }
 
+    fn map_device_id(&self, id: tao::event::DeviceId) -> u64 {
+        let mut map = self.device_ids.lock().unwrap();
+        if let Some(existing) = map.get(&id) {
+            return *existing;
+        }
+        let next = self.next_device_id.fetch_add(1, Ordering::Relaxed);
+        map.insert(id, next);
+        next
+    }
+
     fn with_target<R>(
         &self,
-        f: impl FnOnce(&tao::event_loop::EventLoopWindowTarget<TaoUserEvent>) -> R,
+        f: impl FnOnce(&tao::event_loop::EventLoopWindowTarget<LoopMessage>) -> R,
     ) -> Result<R, TaoError> {
         CURRENT_TARGET.with(|cell| {
             let ptr = cell.get();
@@ -103,26 +966,190 @@ impl App {
     }
 }
 
+impl IdMap for App {
+    fn window_id(&self, id: tao::window::WindowId) -> u64 {
+        self.map_window_id(id)
+    }
+
+    fn device_id(&self, id: tao::event::DeviceId) -> u64 {
+        self.map_device_id(id)
+    }
+}
+
 #[uniffi::export]
 impl App {
+    /// Asks the event loop to exit as soon as it processes this request. Callable from any thread.
+    pub fn request_exit(&self) -> Result<(), TaoError> {
+        self.proxy.send_event(LoopMessage::Exit)
+    }
+
+    pub fn event_mask(&self) -> EventMask {
+        *self.event_mask.lock().unwrap()
+    }
+
+    /// Takes effect from the next dispatched event.
+    pub fn set_event_mask(&self, mask: EventMask) {
+        *self.event_mask.lock().unwrap() = mask;
+    }
+
+    pub fn set_coalesce_pointer_motion(&self, enabled: bool) {
+        self.coalesce_pointer_motion.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Delivers `event` to the handler on the next loop iteration, as if tao had emitted it.
+    ///
+    /// Meant for tests and tooling; the event skips `EventMask` and click tracking.
+    pub fn inject_event(&self, event: TaoEvent) -> Result<(), TaoError> {
+        self.proxy.send_event(LoopMessage::Inject(Box::new(event)))
+    }
+
+    /// Shorthand for `inject_event` with a `TaoEvent::WindowEvent`.
+    pub fn inject_window_event(&self, window_id: u64, event: TaoWindowEvent) -> Result<(), TaoError> {
+        self.inject_event(TaoEvent::WindowEvent { window_id, event })
+    }
+
+    /// Runs `task` on the event loop thread during the next loop iteration.
+    ///
+    /// The window target is active while the task runs, so it may create windows or query
+    /// monitors. Tasks run in the order they were queued.
+    pub fn run_on_event_loop(&self, task: Box<dyn MainThreadTask>) -> Result<(), TaoError> {
+        let mut tasks = self.tasks.lock().unwrap();
+        if tasks.is_empty() {
+            self.proxy.send_event(LoopMessage::RunTasks)?;
+        }
+        tasks.push(task);
+        Ok(())
+    }
+
+    /// Starts a timer that delivers `TaoUserEvent::Timer { id }` once `interval_ms` elapsed,
+    /// and then every `interval_ms` if `repeating`. Returns the timer id.
+    pub fn set_timer(&self, interval_ms: u64, repeating: bool) -> u64 {
+        let id = self
+            .timers
+            .lock()
+            .unwrap()
+            .add(Duration::from_millis(interval_ms), repeating, Instant::now());
+        // Wake the loop so it picks up the new deadline.
+        let _ = self.proxy.send_event(LoopMessage::Resume);
+        id
+    }
+
+    /// Returns false if the timer already fired (one-shot) or does not exist.
+    pub fn cancel_timer(&self, id: u64) -> bool {
+        self.timers.lock().unwrap().cancel(id)
+    }
+
+    /// Counts and callback latencies per event type, collected while metrics are enabled.
+    ///
+    /// Callback time covers the handler and, for `run_batched`/`run_async`, only the queueing.
+    pub fn metrics(&self) -> DispatchMetrics {
+        self.metrics
+            .lock()
+            .unwrap()
+            .snapshot(Instant::now(), self.user_events.dropped())
+    }
+
+    pub fn set_metrics_enabled(&self, enabled: bool) {
+        self.metrics.lock().unwrap().set_enabled(enabled);
+    }
+
+    pub fn reset_metrics(&self) {
+        self.metrics.lock().unwrap().reset();
+    }
+
+    /// Sets the threshold for `TaoEvent::HandlerStall`; `None` (or zero) turns the watchdog off.
+    pub fn set_stall_threshold_ms(&self, threshold_ms: Option<u64>) {
+        self.stall_threshold_ms
+            .store(threshold_ms.unwrap_or(0), Ordering::Relaxed);
+    }
+
+    /// Registers cleanup that runs once, right after the handler received `LoopDestroyed`
+    /// (after the last frame with `run_return_loop`, or when an `EventLoopPump` is closed).
+    ///
+    /// With `run_on_demand`, whose runs all end with `LoopDestroyed`, they run once the loop is torn
+    /// down by `close_on_demand` or by the exit of the thread that ran it.
+    pub fn on_exit(&self, hook: Box<dyn ExitHook>) {
+        self.exit_hooks.lock().unwrap().push(hook);
+    }
+
+    /// Registers a listener that sees every event before the main handler does.
+    ///
+    /// With `window_id` set, only window and redraw events of that window are passed on.
+    /// Returns an id for `remove_event_listener`.
+    pub fn add_event_listener(&self, listener: Arc<dyn TaoEventListener>, window_id: Option<u64>) -> u64 {
+        let id = self.next_listener_id.fetch_add(1, Ordering::Relaxed);
+        self.listeners.lock().unwrap().push(Listener {
+            id,
+            window_id,
+            listener,
+        });
+        id
+    }
+
+    /// Returns false if no listener with this id was registered.
+    pub fn remove_event_listener(&self, id: u64) -> bool {
+        let mut listeners = self.listeners.lock().unwrap();
+        let before = listeners.len();
+        listeners.retain(|l| l.id != id);
+        listeners.len() != before
+    }
+
+    /// Display connection of the event loop, available before any window exists
+    /// (e.g. to create a Vulkan instance with the right surface extensions).
+    pub fn raw_display_handle(&self) -> Result<crate::RawDisplayHandle, TaoError> {
+        use raw_window_handle::HasDisplayHandle;
+
+        self.with_target(|target| {
+            let handle = target
+                .display_handle()
+                .map_err(|e| TaoError::message(e.to_string()))?;
+            Ok(crate::RawDisplayHandle::from_rwh(handle.as_raw()))
+        })?
+    }
+
+    /// Receives messages sent to channel `name` with `send_ipc_message` as `TaoEvent::IpcMessage`
+    /// until the loop exits. Fails if another process already listens on `name`, which makes it
+    /// usable as a single-instance check. Call from within the event loop callback.
+    pub fn listen_ipc(&self, name: String) -> Result<(), TaoError> {
+        self.with_target(|_| crate::ipc::listen(&name, &self.proxy))?
+    }
+
+    /// Windowing system the event loop is connected to.
+    pub fn display_backend(&self) -> Result<crate::DisplayBackend, TaoError> {
+        use raw_window_handle::HasDisplayHandle;
+
+        self.with_target(|target| {
+            let handle = target
+                .display_handle()
+                .map_err(|e| TaoError::message(e.to_string()))?;
+            crate::DisplayBackend::from_rwh(handle.as_raw()).ok_or(TaoError::Unsupported)
+        })?
+    }
+
     pub fn create_proxy(&self) -> Arc<EventLoopProxy> {
         Arc::new(EventLoopProxy {
             inner: self.proxy.clone(),
+            user_events: self.user_events.clone(),
         })
     }
 
     pub fn create_window(&self, builder: Arc<WindowBuilder>) -> Result<Arc<Window>, TaoError> {
+        let application_id = builder.application_id().or_else(|| self.application_id.lock().unwrap().clone());
         self.with_target(|target| {
-            let tao_builder = builder.clone_inner();
-            tao_builder.build(target).map_err(TaoError::from)
+            let tao_window = builder.clone_inner().build(target)?;
+            if let Some(id) = &application_id {
+                crate::app_id::set_application_id(&tao_window, id);
+            }
+            Ok::<_, TaoError>(tao_window)
         })?
         .map(|tao_window| {
             let tao_id = tao_window.id();
             let id = self.map_window_id(tao_id);
-            Arc::new(Window {
-                id,
-                inner: Mutex::new(tao_window),
-            })
+            let window = Arc::new(Window::new(id, tao_window, self.proxy.clone()));
+            self.windows.lock().unwrap().insert(id, Arc::downgrade(&window));
+            // Pace frames to this window's display from the start, not only after it moves.
+            self.follow_refresh_rates();
+            window
         })
     }
 
@@ -130,6 +1157,12 @@ impl App {
         self.create_window(Arc::new(WindowBuilder::new()))
     }
 
+    /// Application id for windows created from now on whose builder sets none; see
+    /// `WindowBuilder::set_application_id`. Linux only; ignored elsewhere.
+    pub fn set_application_id(&self, application_id: Option<String>) {
+        *self.application_id.lock().unwrap() = application_id;
+    }
+
     pub fn available_monitors(&self) -> Result<Vec<Arc<crate::Monitor>>, TaoError> {
         self.with_target(|target| {
             Ok(target
@@ -142,16 +1175,254 @@ impl App {
     pub fn primary_monitor(&self) -> Result<Option<Arc<crate::Monitor>>, TaoError> {
         self.with_target(|target| Ok(target.primary_monitor().map(|m| Arc::new(crate::Monitor { inner: m }))))?
     }
+
+    /// Keyboards, mice, touch screens and other HID devices currently attached.
+    ///
+    /// Device ids in events are per-session numbers and are not related to `InputDevice::id`.
+    pub fn input_devices(&self) -> Vec<crate::InputDevice> {
+        crate::devices::input_devices()
+    }
+
+    /// Keeps the system, and with `SleepInhibition::Display` the display, awake until the
+    /// returned handle is released or dropped. `reason` may be shown to the user.
+    pub fn inhibit_sleep(
+        &self,
+        reason: String,
+        inhibition: crate::SleepInhibition,
+    ) -> Result<Arc<crate::InhibitHandle>, TaoError> {
+        Ok(Arc::new(crate::InhibitHandle::new(&reason, inhibition)?))
+    }
+
+    /// Seconds since the user last used any input device, in any application.
+    ///
+    /// On Linux this needs GNOME's idle monitor or the freedesktop screensaver service.
+    pub fn system_idle_seconds(&self) -> Result<f64, TaoError> {
+        crate::idle::system_idle_seconds()
+    }
+
+    /// Sends `TaoEvent::IdleChanged` when the system idle time reaches `threshold_seconds`
+    /// and again when input resumes, checking once per second. `None` stops watching.
+    pub fn set_idle_threshold(&self, threshold_seconds: Option<u32>) {
+        let mut watch = self.idle_watch.lock().unwrap();
+        if let Some(stop) = watch.take() {
+            stop.store(true, Ordering::Relaxed);
+        }
+        let Some(threshold_seconds) = threshold_seconds else {
+            return;
+        };
+        let stop = Arc::new(AtomicBool::new(false));
+        let proxy = self.proxy.clone();
+        crate::idle::watch(threshold_seconds, Arc::clone(&stop), move |idle, idle_seconds| {
+            proxy.send_event(LoopMessage::IdleChanged { idle, idle_seconds }).is_ok()
+        });
+        *watch = Some(stop);
+    }
+
+    /// High contrast, reduce motion and reduce transparency as set by the user.
+    pub fn accessibility_preferences(&self) -> Result<crate::AccessibilityPreferences, TaoError> {
+        crate::accessibility::accessibility_preferences()
+    }
+
+    /// Sends `TaoEvent::AccessibilityChanged` when the accessibility preferences change,
+    /// checking every two seconds.
+    pub fn set_watch_accessibility(&self, watch: bool) {
+        let mut current = self.accessibility_watch.lock().unwrap();
+        if let Some(stop) = current.take() {
+            stop.store(true, Ordering::Relaxed);
+        }
+        if !watch {
+            return;
+        }
+        let stop = Arc::new(AtomicBool::new(false));
+        let proxy = self.proxy.clone();
+        crate::accessibility::watch(Arc::clone(&stop), move |preferences| {
+            proxy.send_event(LoopMessage::AccessibilityChanged(preferences)).is_ok()
+        });
+        *current = Some(stop);
+    }
+
+    /// Changes the activation policy while running (macOS only), e.g. `Regular` while a window is
+    /// open and `Accessory` once only the tray icon remains. Call on the event loop thread.
+    pub fn set_activation_policy(&self, policy: ActivationPolicy) -> Result<(), TaoError> {
+        #[cfg(target_os = "macos")]
+        return self.with_target(|target| {
+            use tao::platform::macos::EventLoopWindowTargetExtMacOS;
+            target.set_activation_policy_at_runtime(policy.into());
+        });
+
+        #[allow(unreachable_code)]
+        {
+            let _ = policy;
+            Err(TaoError::Unsupported)
+        }
+    }
+
+    /// Shows or hides the Dock icon without changing the activation policy (macOS only).
+    /// Call on the event loop thread.
+    pub fn set_dock_visibility(&self, visible: bool) -> Result<(), TaoError> {
+        #[cfg(target_os = "macos")]
+        return self.with_target(|target| {
+            use tao::platform::macos::EventLoopWindowTargetExtMacOS;
+            target.set_dock_visibility(visible);
+        });
+
+        #[allow(unreachable_code)]
+        {
+            let _ = visible;
+            Err(TaoError::Unsupported)
+        }
+    }
+
+    /// Brings the application to the front (macOS only). With `ignoring_other_apps` it takes
+    /// focus even while the user works in another application, e.g. after a tray icon click.
+    /// Call on the event loop thread.
+    pub fn activate(&self, ignoring_other_apps: bool) -> Result<(), TaoError> {
+        #[cfg(target_os = "macos")]
+        return self.with_target(|_| {
+            use objc2::{class, msg_send, runtime::AnyObject};
+            let app: *mut AnyObject = unsafe { msg_send![class!(NSApplication), sharedApplication] };
+            let _: () = unsafe { msg_send![&*app, activateIgnoringOtherApps: ignoring_other_apps] };
+        });
+
+        #[allow(unreachable_code)]
+        {
+            let _ = ignoring_other_apps;
+            Err(TaoError::Unsupported)
+        }
+    }
+
+    /// Hides all windows of the application, like Cmd+H (macOS only). Call on the event loop
+    /// thread.
+    pub fn hide(&self) -> Result<(), TaoError> {
+        #[cfg(target_os = "macos")]
+        return self.with_target(|target| {
+            use tao::platform::macos::EventLoopWindowTargetExtMacOS;
+            target.hide_application();
+        });
+
+        #[allow(unreachable_code)]
+        Err(TaoError::Unsupported)
+    }
+
+    /// Hides all other applications, like Option+Cmd+H (macOS only). Call on the event loop
+    /// thread.
+    pub fn hide_other_applications(&self) -> Result<(), TaoError> {
+        #[cfg(target_os = "macos")]
+        return self.with_target(|target| {
+            use tao::platform::macos::EventLoopWindowTargetExtMacOS;
+            target.hide_other_applications();
+        });
+
+        #[allow(unreachable_code)]
+        Err(TaoError::Unsupported)
+    }
+
+    /// Turns Cmd+Q and Quit from the Dock into `TaoEvent::QuitRequested` instead of quitting
+    /// right away (macOS only), e.g. to ask about unsaved changes first.
+    pub fn set_intercept_quit(&self, intercept: bool) -> Result<(), TaoError> {
+        crate::quit::set_intercept(intercept)
+    }
+
+    /// Consumes the Android back button and gesture and reports them as `TaoEvent::BackPressed`.
+    /// Turn it off at the root of the UI so back leaves the application as usual.
+    pub fn set_handle_back(&self, handle: bool) -> Result<(), TaoError> {
+        #[cfg(target_os = "android")]
+        {
+            crate::android::set_handle_back(handle);
+            return Ok(());
+        }
+
+        #[allow(unreachable_code)]
+        {
+            let _ = handle;
+            Err(TaoError::Unsupported)
+        }
+    }
+
+    /// The parts of the window covered by system bars, the display cutout and the on-screen
+    /// keyboard (Android), as last reported by `TaoEvent::InsetsChanged`.
+    pub fn window_insets(&self) -> Result<crate::WindowInsets, TaoError> {
+        #[cfg(target_os = "android")]
+        return Ok(crate::android::window_insets());
+
+        #[allow(unreachable_code)]
+        Err(TaoError::Unsupported)
+    }
+
+    /// Asks the OS to wait before logging out or shutting down while the returned block is
+    /// alive, showing `reason` to the user (Windows, Linux). Call on the event loop thread.
+    ///
+    /// Take a block while there is unsaved work, and release it once the work is saved.
+    pub fn block_session_end(&self, reason: String) -> Result<Arc<crate::SessionEndBlock>, TaoError> {
+        Ok(Arc::new(crate::SessionEndBlock::new(reason, self.proxy.clone())?))
+    }
+
+    /// Shows a desktop notification. Clicks come back as `TaoEvent::NotificationActivated`.
+    pub fn show_notification(&self, notification: crate::Notification) -> Result<(), TaoError> {
+        let proxy = self.proxy.clone();
+        let (id, window_id) = (notification.id.clone(), notification.window_id);
+        crate::notification::show(notification, move |action| {
+            let _ = proxy.send_event(LoopMessage::NotificationActivated {
+                id: id.clone(),
+                action,
+                window_id,
+            });
+        })
+    }
+
+    /// The monitor containing the global point (`x`, `y`), in physical pixels.
+    pub fn monitor_from_point(&self, x: f64, y: f64) -> Result<Option<Arc<crate::Monitor>>, TaoError> {
+        self.with_target(|target| Ok(target.monitor_from_point(x, y).map(|m| Arc::new(crate::Monitor { inner: m }))))?
+    }
 }
 
+/// The macOS menu bar, which mobile platforms do not have.
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
 #[uniffi::export]
-pub fn run(handler: Box<dyn TaoEventHandler>) {
-    run_with_config(RunConfig::default(), handler)
+impl App {
+    /// Sets the application menu shown in the macOS menu bar, or removes it with `None`.
+    /// Other platforms have per-window menus; see `Window::set_menu`.
+    pub fn set_app_menu(&self, menu: Option<Arc<Menu>>) -> Result<(), TaoError> {
+        #[cfg(target_os = "macos")]
+        {
+            let mut current = self.app_menu.lock().unwrap();
+            if let Some(old) = current.take() {
+                old.inner.get()?.remove_for_nsapp();
+            }
+            if let Some(menu) = &menu {
+                menu.inner.get()?.init_for_nsapp();
+            }
+            *current = menu;
+            return Ok(());
+        }
+
+        #[allow(unreachable_code)]
+        {
+            let _ = menu;
+            Err(TaoError::Unsupported)
+        }
+    }
 }
 
+#[cfg(feature = "dbus")]
 #[uniffi::export]
-pub fn run_with_config(config: RunConfig, handler: Box<dyn TaoEventHandler>) {
-    let mut builder = tao::event_loop::EventLoopBuilder::<TaoUserEvent>::with_user_event();
+impl App {
+    /// Calls a D-Bus method on the connection TaoKt's own integrations share (Linux only).
+    ///
+    /// `args` and the result are tuples in GVariant text format, e.g. `('org.freedesktop.appearance',
+    /// 'color-scheme')`. Blocks until the reply arrives; call it off the event loop thread for slow
+    /// services.
+    pub fn call_dbus(&self, method: crate::DBusMethod, args: Option<String>) -> Result<String, TaoError> {
+        crate::dbus::call_text(&method, args.as_deref())
+    }
+}
+
+fn build_event_loop(config: &RunConfig) -> tao::event_loop::EventLoop<LoopMessage> {
+    if let Some(thread) = &config.event_loop_thread {
+        configure_current_thread(thread);
+    }
+
+    let mut builder = tao::event_loop::EventLoopBuilder::<LoopMessage>::with_user_event();
 
     #[cfg(any(
         target_os = "linux",
@@ -162,35 +1433,236 @@ pub fn run_with_config(config: RunConfig, handler: Box<dyn TaoEventHandler>) {
     ))]
     {
         use tao::platform::unix::EventLoopBuilderExtUnix;
-        builder.with_any_thread(true);
+        builder.with_any_thread(config.allow_any_thread);
+        // Must happen before tao initializes GTK and opens the display.
+        if let Some(backend) = config.display_backend.and_then(crate::DisplayBackend::gdk_backend) {
+            gtk::gdk::set_allowed_backends(backend);
+        }
     }
 
     #[cfg(target_os = "windows")]
     {
         use tao::platform::windows::EventLoopBuilderExtWindows;
-        builder.with_any_thread(true);
+
+        builder.with_any_thread(config.allow_any_thread);
+        if let Some(awareness) = config.dpi_awareness {
+            builder.with_dpi_aware(false);
+            // Fails if the host already chose an awareness; that one stays in effect.
+            crate::dpi::set_dpi_awareness(awareness);
+        }
+        crate::msg_hook::install(&mut builder, config.windows_message_hook.clone());
     }
 
     let event_loop = builder.build();
     event_loop.set_device_event_filter(config.device_event_filter.into());
 
-    let app = Arc::new(App {
-        proxy: event_loop.create_proxy(),
-        next_window_id: AtomicU64::new(1),
-        window_ids: Mutex::new(HashMap::new()),
+    #[cfg(target_os = "macos")]
+    let event_loop = {
+        use tao::platform::macos::EventLoopExtMacOS;
+        let mut event_loop = event_loop;
+        if let Some(policy) = config.activation_policy {
+            event_loop.set_activation_policy(policy.into());
+        }
+        event_loop
+    };
+
+    event_loop
+}
+
+/// Runs the event loop on the calling thread. On iOS this must be the main thread, and the
+/// call never returns: the process exits when the loop does.
+#[uniffi::export]
+pub fn run(handler: Box<dyn TaoEventHandler>) {
+    run_with_config(RunConfig::default(), handler)
+}
+
+#[uniffi::export]
+pub fn run_with_config(config: RunConfig, handler: Box<dyn TaoEventHandler>) {
+    let event_loop = build_event_loop(&config);
+    let app = App::new(&event_loop, &config);
+
+    event_loop.run(move |event, target, control_flow| {
+        let _guard = TargetGuard::set(target as *const _);
+        let destroyed = matches!(event, tao::event::Event::LoopDestroyed);
+        app.dispatch(event, control_flow, |event| handler.handle_event(event, app.clone()));
+        if destroyed {
+            app.user_events.close();
+        }
     });
+}
+
+/// Like `run_with_config`, passing the affected `Window` along with each window event.
+#[uniffi::export]
+pub fn run_with_windows(config: RunConfig, handler: Box<dyn TaoWindowEventHandler>) {
+    let event_loop = build_event_loop(&config);
+    let app = App::new(&event_loop, &config);
 
     event_loop.run(move |event, target, control_flow| {
         let _guard = TargetGuard::set(target as *const _);
-        let converted = convert_event(event, |id| app.map_window_id(id));
-        if let Some(cf) = handler.handle_event(converted, app.clone()).to_tao() {
-            *control_flow = cf;
+        let destroyed = matches!(event, tao::event::Event::LoopDestroyed);
+        app.dispatch(event, control_flow, |event| {
+            let window = event.window_id().and_then(|id| app.window(id));
+            handler.handle_event(event, window, app.clone())
+        });
+        if destroyed {
+            app.user_events.close();
+        }
+    });
+}
+
+/// Events collected between `NewEvents` and `MainEventsCleared`.
+#[derive(Default)]
+struct EventBatch {
+    events: Vec<TaoEvent>,
+    open: bool,
+}
+
+impl EventBatch {
+    fn dispatch(
+        &mut self,
+        app: &Arc<App>,
+        event: tao::event::Event<'_, LoopMessage>,
+        control_flow: &mut tao::event_loop::ControlFlow,
+        handler: &dyn TaoBatchEventHandler,
+    ) {
+        match event {
+            tao::event::Event::NewEvents(_) => self.open = true,
+            tao::event::Event::MainEventsCleared | tao::event::Event::LoopDestroyed => self.open = false,
+            _ => {}
+        }
+
+        app.dispatch(event, control_flow, |event| {
+            self.events.push(event);
+            ControlFlow::Keep
+        });
+
+        if !self.open && !self.events.is_empty() {
+            let events = std::mem::take(&mut self.events);
+            let cf = match app.catch(|| handler.handle_events(events, app.clone())) {
+                Ok(cf) => cf,
+                // A handler that also fails on the error report is not retried.
+                Err(message) => panic::catch_unwind(AssertUnwindSafe(|| {
+                    handler.handle_events(vec![TaoEvent::HandlerError { message }], app.clone())
+                }))
+                .unwrap_or(ControlFlow::Keep),
+            };
+            app.apply_control_flow(cf, control_flow);
+        }
+    }
+}
+
+/// Like `run_with_config`, but delivers events in batches to cut down on FFI calls.
+#[uniffi::export]
+pub fn run_batched(config: RunConfig, handler: Box<dyn TaoBatchEventHandler>) {
+    let event_loop = build_event_loop(&config);
+    let app = App::new(&event_loop, &config);
+    let mut batch = EventBatch::default();
+
+    event_loop.run(move |event, target, control_flow| {
+        let _guard = TargetGuard::set(target as *const _);
+        let destroyed = matches!(event, tao::event::Event::LoopDestroyed);
+        batch.dispatch(&app, event, control_flow, handler.as_ref());
+        if destroyed {
+            app.user_events.close();
+        }
+    });
+}
+
+/// Wakes the event loop when a suspended async handler call can continue.
+struct ResumeWaker(Mutex<LoopProxy>);
+
+impl Wake for ResumeWaker {
+    fn wake(self: Arc<Self>) {
+        let _ = self.0.lock().unwrap().send_event(LoopMessage::Resume);
+    }
+}
+
+/// Feeds events to an async handler one call at a time.
+struct AsyncDelivery {
+    handler: Arc<dyn TaoAsyncEventHandler>,
+    waker: Waker,
+    in_flight: Option<Pin<Box<dyn Future<Output = ControlFlow> + Send>>>,
+    /// The call in flight reports a failure of the previous one.
+    reporting: bool,
+    backlog: VecDeque<TaoEvent>,
+}
+
+impl AsyncDelivery {
+    fn new(app: &App, handler: Arc<dyn TaoAsyncEventHandler>) -> Self {
+        Self {
+            handler,
+            waker: Waker::from(Arc::new(ResumeWaker(Mutex::new(app.proxy.clone())))),
+            in_flight: None,
+            reporting: false,
+            backlog: VecDeque::new(),
+        }
+    }
+
+    /// Runs handler calls until one suspends or the backlog is empty.
+    fn advance(&mut self, app: &Arc<App>, control_flow: &mut tao::event_loop::ControlFlow) {
+        let mut cx = Context::from_waker(&self.waker);
+        loop {
+            if let Some(future) = self.in_flight.as_mut() {
+                match app.catch(|| future.as_mut().poll(&mut cx)) {
+                    Ok(Poll::Ready(cf)) => {
+                        self.in_flight = None;
+                        self.reporting = false;
+                        app.apply_control_flow(cf, control_flow);
+                    }
+                    Ok(Poll::Pending) => return,
+                    Err(message) => {
+                        self.in_flight = None;
+                        // A handler that also fails on the error report is not retried.
+                        if !std::mem::take(&mut self.reporting) {
+                            self.start(app, TaoEvent::HandlerError { message });
+                            self.reporting = true;
+                            continue;
+                        }
+                    }
+                }
+            }
+
+            let Some(event) = self.backlog.pop_front() else {
+                return;
+            };
+            self.start(app, event);
+        }
+    }
+
+    fn start(&mut self, app: &Arc<App>, event: TaoEvent) {
+        let handler = self.handler.clone();
+        let app = app.clone();
+        self.in_flight = Some(Box::pin(async move { handler.handle_event(event, app).await }));
+    }
+}
+
+/// Like `run_with_config`, for handlers that suspend.
+///
+/// Events arriving while a call is suspended are queued, including `LoopDestroyed`, which is
+/// lost if the loop ends before the pending call completes.
+#[uniffi::export]
+pub fn run_async(config: RunConfig, handler: Arc<dyn TaoAsyncEventHandler>) {
+    let event_loop = build_event_loop(&config);
+    let app = App::new(&event_loop, &config);
+    let mut delivery = AsyncDelivery::new(&app, handler);
+
+    event_loop.run(move |event, target, control_flow| {
+        let _guard = TargetGuard::set(target as *const _);
+        let destroyed = matches!(event, tao::event::Event::LoopDestroyed);
+        app.dispatch(event, control_flow, |event| {
+            delivery.backlog.push_back(event);
+            ControlFlow::Keep
+        });
+        delivery.advance(&app, control_flow);
+        if destroyed {
+            app.user_events.close();
         }
     });
 }
 
+/// Runs the loop until `should_quit` returns true and returns the exit code of the last run.
 #[uniffi::export]
-pub fn run_return_loop(handler: Box<dyn TaoRunReturnHandler>) -> Result<(), TaoError> {
+pub fn run_return_loop(handler: Box<dyn TaoRunReturnHandler>) -> Result<i32, TaoError> {
     run_return_loop_with_config(RunConfig::default(), handler)
 }
 
@@ -198,7 +1670,68 @@ pub fn run_return_loop(handler: Box<dyn TaoRunReturnHandler>) -> Result<(), TaoE
 pub fn run_return_loop_with_config(
     config: RunConfig,
     handler: Box<dyn TaoRunReturnHandler>,
-) -> Result<(), TaoError> {
+) -> Result<i32, TaoError> {
+    #[cfg(target_os = "ios")]
+    {
+        let _ = config;
+        let _ = handler;
+        return Err(TaoError::Unsupported);
+    }
+
+    #[cfg(not(target_os = "ios"))]
+    {
+        use tao::platform::run_return::EventLoopExtRunReturn;
+
+        let mut event_loop = build_event_loop(&config);
+        let app = App::new(&event_loop, &config);
+        app.exit_hooks_on_destroy.store(false, Ordering::Relaxed);
+
+        let mut clock = FrameClock::new(Instant::now());
+        let mut exit_code = 0;
+        while !handler.should_quit() && !app.exit_requested.load(Ordering::SeqCst) {
+            exit_code = event_loop.run_return(|event, target, control_flow| {
+                let _guard = TargetGuard::set(target as *const _);
+                app.dispatch(event, control_flow, |event| handler.handle_event(event, app.clone()));
+            });
+
+            let interval = app.frames.lock().unwrap().interval();
+            handler.render(clock.tick(Instant::now(), interval));
+        }
+
+        app.run_exit_hooks();
+        app.user_events.close();
+        Ok(exit_code)
+    }
+}
+
+struct OnDemandLoop {
+    event_loop: tao::event_loop::EventLoop<LoopMessage>,
+    app: Arc<App>,
+}
+
+impl Drop for OnDemandLoop {
+    fn drop(&mut self) {
+        self.app.run_exit_hooks();
+        self.app.user_events.close();
+    }
+}
+
+thread_local! {
+    static ON_DEMAND_LOOP: RefCell<Option<OnDemandLoop>> = const { RefCell::new(None) };
+    static ON_DEMAND_CLOSED: Cell<bool> = const { Cell::new(false) };
+}
+
+static ON_DEMAND_THREAD: Mutex<Option<std::thread::ThreadId>> = Mutex::new(None);
+
+/// Runs the event loop until the handler exits it, and can be called again afterwards.
+///
+/// The first call creates an event loop that is kept for the lifetime of the calling thread;
+/// later calls resume it, so proxies and windows stay valid between runs. Every run starts
+/// with `NewEvents { cause: Init }` and ends with `LoopDestroyed`. Apart from
+/// `device_event_filter`, `config` is only used by the first call. All calls must come from
+/// the same thread. Returns the exit code the handler exited with.
+#[uniffi::export]
+pub fn run_on_demand(config: RunConfig, handler: Box<dyn TaoEventHandler>) -> Result<i32, TaoError> {
     #[cfg(target_os = "ios")]
     {
         let _ = config;
@@ -210,47 +1743,238 @@ pub fn run_return_loop_with_config(
     {
         use tao::platform::run_return::EventLoopExtRunReturn;
 
-        let mut builder = tao::event_loop::EventLoopBuilder::<TaoUserEvent>::with_user_event();
+        let current = std::thread::current().id();
+        let first_run = {
+            let mut owner = ON_DEMAND_THREAD.lock().unwrap();
+            match *owner {
+                Some(id) if id != current => {
+                    return Err(TaoError::message(
+                        "run_on_demand must always be called from the same thread",
+                    ));
+                }
+                Some(_) => false,
+                None => {
+                    *owner = Some(current);
+                    true
+                }
+            }
+        };
+
+        let mut state = match ON_DEMAND_LOOP.with(|cell| cell.borrow_mut().take()) {
+            Some(state) => state,
+            None if first_run => {
+                let event_loop = build_event_loop(&config);
+                let app = App::new(&event_loop, &config);
+                // Every run ends with LoopDestroyed; the hooks wait for the teardown.
+                app.exit_hooks_on_destroy.store(false, Ordering::Relaxed);
+                OnDemandLoop { event_loop, app }
+            }
+            None if ON_DEMAND_CLOSED.get() => return Err(TaoError::message("run_on_demand loop is closed")),
+            None => return Err(TaoError::message("run_on_demand is already running")),
+        };
+        state
+            .event_loop
+            .set_device_event_filter(config.device_event_filter.into());
+
+        let app = state.app.clone();
+        app.exit_requested.store(false, Ordering::SeqCst);
+        let exit_code = state.event_loop.run_return(|event, target, control_flow| {
+            let _guard = TargetGuard::set(target as *const _);
+            app.dispatch(event, control_flow, |event| handler.handle_event(event, app.clone()));
+        });
+
+        ON_DEMAND_LOOP.with(|cell| *cell.borrow_mut() = Some(state));
+        Ok(exit_code)
+    }
+}
+
+/// Destroys the event loop kept by `run_on_demand`, running the exit hooks registered with
+/// `App::on_exit`. Must be called from the thread that ran it, between runs; later calls to
+/// `run_on_demand` fail. Does nothing if `run_on_demand` never ran.
+#[uniffi::export]
+pub fn close_on_demand() -> Result<(), TaoError> {
+    let owner = *ON_DEMAND_THREAD.lock().unwrap();
+    match owner {
+        None => return Ok(()),
+        Some(id) if id != std::thread::current().id() => {
+            return Err(TaoError::message(
+                "close_on_demand must be called from the thread that ran run_on_demand",
+            ));
+        }
+        Some(_) => {}
+    }
+    if ON_DEMAND_CLOSED.get() {
+        return Ok(());
+    }
+    let state = ON_DEMAND_LOOP.with(|cell| cell.borrow_mut().take());
+    let Some(state) = state else {
+        return Err(TaoError::message("run_on_demand is running"));
+    };
+    ON_DEMAND_CLOSED.set(true);
+    drop(state);
+    Ok(())
+}
+
+struct PumpState {
+    // Boxed so the target pointer kept in `CURRENT_TARGET` between pumps stays valid.
+    event_loop: Box<tao::event_loop::EventLoop<LoopMessage>>,
+    target_guard: TargetGuard,
+    started: bool,
+}
+
+struct SendablePumpState(PumpState);
+
+// Safety: the state is only touched from the thread that created it; every access
+// checks `EventLoopPump::owner` first.
+unsafe impl Send for SendablePumpState {}
+
+/// An event loop driven by the caller instead of taking over the thread.
+///
+/// Meant for hosts that already own a main loop: call `pump_events` from it periodically.
+/// Between pumps the loop's target stays current on the owning thread, so `App` methods
+/// such as `create_window` work from that thread at any time.
+#[derive(uniffi::Object)]
+pub struct EventLoopPump {
+    app: Arc<App>,
+    owner: std::thread::ThreadId,
+    state: Mutex<Option<SendablePumpState>>,
+}
+
+#[uniffi::export]
+impl EventLoopPump {
+    /// Creates the event loop on the calling thread, which becomes the only thread allowed to pump it.
+    #[uniffi::constructor]
+    pub fn new(config: RunConfig) -> Result<Self, TaoError> {
+        #[cfg(target_os = "ios")]
+        {
+            let _ = config;
+            return Err(TaoError::Unsupported);
+        }
 
-        #[cfg(any(
-            target_os = "linux",
-            target_os = "dragonfly",
-            target_os = "freebsd",
-            target_os = "netbsd",
-            target_os = "openbsd"
-        ))]
+        #[cfg(not(target_os = "ios"))]
         {
-            use tao::platform::unix::EventLoopBuilderExtUnix;
-            builder.with_any_thread(true);
+            let event_loop = Box::new(build_event_loop(&config));
+            let app = App::new(&event_loop, &config);
+            let target: &tao::event_loop::EventLoopWindowTarget<LoopMessage> = &event_loop;
+            let target_guard = TargetGuard::set(target as *const _);
+            Ok(Self {
+                app,
+                owner: std::thread::current().id(),
+                state: Mutex::new(Some(SendablePumpState(PumpState {
+                    event_loop,
+                    target_guard,
+                    started: false,
+                }))),
+            })
         }
+    }
+
+    pub fn app(&self) -> Arc<App> {
+        self.app.clone()
+    }
 
-        #[cfg(target_os = "windows")]
+    /// Processes pending events, waiting up to `timeout_ms` for the first one, and returns them.
+    ///
+    /// Returns after one loop iteration (`NewEvents` .. `RedrawEventsCleared`) that contained
+    /// events, or once the timeout elapsed. A timeout of zero never blocks.
+    pub fn pump_events(&self, timeout_ms: u64) -> Result<Vec<TaoEvent>, TaoError> {
+        self.check_owner()?;
+
+        #[cfg(target_os = "ios")]
         {
-            use tao::platform::windows::EventLoopBuilderExtWindows;
-            builder.with_any_thread(true);
+            let _ = timeout_ms;
+            return Err(TaoError::Unsupported);
         }
 
-        let mut event_loop = builder.build();
-        event_loop.set_device_event_filter(config.device_event_filter.into());
+        #[cfg(not(target_os = "ios"))]
+        {
+            use tao::{
+                event::{Event, StartCause},
+                event_loop::ControlFlow as NativeControlFlow,
+                platform::run_return::EventLoopExtRunReturn,
+            };
 
-        let app = Arc::new(App {
-            proxy: event_loop.create_proxy(),
-            next_window_id: AtomicU64::new(1),
-            window_ids: Mutex::new(HashMap::new()),
-        });
+            let mut state = self.state.lock().unwrap();
+            let state = &mut state
+                .as_mut()
+                .ok_or_else(|| TaoError::message("Event loop pump is closed"))?
+                .0;
+
+            let deadline = Instant::now() + std::time::Duration::from_millis(timeout_ms);
+            let mut events = Vec::new();
+            let mut received = false;
+            let started = &mut state.started;
+            let app = &self.app;
+            // An exit requested during a previous pump ended that pump; it must not end this one too.
+            app.exit_requested.store(false, Ordering::SeqCst);
 
-        while !handler.should_quit() {
-            event_loop.run_return(|event, target, control_flow| {
+            state.event_loop.run_return(|event, target, control_flow| {
                 let _guard = TargetGuard::set(target as *const _);
-                let converted = convert_event(event, |id| app.map_window_id(id));
-                if let Some(cf) = handler.handle_event(converted, app.clone()).to_tao() {
-                    *control_flow = cf;
+                match &event {
+                    // Each run_return starts with Init and ends with LoopDestroyed; only the
+                    // very first Init is meaningful to the caller.
+                    Event::NewEvents(StartCause::Init) if *started => {
+                        *control_flow = NativeControlFlow::WaitUntil(deadline);
+                        return;
+                    }
+                    Event::LoopDestroyed => return,
+                    Event::NewEvents(_) => *control_flow = NativeControlFlow::WaitUntil(deadline),
+                    Event::MainEventsCleared | Event::RedrawEventsCleared => {}
+                    _ => received = true,
+                }
+                *started = true;
+
+                let end_of_iteration = matches!(event, Event::RedrawEventsCleared);
+                app.dispatch(event, control_flow, |event| {
+                    events.push(event);
+                    ControlFlow::Keep
+                });
+                if end_of_iteration && (received || Instant::now() >= deadline) {
+                    *control_flow = NativeControlFlow::Exit;
                 }
             });
 
-            handler.render();
+            Ok(events)
         }
+    }
 
+    /// Destroys the event loop. Must be called from the owning thread.
+    pub fn close(&self) -> Result<(), TaoError> {
+        self.check_owner()?;
+        if let Some(state) = self.state.lock().unwrap().take() {
+            self.app.run_exit_hooks();
+            self.app.user_events.close();
+            let PumpState {
+                event_loop,
+                target_guard,
+                ..
+            } = state.0;
+            drop(target_guard);
+            drop(event_loop);
+        }
         Ok(())
     }
 }
+
+impl EventLoopPump {
+    fn check_owner(&self) -> Result<(), TaoError> {
+        if std::thread::current().id() != self.owner {
+            return Err(TaoError::message(
+                "EventLoopPump can only be used from the thread that created it",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Drop for EventLoopPump {
+    fn drop(&mut self) {
+        if self.close().is_err() {
+            // Dropped on a foreign thread: the loop cannot be torn down here, and the owning
+            // thread may still point at its target, so leak it instead.
+            if let Some(state) = self.state.get_mut().unwrap().take() {
+                std::mem::forget(state);
+            }
+        }
+    }
+}
@@ -0,0 +1,101 @@
+//! Application id on Linux: the Wayland `app_id` and the X11 `WM_CLASS`.
+//!
+//! Desktops match both against the `.desktop` file name to pick the icon and group taskbar
+//! entries. GTK derives them from the process-wide program name, and on Wayland only reads it
+//! when the window is shown, so TaoKt sets them on each window instead: `WM_CLASS` directly, and
+//! the `app_id` through `gdk_wayland_window_set_application_id` every time the window is mapped.
+
+/// Sets `id` as the application id of `window`. Call on the event loop thread.
+pub(crate) fn set_application_id(window: &tao::window::Window, id: &str) {
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    gdk::set_application_id(window, id);
+
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )))]
+    let _ = (window, id);
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+mod gdk {
+    use std::ffi::{c_char, CString};
+
+    use gtk::{
+        gdk::{self, prelude::*, Atom, ChangeData, PropMode},
+        glib::{object::ObjectType, Propagation},
+        prelude::*,
+    };
+    use tao::platform::unix::WindowExtUnix;
+
+    /// Object data key holding the id on the `GtkWindow`.
+    const KEY: &str = "taokt-application-id";
+
+    type SetApplicationId = unsafe extern "C" fn(*mut gdk::ffi::GdkWindow, *const c_char);
+
+    pub(super) fn set_application_id(window: &tao::window::Window, id: &str) {
+        let gtk_window = window.gtk_window();
+        // Safety: KEY is only ever set to a String.
+        let first = unsafe { gtk_window.data::<String>(KEY) }.is_none();
+        unsafe { gtk_window.set_data(KEY, id.to_string()) };
+        if first {
+            // Wayland creates a new toplevel, which takes the program name again, on every map.
+            gtk_window.connect_map_event(|gtk_window, _| {
+                if let (Some(window), Some(id)) = (gtk_window.window(), unsafe { gtk_window.data::<String>(KEY) }) {
+                    apply(&window, unsafe { id.as_ref() });
+                }
+                Propagation::Proceed
+            });
+        }
+        if let Some(window) = gtk_window.window() {
+            apply(&window, id);
+        }
+    }
+
+    fn apply(window: &gdk::Window, id: &str) {
+        match window.display().type_().name() {
+            "GdkX11Display" => {
+                // WM_CLASS holds the instance and class names, each nul-terminated.
+                let value = format!("{id}\0{id}\0");
+                gdk::property_change(
+                    window,
+                    &Atom::intern("WM_CLASS"),
+                    &Atom::intern("STRING"),
+                    8,
+                    PropMode::Replace,
+                    ChangeData::UChars(value.as_bytes()),
+                );
+            }
+            "GdkWaylandDisplay" => {
+                let (Some(set), Ok(id)) = (wayland_set_application_id(), CString::new(id)) else {
+                    return;
+                };
+                unsafe { set(window.as_ptr(), id.as_ptr()) };
+            }
+            _ => {}
+        }
+    }
+
+    /// GTK 3.24.22 and later; looked up at runtime so that older GTK and builds without the
+    /// Wayland backend still load.
+    fn wayland_set_application_id() -> Option<SetApplicationId> {
+        let library = libloading::os::unix::Library::this();
+        let symbol = unsafe { library.get::<SetApplicationId>(b"gdk_wayland_window_set_application_id\0") }.ok()?;
+        Some(*symbol)
+    }
+}
@@ -0,0 +1,142 @@
+//! Multi-click detection using the platform double-click settings.
+
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use crate::{ElementState, MouseButton, PhysicalPositionF64, TaoWindowEvent};
+
+struct LastPress {
+    window_id: u64,
+    button: MouseButton,
+    at: Instant,
+    position: PhysicalPositionF64,
+    count: u32,
+}
+
+/// Fills in `MouseInput::click_count` from consecutive presses.
+///
+/// Presses of the same button in the same window count as one multi-click when they
+/// happen within the double-click interval and distance of the previous press.
+pub(crate) struct ClickTracker {
+    interval: Duration,
+    distance: f64,
+    cursors: HashMap<u64, PhysicalPositionF64>,
+    last: Option<LastPress>,
+}
+
+impl ClickTracker {
+    pub(crate) fn new(interval: Duration, distance: f64) -> Self {
+        Self {
+            interval,
+            distance,
+            cursors: HashMap::new(),
+            last: None,
+        }
+    }
+
+    /// Creates a tracker with the current OS double-click interval and distance.
+    pub(crate) fn from_platform() -> Self {
+        let (interval, distance) = platform_double_click_settings();
+        Self::new(interval, distance)
+    }
+
+    pub(crate) fn track(&mut self, window_id: u64, event: &mut TaoWindowEvent, now: Instant) {
+        match event {
+            TaoWindowEvent::CursorMoved { position, .. } => {
+                self.cursors.insert(window_id, *position);
+            }
+            TaoWindowEvent::Destroyed => {
+                self.cursors.remove(&window_id);
+            }
+            TaoWindowEvent::MouseInput {
+                state,
+                button,
+                click_count,
+                ..
+            } => {
+                let position = self
+                    .cursors
+                    .get(&window_id)
+                    .copied()
+                    .unwrap_or(PhysicalPositionF64 { x: 0.0, y: 0.0 });
+                *click_count = match state {
+                    ElementState::Pressed => self.press(window_id, *button, position, now),
+                    ElementState::Released => match &self.last {
+                        Some(last) if last.window_id == window_id && last.button == *button => last.count,
+                        _ => 1,
+                    },
+                };
+            }
+            _ => {}
+        }
+    }
+
+    fn press(&mut self, window_id: u64, button: MouseButton, position: PhysicalPositionF64, now: Instant) -> u32 {
+        let count = match &self.last {
+            Some(last)
+                if last.window_id == window_id
+                    && last.button == button
+                    && now.saturating_duration_since(last.at) <= self.interval
+                    && (position.x - last.position.x).abs() <= self.distance
+                    && (position.y - last.position.y).abs() <= self.distance =>
+            {
+                last.count + 1
+            }
+            _ => 1,
+        };
+        self.last = Some(LastPress {
+            window_id,
+            button,
+            at: now,
+            position,
+            count,
+        });
+        count
+    }
+}
+
+/// Returns the double-click interval and the maximum distance (in pixels) between clicks.
+fn platform_double_click_settings() -> (Duration, f64) {
+    #[cfg(target_os = "windows")]
+    {
+        use windows_sys::Win32::UI::{
+            Input::KeyboardAndMouse::GetDoubleClickTime,
+            WindowsAndMessaging::{GetSystemMetrics, SM_CXDOUBLECLK, SM_CYDOUBLECLK},
+        };
+        unsafe {
+            let interval = Duration::from_millis(GetDoubleClickTime() as u64);
+            // The metrics describe the whole rectangle centered on the first click.
+            let distance = GetSystemMetrics(SM_CXDOUBLECLK).max(GetSystemMetrics(SM_CYDOUBLECLK)) as f64 / 2.0;
+            return (interval, distance);
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        use objc2::{class, msg_send};
+        let seconds: f64 = unsafe { msg_send![class!(NSEvent), doubleClickInterval] };
+        return (Duration::from_secs_f64(seconds), 4.0);
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    {
+        use gtk::prelude::GtkSettingsExt;
+        if let Some(settings) = gtk::Settings::default() {
+            return (
+                Duration::from_millis(settings.gtk_double_click_time().max(0) as u64),
+                settings.gtk_double_click_distance().max(0) as f64,
+            );
+        }
+    }
+
+    #[allow(unreachable_code)]
+    (Duration::from_millis(500), 4.0)
+}
@@ -0,0 +1,99 @@
+use crate::{TaoDeviceEvent, TaoEvent, TaoWindowEvent};
+
+/// Merges pointer motion delivered within one loop iteration.
+///
+/// `CursorMoved` keeps the latest position per window and device, `MouseMotion` sums
+/// the deltas per device. Pending motion is released, in arrival order, as soon as any
+/// other event shows up, so `MainEventsCleared` always flushes it.
+#[derive(Default)]
+pub(crate) struct MotionCoalescer {
+    pending: Vec<TaoEvent>,
+    merged: u64,
+}
+
+impl MotionCoalescer {
+    /// Returns the events ready for delivery. With `coalesce` off nothing is held back.
+    pub(crate) fn push(&mut self, event: TaoEvent, coalesce: bool) -> Vec<TaoEvent> {
+        if coalesce && is_motion(&event) {
+            match self.pending.iter_mut().find(|pending| same_source(pending, &event)) {
+                Some(pending) => {
+                    merge(pending, event);
+                    self.merged += 1;
+                }
+                None => self.pending.push(event),
+            }
+            return Vec::new();
+        }
+
+        let mut ready = std::mem::take(&mut self.pending);
+        ready.push(event);
+        ready
+    }
+}
+
+impl MotionCoalescer {
+    /// Number of events merged away since the last call.
+    pub(crate) fn take_merged(&mut self) -> u64 {
+        std::mem::take(&mut self.merged)
+    }
+}
+
+fn is_motion(event: &TaoEvent) -> bool {
+    matches!(
+        event,
+        TaoEvent::WindowEvent {
+            event: TaoWindowEvent::CursorMoved { .. },
+            ..
+        } | TaoEvent::DeviceEvent {
+            event: TaoDeviceEvent::MouseMotion { .. },
+            ..
+        }
+    )
+}
+
+fn same_source(a: &TaoEvent, b: &TaoEvent) -> bool {
+    match (a, b) {
+        (
+            TaoEvent::WindowEvent {
+                window_id: a_window,
+                event: TaoWindowEvent::CursorMoved { device_id: a_device, .. },
+            },
+            TaoEvent::WindowEvent {
+                window_id: b_window,
+                event: TaoWindowEvent::CursorMoved { device_id: b_device, .. },
+            },
+        ) => a_window == b_window && a_device == b_device,
+        (
+            TaoEvent::DeviceEvent {
+                device_id: a_device,
+                event: TaoDeviceEvent::MouseMotion { .. },
+            },
+            TaoEvent::DeviceEvent {
+                device_id: b_device,
+                event: TaoDeviceEvent::MouseMotion { .. },
+            },
+        ) => a_device == b_device,
+        _ => false,
+    }
+}
+
+fn merge(pending: &mut TaoEvent, event: TaoEvent) {
+    match (pending, event) {
+        (
+            TaoEvent::DeviceEvent {
+                event: TaoDeviceEvent::MouseMotion { delta_x, delta_y },
+                ..
+            },
+            TaoEvent::DeviceEvent {
+                event: TaoDeviceEvent::MouseMotion {
+                    delta_x: dx, delta_y: dy,
+                },
+                ..
+            },
+        ) => {
+            *delta_x += dx;
+            *delta_y += dy;
+        }
+        (pending, event) => *pending = event,
+    }
+}
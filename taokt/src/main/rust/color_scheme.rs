@@ -0,0 +1,104 @@
+//! The system color scheme from the freedesktop settings portal.
+//!
+//! tao derives the theme from the GTK theme name, which is wrong under Wayland, in Flatpak and
+//! with themes that do not end in `-dark`. The portal's `color-scheme` follows the desktop's
+//! dark style switch instead.
+
+use crate::Theme;
+
+/// Maps `org.freedesktop.appearance.color-scheme`: 0 is no preference, 1 dark, 2 light.
+pub(crate) fn theme_from_color_scheme(value: u32) -> Option<Theme> {
+    match value {
+        1 => Some(Theme::Dark),
+        2 => Some(Theme::Light),
+        _ => None,
+    }
+}
+
+/// The portal's theme, if the desktop states a preference.
+pub(crate) fn system_theme() -> Option<Theme> {
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    return *portal::SCHEME.lock().unwrap();
+
+    #[allow(unreachable_code)]
+    None
+}
+
+/// Reads the color scheme and reports changes to `proxy` (Linux only).
+pub(crate) fn watch(proxy: &crate::LoopProxy) {
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    portal::watch(proxy.clone());
+
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )))]
+    let _ = proxy;
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+mod portal {
+    use std::sync::Mutex;
+
+    use gtk::gio::DBusSignalFlags;
+
+    use super::theme_from_color_scheme;
+    use crate::{dbus, LoopMessage, LoopProxy, Theme};
+
+    const NAMESPACE: &str = "org.freedesktop.appearance";
+    const KEY: &str = "color-scheme";
+
+    pub(super) static SCHEME: Mutex<Option<Theme>> = Mutex::new(None);
+
+    pub(super) fn watch(proxy: LoopProxy) {
+        let Ok(connection) = dbus::session() else {
+            return;
+        };
+        *SCHEME.lock().unwrap() = dbus::read_setting(NAMESPACE, KEY)
+            .and_then(|value| value.get::<u32>())
+            .and_then(theme_from_color_scheme);
+        connection.signal_subscribe(
+            Some("org.freedesktop.portal.Desktop"),
+            Some("org.freedesktop.portal.Settings"),
+            Some("SettingChanged"),
+            Some("/org/freedesktop/portal/desktop"),
+            Some(NAMESPACE),
+            DBusSignalFlags::NONE,
+            move |_, _, _, _, _, parameters| {
+                if parameters.child_value(1).str() != Some(KEY) {
+                    return;
+                }
+                let mut value = parameters.child_value(2);
+                while let Some(inner) = value.as_variant() {
+                    value = inner;
+                }
+                let theme = value.get::<u32>().and_then(theme_from_color_scheme);
+                let changed = std::mem::replace(&mut *SCHEME.lock().unwrap(), theme) != theme;
+                if let (true, Some(theme)) = (changed, theme) {
+                    let _ = proxy.send_event(LoopMessage::ColorSchemeChanged(theme));
+                }
+            },
+        );
+    }
+}
@@ -0,0 +1,197 @@
+//! Platform cursors looked up by name, for shapes the `CursorIcon` enum does not cover.
+
+use crate::TaoError;
+
+/// Resource id of the Windows system cursor `IDC_<name>`; `name` may omit the prefix and is
+/// case-insensitive, e.g. `"IDC_PIN"` or `"pin"`.
+#[cfg(any(target_os = "windows", test))]
+pub(crate) fn windows_cursor_id(name: &str) -> Option<u16> {
+    let name = name.to_ascii_uppercase();
+    let name = name.strip_prefix("IDC_").unwrap_or(&name);
+    Some(match name {
+        "ARROW" => 32512,
+        "IBEAM" => 32513,
+        "WAIT" => 32514,
+        "CROSS" => 32515,
+        "UPARROW" => 32516,
+        "SIZENWSE" => 32642,
+        "SIZENESW" => 32643,
+        "SIZEWE" => 32644,
+        "SIZENS" => 32645,
+        "SIZEALL" => 32646,
+        "NO" => 32648,
+        "HAND" => 32649,
+        "APPSTARTING" => 32650,
+        "HELP" => 32651,
+        "PIN" => 32671,
+        "PERSON" => 32672,
+        _ => return None,
+    })
+}
+
+/// The `NSCursor` class method called `name`. Only these public cursor getters are accepted.
+#[cfg(any(target_os = "macos", test))]
+pub(crate) fn macos_cursor_selector(name: &str) -> Option<&'static std::ffi::CStr> {
+    Some(match name {
+        "arrowCursor" => c"arrowCursor",
+        "IBeamCursor" => c"IBeamCursor",
+        "IBeamCursorForVerticalLayout" => c"IBeamCursorForVerticalLayout",
+        "crosshairCursor" => c"crosshairCursor",
+        "closedHandCursor" => c"closedHandCursor",
+        "openHandCursor" => c"openHandCursor",
+        "pointingHandCursor" => c"pointingHandCursor",
+        "resizeLeftCursor" => c"resizeLeftCursor",
+        "resizeRightCursor" => c"resizeRightCursor",
+        "resizeLeftRightCursor" => c"resizeLeftRightCursor",
+        "resizeUpCursor" => c"resizeUpCursor",
+        "resizeDownCursor" => c"resizeDownCursor",
+        "resizeUpDownCursor" => c"resizeUpDownCursor",
+        "disappearingItemCursor" => c"disappearingItemCursor",
+        "operationNotAllowedCursor" => c"operationNotAllowedCursor",
+        "dragLinkCursor" => c"dragLinkCursor",
+        "dragCopyCursor" => c"dragCopyCursor",
+        "contextualMenuCursor" => c"contextualMenuCursor",
+        _ => return None,
+    })
+}
+
+/// Rejects names the platform cannot have, before the cursor is set on the event loop thread.
+pub(crate) fn check_name(name: &str) -> Result<(), TaoError> {
+    let known = {
+        #[cfg(target_os = "windows")]
+        {
+            windows_cursor_id(name).is_some()
+        }
+        #[cfg(target_os = "macos")]
+        {
+            macos_cursor_selector(name).is_some()
+        }
+        #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+        {
+            !name.is_empty() && !name.contains('\0')
+        }
+    };
+    if known {
+        Ok(())
+    } else {
+        Err(TaoError::message(format!("Unknown cursor name: {name}")))
+    }
+}
+
+/// Shows the platform cursor called `name` over `window` until `clear_named_cursor` or the
+/// next `set_cursor_icon`.
+pub(crate) fn set_named_cursor(window: &tao::window::Window, name: &str) -> Result<(), TaoError> {
+    let unknown = || TaoError::message(format!("Unknown cursor name: {name}"));
+
+    #[cfg(target_os = "windows")]
+    {
+        use tao::platform::windows::WindowExtWindows;
+        use windows_sys::Win32::UI::WindowsAndMessaging::{LoadCursorW, SetCursor};
+
+        let id = windows_cursor_id(name).ok_or_else(unknown)?;
+        let cursor = unsafe { LoadCursorW(std::ptr::null_mut(), id as usize as *const u16) };
+        if cursor.is_null() {
+            return Err(unknown());
+        }
+        win32::subclass(window.hwnd() as _, cursor as usize);
+        unsafe { SetCursor(cursor) };
+        return Ok(());
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        use objc2::{
+            class, msg_send,
+            runtime::{AnyObject, Sel},
+        };
+
+        let selector = Sel::register(macos_cursor_selector(name).ok_or_else(unknown)?);
+        let _ = window;
+        unsafe {
+            let cursor: *mut AnyObject = msg_send![class!(NSCursor), performSelector: selector];
+            if cursor.is_null() {
+                return Err(unknown());
+            }
+            let _: () = msg_send![&*cursor, set];
+        }
+        return Ok(());
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    {
+        use gtk::prelude::WidgetExt;
+        use tao::platform::unix::WindowExtUnix;
+
+        // Names from the Xcursor theme or the CSS cursor spec, e.g. "zoom-in" or "grabbing".
+        let gdk_window = window
+            .gtk_window()
+            .window()
+            .ok_or_else(|| TaoError::message("Window is not realized"))?;
+        let cursor = gtk::gdk::Cursor::from_name(&gdk_window.display(), name).ok_or_else(unknown)?;
+        gdk_window.set_cursor(Some(&cursor));
+        return Ok(());
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let _ = (window, unknown);
+        Err(TaoError::Unsupported)
+    }
+}
+
+/// Stops overriding tao's cursor after `set_named_cursor`. tao restores its own cursor on
+/// macOS and Linux when `set_cursor_icon` is called, so only Windows needs undoing.
+pub(crate) fn clear_named_cursor(window: &tao::window::Window) {
+    #[cfg(target_os = "windows")]
+    {
+        use tao::platform::windows::WindowExtWindows;
+        win32::unsubclass(window.hwnd() as _);
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    let _ = window;
+}
+
+#[cfg(target_os = "windows")]
+mod win32 {
+    use windows_sys::Win32::{
+        Foundation::{HWND, LPARAM, LRESULT, WPARAM},
+        UI::{
+            Shell::{DefSubclassProc, RemoveWindowSubclass, SetWindowSubclass},
+            WindowsAndMessaging::{SetCursor, HTCLIENT, WM_SETCURSOR},
+        },
+    };
+
+    const SUBCLASS_ID: usize = 0x7461_6f6b;
+
+    /// tao answers `WM_SETCURSOR` with its own cursor; answer it first with `cursor` over the
+    /// client area. Subclassing again only replaces the cursor.
+    pub(super) fn subclass(hwnd: HWND, cursor: usize) {
+        unsafe { SetWindowSubclass(hwnd, Some(cursor_proc), SUBCLASS_ID, cursor) };
+    }
+
+    pub(super) fn unsubclass(hwnd: HWND) {
+        unsafe { RemoveWindowSubclass(hwnd, Some(cursor_proc), SUBCLASS_ID) };
+    }
+
+    unsafe extern "system" fn cursor_proc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+        _id: usize,
+        cursor: usize,
+    ) -> LRESULT {
+        if msg == WM_SETCURSOR && (lparam & 0xffff) as u32 == HTCLIENT {
+            unsafe { SetCursor(cursor as _) };
+            return 1;
+        }
+        unsafe { DefSubclassProc(hwnd, msg, wparam, lparam) }
+    }
+}
@@ -0,0 +1,118 @@
+//! Dirty regions for `TaoEvent::RedrawRequested::dirty_rects`.
+//!
+//! A redraw the application did not request is a full one. On Windows the system's own damage
+//! (an uncovered or restored area) is read from `WM_PAINT` and added to the region, since it can
+//! coincide with requested rectangles; the redraws tao requests do not invalidate anything.
+
+use std::sync::{Arc, Mutex};
+
+use crate::PhysicalRect;
+
+/// Most rectangles kept before the region collapses into its bounding box.
+const MAX_RECTS: usize = 16;
+
+/// Areas passed to `Window::request_redraw_rect` since the last `RedrawRequested`, plus system
+/// damage where the platform reports it.
+#[derive(Default)]
+pub(crate) struct DirtyRegion {
+    rects: Vec<PhysicalRect>,
+    /// A full redraw was requested, which makes the rectangles irrelevant.
+    full: bool,
+}
+
+impl DirtyRegion {
+    pub(crate) fn add(&mut self, rect: PhysicalRect) {
+        if self.full || rect.is_empty() || self.rects.iter().any(|r| r.contains(&rect)) {
+            return;
+        }
+        self.rects.retain(|r| !rect.contains(r));
+        self.rects.push(rect);
+        if self.rects.len() > MAX_RECTS {
+            let bounds = self.rects.iter().skip(1).fold(self.rects[0], |acc, r| acc.union(r));
+            self.rects = vec![bounds];
+        }
+    }
+
+    pub(crate) fn invalidate_all(&mut self) {
+        self.full = true;
+        self.rects.clear();
+    }
+
+    /// Returns the areas to repaint and resets the region. Empty means the whole window.
+    pub(crate) fn take(&mut self) -> Vec<PhysicalRect> {
+        let rects = std::mem::take(&mut self.rects);
+        if std::mem::take(&mut self.full) {
+            return Vec::new();
+        }
+        rects
+    }
+}
+
+pub(crate) type SharedDirtyRegion = Arc<Mutex<DirtyRegion>>;
+
+/// Adds the system's damage of `window` to `region` (Windows only). Call on the event loop thread.
+pub(crate) fn attach(window: &tao::window::Window, region: &SharedDirtyRegion) {
+    #[cfg(target_os = "windows")]
+    win32::attach(window, region);
+
+    #[cfg(not(target_os = "windows"))]
+    let _ = (window, region);
+}
+
+#[cfg(target_os = "windows")]
+mod win32 {
+    use std::sync::{Mutex, Weak};
+
+    use tao::platform::windows::WindowExtWindows;
+    use windows_sys::Win32::{
+        Foundation::{HWND, LPARAM, LRESULT, RECT, WPARAM},
+        Graphics::Gdi::GetUpdateRect,
+        UI::{
+            Shell::{DefSubclassProc, RemoveWindowSubclass, SetWindowSubclass},
+            WindowsAndMessaging::{WM_NCDESTROY, WM_PAINT},
+        },
+    };
+
+    use super::{DirtyRegion, SharedDirtyRegion};
+    use crate::PhysicalRect;
+
+    const SUBCLASS_ID: usize = 0x6461_6d67;
+
+    pub(super) fn attach(window: &tao::window::Window, region: &SharedDirtyRegion) {
+        let region = Box::into_raw(Box::new(std::sync::Arc::downgrade(region)));
+        unsafe { SetWindowSubclass(window.hwnd() as _, Some(damage_proc), SUBCLASS_ID, region as usize) };
+    }
+
+    unsafe extern "system" fn damage_proc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+        _id: usize,
+        region: usize,
+    ) -> LRESULT {
+        let region = region as *mut Weak<Mutex<DirtyRegion>>;
+        match msg {
+            WM_PAINT => {
+                let mut rect: RECT = unsafe { std::mem::zeroed() };
+                // Zero when nothing is invalid, as for tao's internal paint requests.
+                if unsafe { GetUpdateRect(hwnd, &mut rect, 0) } != 0 {
+                    if let Some(region) = unsafe { (*region).upgrade() } {
+                        region.lock().unwrap().add(PhysicalRect {
+                            x: rect.left,
+                            y: rect.top,
+                            width: (rect.right - rect.left).max(0) as u32,
+                            height: (rect.bottom - rect.top).max(0) as u32,
+                        });
+                    }
+                }
+            }
+            WM_NCDESTROY => unsafe {
+                RemoveWindowSubclass(hwnd, Some(damage_proc), SUBCLASS_ID);
+                drop(Box::from_raw(region));
+            },
+            _ => {}
+        }
+        unsafe { DefSubclassProc(hwnd, msg, wparam, lparam) }
+    }
+}
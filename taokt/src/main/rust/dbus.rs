@@ -0,0 +1,165 @@
+//! Session bus access shared by the Linux desktop integrations: the settings portal, sleep
+//! inhibition, idle time, session end, notifications and tray availability.
+//!
+//! gio hands out one shared connection per bus, so every integration uses the same socket. With
+//! the `dbus` feature, `App::call_dbus` exposes it for calls TaoKt does not wrap.
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+pub(crate) use bus::*;
+
+#[cfg(feature = "dbus")]
+use crate::TaoError;
+
+#[cfg(feature = "dbus")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum DBusBus {
+    Session,
+    System,
+}
+
+/// A method to call with `App::call_dbus`.
+#[cfg(feature = "dbus")]
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct DBusMethod {
+    pub bus: DBusBus,
+    /// Bus name of the service, e.g. `org.freedesktop.portal.Desktop`.
+    pub destination: String,
+    pub path: String,
+    pub interface: String,
+    pub member: String,
+    /// Reply timeout; the bus default (25 s) when unset.
+    #[uniffi(default = None)]
+    pub timeout_ms: Option<u32>,
+}
+
+/// Calls `method` with `args`, a tuple in GVariant text format such as `('org.freedesktop.appearance',
+/// 'color-scheme')`, and returns the reply tuple in the same format.
+#[cfg(feature = "dbus")]
+pub(crate) fn call_text(method: &DBusMethod, args: Option<&str>) -> Result<String, TaoError> {
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    return bus::call_text(method, args);
+
+    #[allow(unreachable_code)]
+    {
+        let _ = (method, args);
+        Err(TaoError::Unsupported)
+    }
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+mod bus {
+    use gtk::{
+        gio::{self, BusType, DBusCallFlags},
+        glib::{ToVariant, Variant},
+    };
+
+    use crate::TaoError;
+
+    /// Timeout for the calls TaoKt makes itself, which all answer immediately.
+    const TIMEOUT_MS: i32 = 1000;
+
+    fn connect(bus: BusType) -> Result<gio::DBusConnection, TaoError> {
+        gio::bus_get_sync(bus, gio::Cancellable::NONE).map_err(|e| TaoError::message(format!("D-Bus: {e}")))
+    }
+
+    pub(crate) fn session() -> Result<gio::DBusConnection, TaoError> {
+        connect(BusType::Session)
+    }
+
+    pub(crate) fn call(
+        destination: &str,
+        path: &str,
+        interface: &str,
+        member: &str,
+        args: Option<&Variant>,
+    ) -> Result<Variant, TaoError> {
+        session()?
+            .call_sync(
+                Some(destination),
+                path,
+                interface,
+                member,
+                args,
+                None,
+                DBusCallFlags::NONE,
+                TIMEOUT_MS,
+                gio::Cancellable::NONE,
+            )
+            .map_err(|e| TaoError::message(format!("D-Bus {interface}.{member}: {e}")))
+    }
+
+    /// Whether a service currently owns `name`.
+    pub(crate) fn name_has_owner(name: &str) -> bool {
+        call(
+            "org.freedesktop.DBus",
+            "/org/freedesktop/DBus",
+            "org.freedesktop.DBus",
+            "NameHasOwner",
+            Some(&(name,).to_variant()),
+        )
+        .ok()
+        .and_then(|reply| reply.child_value(0).get::<bool>())
+        .unwrap_or(false)
+    }
+
+    /// `org.freedesktop.portal.Settings.ReadOne`, unwrapped from its variant boxes.
+    pub(crate) fn read_setting(namespace: &str, key: &str) -> Option<Variant> {
+        let reply = call(
+            "org.freedesktop.portal.Desktop",
+            "/org/freedesktop/portal/desktop",
+            "org.freedesktop.portal.Settings",
+            "ReadOne",
+            Some(&(namespace, key).to_variant()),
+        )
+        .ok()?;
+        let mut value = reply.child_value(0);
+        while let Some(inner) = value.as_variant() {
+            value = inner;
+        }
+        Some(value)
+    }
+
+    #[cfg(feature = "dbus")]
+    pub(super) fn call_text(method: &super::DBusMethod, args: Option<&str>) -> Result<String, TaoError> {
+        let args = args
+            .map(|text| Variant::parse(None, text))
+            .transpose()
+            .map_err(|e| TaoError::message(format!("Invalid D-Bus arguments: {e}")))?;
+        let bus = match method.bus {
+            super::DBusBus::Session => BusType::Session,
+            super::DBusBus::System => BusType::System,
+        };
+        let reply = connect(bus)?
+            .call_sync(
+                Some(&method.destination),
+                &method.path,
+                &method.interface,
+                &method.member,
+                args.as_ref(),
+                None,
+                DBusCallFlags::NONE,
+                method.timeout_ms.map_or(-1, |ms| ms.min(i32::MAX as u32) as i32),
+                gio::Cancellable::NONE,
+            )
+            .map_err(|e| TaoError::message(format!("D-Bus {}.{}: {e}", method.interface, method.member)))?;
+        Ok(reply.print(true).to_string())
+    }
+}
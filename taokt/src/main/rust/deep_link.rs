@@ -0,0 +1,331 @@
+//! Custom URL scheme ("myapp://...") registration.
+//!
+//! On macOS, schemes are declared in the bundle's Info.plist (`CFBundleURLTypes`)
+//! and opened URLs arrive as `TaoEvent::Opened`. On Windows and Linux the OS starts
+//! the registered command with the URL as an argument; `launch_urls` extracts those
+//! at startup and `EventLoopProxy::open_urls` forwards URLs received by other means
+//! into the running loop. A second launch hands its URLs to the running instance with
+//! `forward_launch_urls`; the instance receives them as `TaoEvent::Opened` on the channel
+//! it listens on with `App::listen_ipc`.
+
+use crate::TaoError;
+
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct UrlSchemeRegistration {
+    /// Scheme without the trailing `://`, e.g. `myapp`.
+    pub scheme: String,
+    /// Human-readable application name shown by the OS.
+    pub app_name: String,
+    /// Command line launched for incoming URLs; the URL is appended as the last argument.
+    /// Defaults to the current executable.
+    #[uniffi(default = None)]
+    pub command: Option<String>,
+}
+
+/// Returns true if `scheme` is a valid RFC 3986 scheme name.
+pub(crate) fn is_valid_scheme(scheme: &str) -> bool {
+    let mut chars = scheme.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic())
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+/// Returns the entries of `args` that are URLs using one of `schemes`.
+pub(crate) fn urls_with_schemes(args: impl IntoIterator<Item = String>, schemes: &[String]) -> Vec<String> {
+    args.into_iter()
+        .filter(|arg| {
+            schemes.iter().any(|scheme| {
+                arg.len() > scheme.len() + 1
+                    && arg.get(..scheme.len()).is_some_and(|prefix| prefix.eq_ignore_ascii_case(scheme))
+                    && arg[scheme.len()..].starts_with(':')
+            })
+        })
+        .collect()
+}
+
+/// Returns the URLs with one of `schemes` this process was launched with.
+#[uniffi::export]
+pub fn launch_urls(schemes: Vec<String>) -> Vec<String> {
+    urls_with_schemes(std::env::args().skip(1), &schemes)
+}
+
+/// Prefix of the IPC message `forward_launch_urls` sends; the URLs follow, separated by nul bytes.
+const URLS_MESSAGE: &[u8] = b"\0taokt-open-urls\0";
+
+pub(crate) fn encode_urls_message(urls: &[String]) -> Vec<u8> {
+    let mut message = URLS_MESSAGE.to_vec();
+    message.extend_from_slice(urls.join("\0").as_bytes());
+    message
+}
+
+/// The URLs of a message sent by `forward_launch_urls`, or `None` for other IPC messages.
+pub(crate) fn decode_urls_message(data: &[u8]) -> Option<Vec<String>> {
+    let urls = std::str::from_utf8(data.strip_prefix(URLS_MESSAGE)?).ok()?;
+    Some(urls.split('\0').filter(|url| !url.is_empty()).map(str::to_string).collect())
+}
+
+/// Sends the URLs with one of `schemes` this process was launched with to the instance
+/// listening on IPC channel `channel`. Returns true if they were delivered, in which case this
+/// process can exit; false if there were none or no instance is running.
+#[uniffi::export]
+pub fn forward_launch_urls(channel: String, schemes: Vec<String>) -> bool {
+    let urls = launch_urls(schemes);
+    !urls.is_empty() && crate::ipc::send_ipc_message(channel, encode_urls_message(&urls)).is_ok()
+}
+
+/// Rejects control characters, which would end the registry value or `.desktop` line early.
+fn check_text(field: &str, value: &str) -> Result<(), TaoError> {
+    if value.chars().any(char::is_control) {
+        return Err(TaoError::message(format!("{field} contains control characters")));
+    }
+    Ok(())
+}
+
+/// Quotes `path` as one `Exec` argument of a `.desktop` file, before string escaping.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    test
+))]
+pub(crate) fn desktop_exec_quote(path: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in path.chars() {
+        match c {
+            '"' | '`' | '$' | '\\' => {
+                quoted.push('\\');
+                quoted.push(c);
+            }
+            // Field codes start with '%'.
+            '%' => quoted.push_str("%%"),
+            _ => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+/// Escapes a `.desktop` string value; control characters were rejected beforehand.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    test
+))]
+pub(crate) fn desktop_string(value: &str) -> String {
+    value.replace('\\', "\\\\")
+}
+
+fn default_command() -> Result<String, TaoError> {
+    let exe = std::env::current_exe()?;
+    let exe = exe.to_str().ok_or_else(|| TaoError::message("The executable path is not valid UTF-8"))?;
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    return Ok(desktop_exec_quote(exe));
+
+    // Windows paths cannot contain quotes.
+    #[allow(unreachable_code)]
+    Ok(format!("\"{exe}\""))
+}
+
+/// Registers the application as the handler of a custom URL scheme for the current user.
+#[uniffi::export]
+pub fn register_url_scheme(registration: UrlSchemeRegistration) -> Result<(), TaoError> {
+    if !is_valid_scheme(&registration.scheme) {
+        return Err(TaoError::message(format!("Invalid URL scheme: {}", registration.scheme)));
+    }
+    let command = match &registration.command {
+        Some(command) => command.clone(),
+        None => default_command()?,
+    };
+    check_text("app_name", &registration.app_name)?;
+    check_text("command", &command)?;
+
+    #[cfg(target_os = "windows")]
+    {
+        return windows::register(&registration, &command);
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    {
+        return linux::register(&registration, &command);
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let _ = command;
+        Err(TaoError::Unsupported)
+    }
+}
+
+/// Removes a registration made with `register_url_scheme`.
+#[uniffi::export]
+pub fn unregister_url_scheme(scheme: String) -> Result<(), TaoError> {
+    if !is_valid_scheme(&scheme) {
+        return Err(TaoError::message(format!("Invalid URL scheme: {scheme}")));
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        return windows::unregister(&scheme);
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    {
+        return linux::unregister(&scheme);
+    }
+
+    #[allow(unreachable_code)]
+    Err(TaoError::Unsupported)
+}
+
+#[cfg(target_os = "windows")]
+mod windows {
+    use windows_sys::Win32::{
+        Foundation::ERROR_SUCCESS,
+        System::Registry::{
+            RegCloseKey, RegCreateKeyExW, RegDeleteTreeW, RegSetValueExW, HKEY, HKEY_CURRENT_USER, KEY_WRITE,
+            REG_OPTION_NON_VOLATILE, REG_SZ,
+        },
+    };
+
+    use super::UrlSchemeRegistration;
+    use crate::TaoError;
+
+    fn wide(value: &str) -> Vec<u16> {
+        value.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    fn set_key_value(path: &str, name: Option<&str>, value: &str) -> Result<(), TaoError> {
+        let path = wide(path);
+        let name = name.map(wide);
+        let value = wide(value);
+        unsafe {
+            let mut key: HKEY = std::ptr::null_mut();
+            let status = RegCreateKeyExW(
+                HKEY_CURRENT_USER,
+                path.as_ptr(),
+                0,
+                std::ptr::null(),
+                REG_OPTION_NON_VOLATILE,
+                KEY_WRITE,
+                std::ptr::null(),
+                &mut key,
+                std::ptr::null_mut(),
+            );
+            if status != ERROR_SUCCESS {
+                return Err(TaoError::message(format!("RegCreateKeyExW failed ({status})")));
+            }
+            let status = RegSetValueExW(
+                key,
+                name.as_ref().map_or(std::ptr::null(), |n| n.as_ptr()),
+                0,
+                REG_SZ,
+                value.as_ptr() as *const u8,
+                (value.len() * 2) as u32,
+            );
+            RegCloseKey(key);
+            if status != ERROR_SUCCESS {
+                return Err(TaoError::message(format!("RegSetValueExW failed ({status})")));
+            }
+        }
+        Ok(())
+    }
+
+    pub(super) fn register(registration: &UrlSchemeRegistration, command: &str) -> Result<(), TaoError> {
+        let root = format!("Software\\Classes\\{}", registration.scheme);
+        set_key_value(&root, None, &format!("URL:{}", registration.app_name))?;
+        set_key_value(&root, Some("URL Protocol"), "")?;
+        set_key_value(&format!("{root}\\shell\\open\\command"), None, &format!("{command} \"%1\""))
+    }
+
+    pub(super) fn unregister(scheme: &str) -> Result<(), TaoError> {
+        let path = wide(&format!("Software\\Classes\\{scheme}"));
+        let status = unsafe { RegDeleteTreeW(HKEY_CURRENT_USER, path.as_ptr()) };
+        if status != ERROR_SUCCESS {
+            return Err(TaoError::message(format!("RegDeleteTreeW failed ({status})")));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+mod linux {
+    use std::{path::PathBuf, process::Command};
+
+    use super::{desktop_string, UrlSchemeRegistration};
+    use crate::TaoError;
+
+    fn applications_dir() -> Result<PathBuf, TaoError> {
+        let data_home = match std::env::var_os("XDG_DATA_HOME") {
+            Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+            _ => {
+                let home = std::env::var_os("HOME").ok_or_else(|| TaoError::message("HOME is not set"))?;
+                PathBuf::from(home).join(".local/share")
+            }
+        };
+        Ok(data_home.join("applications"))
+    }
+
+    fn desktop_file_name(scheme: &str) -> String {
+        format!("taokt-{scheme}-handler.desktop")
+    }
+
+    pub(super) fn register(registration: &UrlSchemeRegistration, command: &str) -> Result<(), TaoError> {
+        let dir = applications_dir()?;
+        std::fs::create_dir_all(&dir)?;
+        let file_name = desktop_file_name(&registration.scheme);
+        let entry = format!(
+            "[Desktop Entry]\nType=Application\nName={}\nExec={} %u\nNoDisplay=true\nMimeType=x-scheme-handler/{};\n",
+            desktop_string(&registration.app_name),
+            desktop_string(command),
+            registration.scheme
+        );
+        std::fs::write(dir.join(&file_name), entry)?;
+
+        let status = Command::new("xdg-mime")
+            .args(["default", &file_name, &format!("x-scheme-handler/{}", registration.scheme)])
+            .status()?;
+        if !status.success() {
+            return Err(TaoError::message(format!("xdg-mime failed ({status})")));
+        }
+        // Refreshing the cache is optional; not every desktop ships the tool.
+        let _ = Command::new("update-desktop-database").arg(&dir).status();
+        Ok(())
+    }
+
+    pub(super) fn unregister(scheme: &str) -> Result<(), TaoError> {
+        let path = applications_dir()?.join(desktop_file_name(scheme));
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,370 @@
+//! Enumeration of attached input devices.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum InputDeviceKind {
+    Keyboard,
+    /// Mice, trackballs and touchpads.
+    Mouse,
+    /// Touch screens and pen digitizers.
+    Touch,
+    Other,
+}
+
+/// An input device attached to the system.
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct InputDevice {
+    /// Stable platform identifier: the Raw Input device path on Windows, the evdev node on
+    /// Linux, the IOKit location id on macOS.
+    pub id: String,
+    /// Product name, or the identifier when the device reports none.
+    pub name: String,
+    pub kind: InputDeviceKind,
+    pub vendor_id: Option<u16>,
+    pub product_id: Option<u16>,
+}
+
+/// Device kind from a HID usage page and usage.
+#[cfg(any(target_os = "windows", target_os = "macos", test))]
+pub(crate) fn kind_from_hid_usage(usage_page: u16, usage: u16) -> InputDeviceKind {
+    match (usage_page, usage) {
+        // Generic desktop: keyboard, keypad.
+        (0x01, 0x06 | 0x07) => InputDeviceKind::Keyboard,
+        // Generic desktop: pointer, mouse.
+        (0x01, 0x01 | 0x02) => InputDeviceKind::Mouse,
+        // Digitizer: touch pad.
+        (0x0d, 0x05) => InputDeviceKind::Mouse,
+        (0x0d, _) => InputDeviceKind::Touch,
+        _ => InputDeviceKind::Other,
+    }
+}
+
+/// Lists keyboards, mice, touch devices and other HID devices currently attached.
+pub(crate) fn input_devices() -> Vec<InputDevice> {
+    #[cfg(target_os = "windows")]
+    return win32::input_devices();
+
+    #[cfg(target_os = "macos")]
+    return iokit::input_devices();
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    return std::fs::read_to_string("/proc/bus/input/devices")
+        .map(|devices| parse_proc_input_devices(&devices))
+        .unwrap_or_default();
+
+    #[allow(unreachable_code)]
+    Vec::new()
+}
+
+/// Parses the kernel's `/proc/bus/input/devices` listing; devices without an evdev node are skipped.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    test
+))]
+pub(crate) fn parse_proc_input_devices(text: &str) -> Vec<InputDevice> {
+    text.split("\n\n").filter_map(parse_proc_input_device).collect()
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    test
+))]
+fn parse_proc_input_device(block: &str) -> Option<InputDevice> {
+    let mut name = None;
+    let mut vendor_id = None;
+    let mut product_id = None;
+    let mut handlers = Vec::new();
+    let mut bitmaps = std::collections::HashMap::new();
+    for line in block.lines() {
+        let Some((tag, value)) = line.split_once(": ") else {
+            continue;
+        };
+        match tag {
+            "I" => {
+                for field in value.split_whitespace() {
+                    match field.split_once('=') {
+                        Some(("Vendor", id)) => vendor_id = u16::from_str_radix(id, 16).ok(),
+                        Some(("Product", id)) => product_id = u16::from_str_radix(id, 16).ok(),
+                        _ => {}
+                    }
+                }
+            }
+            "N" => name = value.strip_prefix("Name=").map(|n| n.trim_matches('"').to_string()),
+            "H" => handlers = value.trim_start_matches("Handlers=").split_whitespace().collect(),
+            "B" => {
+                if let Some((key, mask)) = value.split_once('=') {
+                    bitmaps.insert(key, mask);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let node = handlers.iter().find(|h| h.starts_with("event"))?;
+    let has_bit = |key: &str, bit: usize| bitmaps.get(key).is_some_and(|mask| bitmap_has_bit(mask, bit));
+    const EV_REP: usize = 0x14;
+    const ABS_MT_POSITION_X: usize = 0x35;
+    const INPUT_PROP_DIRECT: usize = 0x01;
+    let kind = if has_bit("ABS", ABS_MT_POSITION_X) && has_bit("PROP", INPUT_PROP_DIRECT) {
+        InputDeviceKind::Touch
+    } else if handlers.iter().any(|h| h.starts_with("mouse")) {
+        InputDeviceKind::Mouse
+    } else if handlers.contains(&"kbd") && has_bit("EV", EV_REP) {
+        InputDeviceKind::Keyboard
+    } else {
+        InputDeviceKind::Other
+    };
+    let id = format!("/dev/input/{node}");
+    Some(InputDevice {
+        name: name.filter(|n| !n.is_empty()).unwrap_or_else(|| id.clone()),
+        id,
+        kind,
+        vendor_id,
+        product_id,
+    })
+}
+
+/// Tests `bit` in a kernel bitmap printed as space-separated hex words, most significant first.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    test
+))]
+fn bitmap_has_bit(mask: &str, bit: usize) -> bool {
+    let word_bits = usize::BITS as usize;
+    mask.split_whitespace()
+        .rev()
+        .nth(bit / word_bits)
+        .and_then(|word| u64::from_str_radix(word, 16).ok())
+        .is_some_and(|word| word & (1 << (bit % word_bits)) != 0)
+}
+
+/// Vendor and product id from a device path containing `VID_xxxx&PID_xxxx`.
+#[cfg(any(target_os = "windows", test))]
+pub(crate) fn ids_from_device_path(path: &str) -> (Option<u16>, Option<u16>) {
+    let path = path.to_ascii_uppercase();
+    let id_after = |prefix: &str| {
+        let start = path.find(prefix)? + prefix.len();
+        u16::from_str_radix(path.get(start..start + 4)?, 16).ok()
+    };
+    (id_after("VID_"), id_after("PID_"))
+}
+
+#[cfg(target_os = "windows")]
+mod win32 {
+    use std::{ffi::c_void, mem::size_of, ptr};
+
+    use windows_sys::Win32::{
+        Devices::HumanInterfaceDevice::HidD_GetProductString,
+        Foundation::{CloseHandle, INVALID_HANDLE_VALUE},
+        Storage::FileSystem::{CreateFileW, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING},
+        UI::Input::{
+            GetRawInputDeviceInfoW, GetRawInputDeviceList, RAWINPUTDEVICELIST, RIDI_DEVICEINFO, RIDI_DEVICENAME,
+            RID_DEVICE_INFO, RIM_TYPEHID, RIM_TYPEKEYBOARD, RIM_TYPEMOUSE,
+        },
+    };
+
+    use super::{ids_from_device_path, kind_from_hid_usage, InputDevice, InputDeviceKind};
+
+    pub(super) fn input_devices() -> Vec<InputDevice> {
+        let entry_size = size_of::<RAWINPUTDEVICELIST>() as u32;
+        let mut count = 0;
+        if unsafe { GetRawInputDeviceList(ptr::null_mut(), &mut count, entry_size) } == u32::MAX {
+            return Vec::new();
+        }
+        let mut list = vec![unsafe { std::mem::zeroed::<RAWINPUTDEVICELIST>() }; count as usize];
+        let written = unsafe { GetRawInputDeviceList(list.as_mut_ptr(), &mut count, entry_size) };
+        if written == u32::MAX {
+            return Vec::new();
+        }
+        list.truncate(written as usize);
+
+        list.iter()
+            .filter_map(|entry| {
+                let path = device_path(entry.hDevice)?;
+                let kind = match entry.dwType {
+                    RIM_TYPEKEYBOARD => InputDeviceKind::Keyboard,
+                    RIM_TYPEMOUSE => InputDeviceKind::Mouse,
+                    RIM_TYPEHID => hid_kind(entry.hDevice),
+                    _ => InputDeviceKind::Other,
+                };
+                let (vendor_id, product_id) = ids_from_device_path(&path);
+                Some(InputDevice {
+                    name: product_string(&path).unwrap_or_else(|| path.clone()),
+                    id: path,
+                    kind,
+                    vendor_id,
+                    product_id,
+                })
+            })
+            .collect()
+    }
+
+    fn device_path(device: *mut c_void) -> Option<String> {
+        let mut len = 0;
+        unsafe { GetRawInputDeviceInfoW(device, RIDI_DEVICENAME, ptr::null_mut(), &mut len) };
+        let mut name = vec![0u16; len as usize];
+        let written = unsafe { GetRawInputDeviceInfoW(device, RIDI_DEVICENAME, name.as_mut_ptr().cast(), &mut len) };
+        if written == u32::MAX || written == 0 {
+            return None;
+        }
+        name.truncate(written as usize);
+        Some(String::from_utf16_lossy(&name).trim_end_matches('\0').to_string())
+    }
+
+    fn hid_kind(device: *mut c_void) -> InputDeviceKind {
+        let mut info: RID_DEVICE_INFO = unsafe { std::mem::zeroed() };
+        info.cbSize = size_of::<RID_DEVICE_INFO>() as u32;
+        let mut len = info.cbSize;
+        let data = (&mut info as *mut RID_DEVICE_INFO).cast();
+        let written = unsafe { GetRawInputDeviceInfoW(device, RIDI_DEVICEINFO, data, &mut len) };
+        if written == u32::MAX {
+            return InputDeviceKind::Other;
+        }
+        let hid = unsafe { info.Anonymous.hid };
+        kind_from_hid_usage(hid.usUsagePage, hid.usUsage)
+    }
+
+    fn product_string(path: &str) -> Option<String> {
+        let wide: Vec<u16> = path.encode_utf16().chain(Some(0)).collect();
+        let handle = unsafe {
+            CreateFileW(
+                wide.as_ptr(),
+                0,
+                FILE_SHARE_READ | FILE_SHARE_WRITE,
+                ptr::null(),
+                OPEN_EXISTING,
+                0,
+                ptr::null_mut(),
+            )
+        };
+        if handle == INVALID_HANDLE_VALUE {
+            return None;
+        }
+        let mut buffer = [0u16; 127];
+        let ok = unsafe { HidD_GetProductString(handle, buffer.as_mut_ptr().cast(), size_of::<[u16; 127]>() as u32) };
+        unsafe { CloseHandle(handle) };
+        if ok == 0 {
+            return None;
+        }
+        let len = buffer.iter().position(|c| *c == 0).unwrap_or(buffer.len());
+        Some(String::from_utf16_lossy(&buffer[..len])).filter(|name| !name.is_empty())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod iokit {
+    use std::ffi::{c_char, c_void, CStr};
+
+    use super::{kind_from_hid_usage, InputDevice};
+
+    type CFTypeRef = *const c_void;
+
+    const K_CF_STRING_ENCODING_UTF8: u32 = 0x0800_0100;
+    const K_CF_NUMBER_SINT64_TYPE: isize = 4;
+
+    #[link(name = "IOKit", kind = "framework")]
+    unsafe extern "C" {
+        fn IOHIDManagerCreate(allocator: CFTypeRef, options: u32) -> CFTypeRef;
+        fn IOHIDManagerSetDeviceMatching(manager: CFTypeRef, matching: CFTypeRef);
+        fn IOHIDManagerCopyDevices(manager: CFTypeRef) -> CFTypeRef;
+        fn IOHIDDeviceGetProperty(device: CFTypeRef, key: CFTypeRef) -> CFTypeRef;
+    }
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    unsafe extern "C" {
+        fn CFSetGetCount(set: CFTypeRef) -> isize;
+        fn CFSetGetValues(set: CFTypeRef, values: *mut CFTypeRef);
+        fn CFStringCreateWithCString(allocator: CFTypeRef, string: *const c_char, encoding: u32) -> CFTypeRef;
+        fn CFStringGetCString(string: CFTypeRef, buffer: *mut c_char, size: isize, encoding: u32) -> bool;
+        fn CFStringGetTypeID() -> usize;
+        fn CFNumberGetTypeID() -> usize;
+        fn CFGetTypeID(value: CFTypeRef) -> usize;
+        fn CFNumberGetValue(number: CFTypeRef, kind: isize, value: *mut c_void) -> bool;
+        fn CFRelease(value: CFTypeRef);
+    }
+
+    pub(super) fn input_devices() -> Vec<InputDevice> {
+        unsafe {
+            let manager = IOHIDManagerCreate(std::ptr::null(), 0);
+            if manager.is_null() {
+                return Vec::new();
+            }
+            IOHIDManagerSetDeviceMatching(manager, std::ptr::null());
+            let set = IOHIDManagerCopyDevices(manager);
+            let mut devices = Vec::new();
+            if !set.is_null() {
+                let mut values = vec![std::ptr::null(); CFSetGetCount(set) as usize];
+                CFSetGetValues(set, values.as_mut_ptr());
+                devices = values.into_iter().map(|device| describe(device)).collect();
+                CFRelease(set);
+            }
+            CFRelease(manager);
+            devices
+        }
+    }
+
+    unsafe fn describe(device: CFTypeRef) -> InputDevice {
+        let number = |key: &CStr| unsafe { number_property(device, key) };
+        let id = number(c"LocationID").map_or_else(|| format!("{device:p}"), |location| format!("{location:#x}"));
+        let usage_page = number(c"PrimaryUsagePage").unwrap_or(0) as u16;
+        let usage = number(c"PrimaryUsage").unwrap_or(0) as u16;
+        InputDevice {
+            name: unsafe { string_property(device, c"Product") }.unwrap_or_else(|| id.clone()),
+            id,
+            kind: kind_from_hid_usage(usage_page, usage),
+            vendor_id: number(c"VendorID").map(|id| id as u16),
+            product_id: number(c"ProductID").map(|id| id as u16),
+        }
+    }
+
+    unsafe fn property(device: CFTypeRef, key: &CStr) -> CFTypeRef {
+        unsafe {
+            let key = CFStringCreateWithCString(std::ptr::null(), key.as_ptr(), K_CF_STRING_ENCODING_UTF8);
+            let value = IOHIDDeviceGetProperty(device, key);
+            CFRelease(key);
+            value
+        }
+    }
+
+    unsafe fn number_property(device: CFTypeRef, key: &CStr) -> Option<i64> {
+        unsafe {
+            let value = property(device, key);
+            if value.is_null() || CFGetTypeID(value) != CFNumberGetTypeID() {
+                return None;
+            }
+            let mut number = 0i64;
+            CFNumberGetValue(value, K_CF_NUMBER_SINT64_TYPE, (&mut number as *mut i64).cast()).then_some(number)
+        }
+    }
+
+    unsafe fn string_property(device: CFTypeRef, key: &CStr) -> Option<String> {
+        unsafe {
+            let value = property(device, key);
+            if value.is_null() || CFGetTypeID(value) != CFStringGetTypeID() {
+                return None;
+            }
+            let mut buffer = [0 as c_char; 256];
+            if !CFStringGetCString(value, buffer.as_mut_ptr(), buffer.len() as isize, K_CF_STRING_ENCODING_UTF8) {
+                return None;
+            }
+            Some(CStr::from_ptr(buffer.as_ptr()).to_string_lossy().into_owned())
+        }
+    }
+}
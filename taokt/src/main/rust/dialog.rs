@@ -0,0 +1,71 @@
+//! Native message boxes backed by rfd.
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum MessageLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+impl From<MessageLevel> for rfd::MessageLevel {
+    fn from(value: MessageLevel) -> Self {
+        match value {
+            MessageLevel::Info => rfd::MessageLevel::Info,
+            MessageLevel::Warning => rfd::MessageLevel::Warning,
+            MessageLevel::Error => rfd::MessageLevel::Error,
+        }
+    }
+}
+
+/// Buttons of a message box. The custom variants replace the default labels, e.g. "Save",
+/// "Don't Save" and "Cancel" for an unsaved-changes prompt.
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Enum)]
+pub enum MessageButtons {
+    Ok,
+    OkCancel,
+    YesNo,
+    YesNoCancel,
+    OkCustom { ok: String },
+    OkCancelCustom { ok: String, cancel: String },
+    YesNoCancelCustom { yes: String, no: String, cancel: String },
+}
+
+impl From<MessageButtons> for rfd::MessageButtons {
+    fn from(value: MessageButtons) -> Self {
+        match value {
+            MessageButtons::Ok => rfd::MessageButtons::Ok,
+            MessageButtons::OkCancel => rfd::MessageButtons::OkCancel,
+            MessageButtons::YesNo => rfd::MessageButtons::YesNo,
+            MessageButtons::YesNoCancel => rfd::MessageButtons::YesNoCancel,
+            MessageButtons::OkCustom { ok } => rfd::MessageButtons::OkCustom(ok),
+            MessageButtons::OkCancelCustom { ok, cancel } => rfd::MessageButtons::OkCancelCustom(ok, cancel),
+            MessageButtons::YesNoCancelCustom { yes, no, cancel } => {
+                rfd::MessageButtons::YesNoCancelCustom(yes, no, cancel)
+            }
+        }
+    }
+}
+
+/// The button that closed a message box. Closing it without a button reports `Cancel`, or
+/// `Ok` for boxes with a single button.
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Enum)]
+pub enum MessageBoxResult {
+    Ok,
+    Cancel,
+    Yes,
+    No,
+    /// A button of a custom variant, identified by its label.
+    Custom { label: String },
+}
+
+impl From<rfd::MessageDialogResult> for MessageBoxResult {
+    fn from(value: rfd::MessageDialogResult) -> Self {
+        match value {
+            rfd::MessageDialogResult::Ok => MessageBoxResult::Ok,
+            rfd::MessageDialogResult::Cancel => MessageBoxResult::Cancel,
+            rfd::MessageDialogResult::Yes => MessageBoxResult::Yes,
+            rfd::MessageDialogResult::No => MessageBoxResult::No,
+            rfd::MessageDialogResult::Custom(label) => MessageBoxResult::Custom { label },
+        }
+    }
+}
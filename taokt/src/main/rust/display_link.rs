@@ -0,0 +1,203 @@
+//! CVDisplayLink-driven frames on macOS.
+//!
+//! The link fires on a CoreVideo thread at every refresh of the window's display. Each tick is
+//! forwarded to the event loop, which reports it as `TaoEvent::DisplayLinkFrame` and issues the
+//! redraw requested with `Window::request_redraw_at_next_vblank`, replacing the frame timer.
+//! The link is paused while no frame is requested and nobody asked for every tick, so an idle
+//! window does not wake the loop at the refresh rate.
+
+use std::{
+    ffi::c_void,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+
+use crate::{LoopMessage, LoopProxy, TaoError};
+
+type CVDisplayLinkRef = *mut c_void;
+
+#[repr(C)]
+#[allow(dead_code)]
+struct CVSMPTETime {
+    subframes: i16,
+    subframe_divisor: i16,
+    counter: u32,
+    kind: u32,
+    flags: u32,
+    hours: i16,
+    minutes: i16,
+    seconds: i16,
+    frames: i16,
+}
+
+#[repr(C)]
+#[allow(dead_code)]
+struct CVTimeStamp {
+    version: u32,
+    video_time_scale: i32,
+    video_time: i64,
+    host_time: u64,
+    rate_scalar: f64,
+    video_refresh_period: i64,
+    smpte_time: CVSMPTETime,
+    flags: u64,
+    reserved: u64,
+}
+
+type OutputCallback = unsafe extern "C" fn(
+    CVDisplayLinkRef,
+    *const CVTimeStamp,
+    *const CVTimeStamp,
+    u64,
+    *mut u64,
+    *mut c_void,
+) -> i32;
+
+#[link(name = "CoreVideo", kind = "framework")]
+unsafe extern "C" {
+    fn CVDisplayLinkCreateWithCGDisplay(display: u32, link: *mut CVDisplayLinkRef) -> i32;
+    fn CVDisplayLinkSetCurrentCGDisplay(link: CVDisplayLinkRef, display: u32) -> i32;
+    fn CVDisplayLinkSetOutputCallback(link: CVDisplayLinkRef, callback: OutputCallback, context: *mut c_void) -> i32;
+    fn CVDisplayLinkStart(link: CVDisplayLinkRef) -> i32;
+    fn CVDisplayLinkStop(link: CVDisplayLinkRef) -> i32;
+    fn CVDisplayLinkRelease(link: CVDisplayLinkRef);
+}
+
+struct Context {
+    window_id: u64,
+    proxy: LoopProxy,
+    /// Set while a tick is on its way to the loop; ticks arriving meanwhile are dropped.
+    pending: Arc<AtomicBool>,
+    /// Nanoseconds per host time unit, as a fraction.
+    timebase: (u64, u64),
+}
+
+/// A running display link; stopped and released when dropped.
+pub(crate) struct DisplayLink {
+    link: CVDisplayLinkRef,
+    context: *mut Context,
+    pending: Arc<AtomicBool>,
+    display: u32,
+    running: bool,
+}
+
+// Safety: CVDisplayLink functions may be called from any thread; the context is only read by
+// the callback and freed after the link has stopped.
+unsafe impl Send for DisplayLink {}
+
+fn check(status: i32, function: &str) -> Result<(), TaoError> {
+    if status == 0 {
+        Ok(())
+    } else {
+        Err(TaoError::message(format!("{function} failed with CVReturn {status}")))
+    }
+}
+
+/// Converts mach host time to nanoseconds with the `numer / denom` timebase.
+pub(crate) fn host_time_to_nanos(host_time: u64, (numer, denom): (u64, u64)) -> u64 {
+    (host_time as u128 * numer as u128 / denom.max(1) as u128) as u64
+}
+
+impl DisplayLink {
+    /// Starts a link for the display with CoreGraphics id `display`.
+    pub(crate) fn new(window_id: u64, display: u32, proxy: LoopProxy) -> Result<Self, TaoError> {
+        let mut timebase = libc::mach_timebase_info { numer: 1, denom: 1 };
+        unsafe { libc::mach_timebase_info(&mut timebase) };
+        let pending = Arc::new(AtomicBool::new(false));
+        let context = Box::into_raw(Box::new(Context {
+            window_id,
+            proxy,
+            pending: pending.clone(),
+            timebase: (timebase.numer as u64, timebase.denom as u64),
+        }));
+        let mut link = std::ptr::null_mut();
+        let started = unsafe {
+            check(CVDisplayLinkCreateWithCGDisplay(display, &mut link), "CVDisplayLinkCreateWithCGDisplay")
+                .and_then(|()| {
+                    check(
+                        CVDisplayLinkSetOutputCallback(link, output_callback, context as *mut c_void),
+                        "CVDisplayLinkSetOutputCallback",
+                    )
+                })
+                .and_then(|()| check(CVDisplayLinkStart(link), "CVDisplayLinkStart"))
+        };
+        if let Err(error) = started {
+            unsafe {
+                if !link.is_null() {
+                    CVDisplayLinkRelease(link);
+                }
+                drop(Box::from_raw(context));
+            }
+            return Err(error);
+        }
+        Ok(Self {
+            link,
+            context,
+            pending,
+            display,
+            running: true,
+        })
+    }
+
+    /// Stops the ticks until `resume`.
+    pub(crate) fn pause(&mut self) {
+        if self.running && unsafe { CVDisplayLinkStop(self.link) } == 0 {
+            self.running = false;
+        }
+    }
+
+    pub(crate) fn resume(&mut self) {
+        if !self.running && unsafe { CVDisplayLinkStart(self.link) } == 0 {
+            self.running = true;
+        }
+    }
+
+    /// Follows the window to another display.
+    pub(crate) fn set_display(&mut self, display: u32) {
+        if display != self.display && unsafe { CVDisplayLinkSetCurrentCGDisplay(self.link, display) } == 0 {
+            self.display = display;
+        }
+    }
+
+    /// Lets the next tick through once the loop handled the previous one.
+    pub(crate) fn frame_handled(&self) {
+        self.pending.store(false, Ordering::Release);
+    }
+}
+
+impl Drop for DisplayLink {
+    fn drop(&mut self) {
+        unsafe {
+            // Waits for a running callback to return.
+            CVDisplayLinkStop(self.link);
+            CVDisplayLinkRelease(self.link);
+            drop(Box::from_raw(self.context));
+        }
+    }
+}
+
+unsafe extern "C" fn output_callback(
+    _link: CVDisplayLinkRef,
+    now: *const CVTimeStamp,
+    output_time: *const CVTimeStamp,
+    _flags_in: u64,
+    _flags_out: *mut u64,
+    context: *mut c_void,
+) -> i32 {
+    let context = unsafe { &*(context as *const Context) };
+    if context.pending.swap(true, Ordering::AcqRel) {
+        return 0;
+    }
+    let (now, output_time) = unsafe { ((*now).host_time, (*output_time).host_time) };
+    let sent = context.proxy.send_event(LoopMessage::DisplayLinkFrame {
+        window_id: context.window_id,
+        timestamp_ns: host_time_to_nanos(now, context.timebase),
+        target_timestamp_ns: host_time_to_nanos(output_time, context.timebase),
+    });
+    if sent.is_err() {
+        context.pending.store(false, Ordering::Release);
+    }
+    0
+}
@@ -0,0 +1,64 @@
+//! Process DPI awareness on Windows.
+//!
+//! tao makes the process per-monitor (v2) aware when the event loop is created. Hosts such
+//! as the JVM may already have picked an awareness, which cannot be changed afterwards, so
+//! TaoKt lets apps choose it and report what is actually in effect.
+
+/// How Windows scales the process's windows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum DpiAwareness {
+    /// Bitmap-stretched by the system; always reports a scale factor of 1.
+    Unaware,
+    /// Scaled for the primary monitor at login; blurry on other monitors.
+    System,
+    /// Scale factor follows the monitor; non-client areas are not scaled.
+    PerMonitor,
+    /// Like `PerMonitor`, with scaled title bars, menus and dialogs.
+    PerMonitorV2,
+}
+
+/// Sets the process awareness; fails when it was already set, e.g. by the host or a manifest.
+#[cfg(target_os = "windows")]
+pub(crate) fn set_dpi_awareness(awareness: DpiAwareness) -> bool {
+    use windows_sys::Win32::UI::HiDpi::{
+        SetProcessDpiAwarenessContext, DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE,
+        DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2, DPI_AWARENESS_CONTEXT_SYSTEM_AWARE, DPI_AWARENESS_CONTEXT_UNAWARE,
+    };
+
+    let context = match awareness {
+        DpiAwareness::Unaware => DPI_AWARENESS_CONTEXT_UNAWARE,
+        DpiAwareness::System => DPI_AWARENESS_CONTEXT_SYSTEM_AWARE,
+        DpiAwareness::PerMonitor => DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE,
+        DpiAwareness::PerMonitorV2 => DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2,
+    };
+    unsafe { SetProcessDpiAwarenessContext(context) != 0 }
+}
+
+/// The DPI awareness in effect for the calling thread (Windows only; `None` elsewhere).
+#[uniffi::export]
+pub fn dpi_awareness() -> Option<DpiAwareness> {
+    #[cfg(target_os = "windows")]
+    {
+        use windows_sys::Win32::UI::HiDpi::{
+            AreDpiAwarenessContextsEqual, GetAwarenessFromDpiAwarenessContext, GetThreadDpiAwarenessContext,
+            DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2, DPI_AWARENESS_PER_MONITOR_AWARE, DPI_AWARENESS_SYSTEM_AWARE,
+            DPI_AWARENESS_UNAWARE,
+        };
+
+        unsafe {
+            let context = GetThreadDpiAwarenessContext();
+            if AreDpiAwarenessContextsEqual(context, DPI_AWARENESS_CONTEXT_PER_MONITOR_AWARE_V2) != 0 {
+                return Some(DpiAwareness::PerMonitorV2);
+            }
+            return match GetAwarenessFromDpiAwarenessContext(context) {
+                DPI_AWARENESS_UNAWARE => Some(DpiAwareness::Unaware),
+                DPI_AWARENESS_SYSTEM_AWARE => Some(DpiAwareness::System),
+                DPI_AWARENESS_PER_MONITOR_AWARE => Some(DpiAwareness::PerMonitor),
+                _ => None,
+            };
+        }
+    }
+
+    #[allow(unreachable_code)]
+    None
+}
@@ -0,0 +1,98 @@
+//! Dragging data out of a window into other applications, backed by the drag crate.
+
+use std::{io::Cursor, path::PathBuf, sync::Arc};
+
+use crate::{events::NativeEventQueue, Icon, TaoError, TaoWindowEvent};
+
+/// What a drag started with `Window::start_drag` carries.
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Enum)]
+pub enum DragData {
+    /// Files, e.g. to copy into Finder or Explorer.
+    Files { paths: Vec<String> },
+    /// Plain text (macOS only).
+    Text { text: String },
+}
+
+impl From<drag::Error> for TaoError {
+    fn from(value: drag::Error) -> Self {
+        TaoError::message(value.to_string())
+    }
+}
+
+/// PNG shown under the cursor: `icon`, or a transparent pixel.
+pub(crate) fn drag_image_png(icon: Option<&Icon>) -> Result<Vec<u8>, TaoError> {
+    let (rgba, width, height) = match icon {
+        Some(icon) => icon
+            .rgba()
+            .ok_or_else(|| TaoError::message("Icons loaded from native resources cannot be used as drag images"))?,
+        None => (vec![0; 4], 1, 1),
+    };
+    let image = image::RgbaImage::from_raw(width, height, rgba)
+        .ok_or_else(|| TaoError::message("Drag image has the wrong number of pixels"))?;
+    let mut png = Vec::new();
+    image
+        .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+        .map_err(|e| TaoError::message(e.to_string()))?;
+    Ok(png)
+}
+
+fn drag_item(data: DragData) -> Result<drag::DragItem, TaoError> {
+    match data {
+        DragData::Files { paths } if paths.is_empty() => Err(TaoError::message("Nothing to drag")),
+        DragData::Files { paths } => Ok(drag::DragItem::Files(paths.into_iter().map(PathBuf::from).collect())),
+        #[cfg(target_os = "macos")]
+        DragData::Text { text } => Ok(drag::DragItem::Data {
+            provider: Box::new(move |_| Some(text.as_bytes().to_vec())),
+            types: vec!["public.utf8-plain-text".to_string()],
+        }),
+        #[cfg(not(target_os = "macos"))]
+        DragData::Text { .. } => Err(TaoError::Unsupported),
+    }
+}
+
+/// Starts an OS drag session from `window`; `queue` receives `TaoWindowEvent::DragFinished`.
+///
+/// On Windows the drag runs a modal loop until the drop, so the window is only locked to read its
+/// handle; other calls on the window can proceed meanwhile.
+pub(crate) fn start_drag(
+    window: &Arc<crate::Window>,
+    data: DragData,
+    drag_image: Option<Arc<Icon>>,
+    queue: &NativeEventQueue,
+) -> Result<(), TaoError> {
+    let item = drag_item(data)?;
+    let image = drag::Image::Raw(drag_image_png(drag_image.as_deref())?);
+    let queue = Arc::downgrade(queue);
+    let on_drop = move |result: drag::DragResult, _: drag::CursorPosition| {
+        if let Some(queue) = queue.upgrade() {
+            let dropped = matches!(result, drag::DragResult::Dropped);
+            queue.lock().unwrap().push(TaoWindowEvent::DragFinished { dropped });
+        }
+    };
+
+    #[cfg(target_os = "windows")]
+    {
+        let source = crate::graphics::WindowHandleSource(window.clone());
+        drag::start_drag(&source, item, image, on_drop, drag::Options::default())?;
+        return Ok(());
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    {
+        use tao::platform::unix::WindowExtUnix;
+        drag::start_drag(window.inner.lock().unwrap().gtk_window(), item, image, on_drop, drag::Options::default())?;
+        return Ok(());
+    }
+
+    #[allow(unreachable_code)]
+    {
+        drag::start_drag(&*window.inner.lock().unwrap(), item, image, on_drop, drag::Options::default())?;
+        Ok(())
+    }
+}
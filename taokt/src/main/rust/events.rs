@@ -1,23 +1,90 @@
-use std::path::PathBuf;
+use std::{
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
 
 use crate::{
-    ElementState, Key, KeyCode, ModifiersState, MouseButton, MouseScrollDelta, PhysicalPositionF64,
-    PhysicalPositionI32, TaoError, Theme,
+    intern_variant, lock_keys_state, AccessibilityPreferences, ElementState, GesturePhase, Key, KeyCode, KeyLocation,
+    LockKeysState, ModifiersState, MouseButton, MouseScrollDelta, PenInput, PhysicalPositionF64, PhysicalPositionI32,
+    PhysicalRect, PhysicalSizeU32, SessionEndReason, ShortcutState, SurfaceColorInfo, TaoError, Theme, WindowInsets,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, uniffi::Enum)]
 pub enum TaoUserEvent {
-    Timer,
+    /// A timer set with `App::set_timer` expired.
+    Timer { id: u64 },
     Message { value: String },
 }
 
+/// Message type carried by the underlying tao event loop.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum LoopMessage {
+    /// One or more events are waiting in the user-event queue.
+    UserEvents,
+    /// URLs forwarded with `EventLoopProxy::open_urls`.
+    OpenUrls(Vec<String>),
+    /// Exit requested with `App::request_exit` or `EventLoopProxy::request_exit`.
+    Exit,
+    /// Wakes the loop, e.g. for a suspended async handler or a newly set timer.
+    Resume,
+    /// Tasks were queued with `App::run_on_event_loop`.
+    RunTasks,
+    /// Window setters were called from another thread.
+    WindowCommands,
+    /// An event passed to `App::inject_event`.
+    Inject(Box<TaoEvent>),
+    /// A menu item with this id was activated.
+    Menu(String),
+    /// Jump list item forwarded with `EventLoopProxy::activate_jump_list_task`.
+    JumpListTask(String),
+    /// A global shortcut with this id changed state.
+    GlobalShortcut(u32, ShortcutState),
+    /// A notification shown with `App::show_notification` was clicked.
+    NotificationActivated {
+        id: String,
+        action: Option<String>,
+        window_id: Option<u64>,
+    },
+    /// The system idle time crossed the threshold set with `App::set_idle_threshold`.
+    IdleChanged { idle: bool, idle_seconds: f64 },
+    /// The accessibility preferences changed while `App::set_watch_accessibility` is on.
+    AccessibilityChanged(AccessibilityPreferences),
+    /// The user is logging out or shutting down.
+    SessionEnding(SessionEndReason),
+    /// A `SessionEndBlock` was taken or released; the windows need the new block reason (Windows).
+    SessionBlocksChanged,
+    /// macOS asked to quit while quit interception is on.
+    QuitRequested,
+    /// The Android back button or gesture, while `App::set_handle_back` is on.
+    BackPressed,
+    /// `TaoActivity` reported new window insets.
+    InsetsChanged(WindowInsets),
+    /// `TaoActivity` was rotated or resized.
+    ConfigurationChanged { size: PhysicalSizeU32, rotation_degrees: u32 },
+    /// The on-screen keyboard appeared, disappeared or changed height.
+    SoftKeyboardChanged { visible: bool, height: u32 },
+    /// The settings portal reported a new color scheme (Linux).
+    ColorSchemeChanged(Theme),
+    /// Received on the channel opened with `App::listen_ipc`.
+    IpcMessage(Vec<u8>),
+    /// The display configuration or color settings changed (Windows, macOS).
+    DisplaySettingsChanged,
+    /// A display link tick for a window (macOS).
+    DisplayLinkFrame {
+        window_id: u64,
+        timestamp_ns: u64,
+        target_timestamp_ns: u64,
+    },
+}
+
 #[derive(Debug, Clone, PartialEq, Eq, uniffi::Enum)]
 pub enum TaoStartCause {
     Init,
     Poll,
     WaitCancelled,
     ResumeTimeReached,
-    Other { value: String },
+    /// A variant without a dedicated mapping; see `describe_other`.
+    Other { code: u32 },
 }
 
 impl From<tao::event::StartCause> for TaoStartCause {
@@ -27,9 +94,7 @@ impl From<tao::event::StartCause> for TaoStartCause {
             tao::event::StartCause::Poll => TaoStartCause::Poll,
             tao::event::StartCause::WaitCancelled { .. } => TaoStartCause::WaitCancelled,
             tao::event::StartCause::ResumeTimeReached { .. } => TaoStartCause::ResumeTimeReached,
-            other => TaoStartCause::Other {
-                value: format!("{other:?}"),
-            },
+            other => TaoStartCause::Other { code: intern_variant(&other) },
         }
     }
 }
@@ -53,7 +118,14 @@ impl From<tao::event::RawKeyEvent> for RawKeyEvent {
 pub struct KeyEvent {
     pub physical_key: KeyCode,
     pub logical_key: Key,
+    /// Text produced by this key press, if any.
+    pub text: Option<String>,
+    pub location: KeyLocation,
     pub state: ElementState,
+    /// True when the event was generated by the key being held down.
+    pub repeat: bool,
+    /// Platform scancode of the physical key, when it has one.
+    pub scancode: Option<u32>,
 }
 
 impl From<tao::event::KeyEvent> for KeyEvent {
@@ -61,80 +133,161 @@ impl From<tao::event::KeyEvent> for KeyEvent {
         Self {
             physical_key: value.physical_key.into(),
             logical_key: value.logical_key.into(),
+            text: value.text.map(|t| t.to_string()),
+            location: value.location.into(),
             state: value.state.into(),
+            repeat: value.repeat,
+            scancode: value.physical_key.to_scancode(),
         }
     }
 }
 
+/// Maps native tao identifiers to the stable numeric ids exposed over FFI.
+pub(crate) trait IdMap {
+    fn window_id(&self, id: tao::window::WindowId) -> u64;
+    fn device_id(&self, id: tao::event::DeviceId) -> u64;
+}
+
 #[derive(Debug, Clone, PartialEq, uniffi::Enum)]
 pub enum TaoWindowEvent {
+    Resized { size: PhysicalSizeU32 },
+    ScaleFactorChanged {
+        scale_factor: f64,
+        new_inner_size: PhysicalSizeU32,
+    },
+    /// Synthesized after a resize that changed the maximized state.
+    ///
+    /// `user_initiated` is false when the change was requested through `Window::set_maximized`,
+    /// and true for caption buttons, keyboard shortcuts or title bar double clicks.
+    MaximizedChanged { maximized: bool, user_initiated: bool },
+    /// Synthesized when the window's monitor refresh rate changes, e.g. after moving it
+    /// between a 60 Hz and a 144 Hz display. `None` when the new rate is unknown.
+    RefreshRateChanged { refresh_rate_millihertz: Option<u32> },
+    /// Synthesized on Wayland when the compositor's preferred fractional scale changes
+    /// (wp_fractional_scale_v1). See `Window::wayland_scale`.
+    FractionalScaleChanged { scale_factor: f64 },
+    /// Synthesized when the color capabilities of the window's display change, e.g. when
+    /// HDR is toggled or the window moves to a wide-gamut display.
+    ColorInfoChanged { color_info: SurfaceColorInfo },
     CloseRequested,
     Destroyed,
     DroppedFile { path: String },
-    KeyboardInput { event: KeyEvent },
-    ModifiersChanged { modifiers: ModifiersState },
-    CursorMoved { position: PhysicalPositionF64 },
-    CursorEntered,
+    /// A drag started with `Window::start_drag` ended; `dropped` is false if it was cancelled.
+    DragFinished { dropped: bool },
+    KeyboardInput { device_id: u64, event: KeyEvent },
+    ModifiersChanged {
+        modifiers: ModifiersState,
+        lock_keys: LockKeysState,
+    },
+    /// Synthesized when Caps Lock or Num Lock toggled without a `ModifiersChanged`, as noticed on
+    /// the next key event or when the window gains focus.
+    LockKeysChanged { lock_keys: LockKeysState },
+    CursorMoved { device_id: u64, position: PhysicalPositionF64 },
+    CursorEntered { device_id: u64 },
     MouseInput {
+        device_id: u64,
         state: ElementState,
         button: MouseButton,
+        /// 1 for a single click, 2 for a double click, and so on. Releases repeat the press count.
+        click_count: u32,
     },
     Moved { position: PhysicalPositionI32 },
+    /// Pen or stylus input with pressure and tilt. Pens also drive the mouse or touch events
+    /// tao reports; handle one or the other.
+    PenInput { input: PenInput },
+    /// Trackpad pinch (macOS, Linux). `delta` is the change in magnification since the last
+    /// event, positive when zooming in (0.1 means 10% larger).
+    PinchGesture { delta: f64, phase: GesturePhase },
+    /// Trackpad two-finger rotation (macOS, Linux) in degrees, positive counterclockwise.
+    RotateGesture { delta: f64, phase: GesturePhase },
+    /// Two-finger double tap on the trackpad (macOS only).
+    SmartZoom,
     ThemeChanged { theme: Theme },
-    Other { value: String },
+    /// The client-side decorations (GTK header bar) were clicked (Linux only).
+    DecorationsClick,
+    /// A variant without a dedicated mapping; see `describe_other`.
+    Other { code: u32 },
 }
 
+/// Window events TaoKt reads from native events that tao does not forward, in arrival order.
+/// The event loop drains them once per iteration.
+pub(crate) type NativeEventQueue = Arc<Mutex<Vec<TaoWindowEvent>>>;
+
 fn path_to_string(path: PathBuf) -> String {
     path.to_string_lossy().to_string()
 }
 
-impl From<tao::event::WindowEvent<'_>> for TaoWindowEvent {
-    fn from(value: tao::event::WindowEvent<'_>) -> Self {
+impl TaoWindowEvent {
+    pub(crate) fn convert(value: tao::event::WindowEvent<'_>, ids: &impl IdMap) -> Self {
         use tao::event::WindowEvent as NativeWindowEvent;
         match value {
+            NativeWindowEvent::Resized(size) => TaoWindowEvent::Resized { size: size.into() },
+            NativeWindowEvent::ScaleFactorChanged {
+                scale_factor,
+                new_inner_size,
+            } => TaoWindowEvent::ScaleFactorChanged {
+                scale_factor,
+                new_inner_size: (*new_inner_size).into(),
+            },
             NativeWindowEvent::CloseRequested => TaoWindowEvent::CloseRequested,
             NativeWindowEvent::Destroyed => TaoWindowEvent::Destroyed,
             NativeWindowEvent::DroppedFile(path) => TaoWindowEvent::DroppedFile {
                 path: path_to_string(path),
             },
-            NativeWindowEvent::KeyboardInput { event, .. } => TaoWindowEvent::KeyboardInput {
+            NativeWindowEvent::KeyboardInput { device_id, event, .. } => TaoWindowEvent::KeyboardInput {
+                device_id: ids.device_id(device_id),
                 event: event.into(),
             },
             NativeWindowEvent::ModifiersChanged(modifiers) => TaoWindowEvent::ModifiersChanged {
                 modifiers: modifiers.into(),
+                lock_keys: lock_keys_state(),
             },
-            NativeWindowEvent::CursorMoved { position, .. } => TaoWindowEvent::CursorMoved {
+            NativeWindowEvent::CursorMoved { device_id, position, .. } => TaoWindowEvent::CursorMoved {
+                device_id: ids.device_id(device_id),
                 position: position.into(),
             },
-            NativeWindowEvent::CursorEntered { .. } => TaoWindowEvent::CursorEntered,
-            NativeWindowEvent::MouseInput { state, button, .. } => TaoWindowEvent::MouseInput {
+            NativeWindowEvent::CursorEntered { device_id } => TaoWindowEvent::CursorEntered {
+                device_id: ids.device_id(device_id),
+            },
+            NativeWindowEvent::MouseInput {
+                device_id,
+                state,
+                button,
+                ..
+            } => TaoWindowEvent::MouseInput {
+                device_id: ids.device_id(device_id),
                 state: state.into(),
                 button: button.into(),
+                click_count: 1,
             },
             NativeWindowEvent::Moved(position) => TaoWindowEvent::Moved {
                 position: position.into(),
             },
             NativeWindowEvent::ThemeChanged(theme) => TaoWindowEvent::ThemeChanged { theme: theme.into() },
-            other => TaoWindowEvent::Other {
-                value: format!("{other:?}"),
-            },
+            NativeWindowEvent::DecorationsClick => TaoWindowEvent::DecorationsClick,
+            other => TaoWindowEvent::Other { code: intern_variant(&other) },
         }
     }
 }
 
 #[derive(Debug, Clone, PartialEq, uniffi::Enum)]
 pub enum TaoDeviceEvent {
+    Added,
+    Removed,
     MouseMotion { delta_x: f64, delta_y: f64 },
     MouseWheel { delta: MouseScrollDelta },
     Button { button: u32, state: ElementState },
     Key { event: RawKeyEvent },
-    Other { value: String },
+    /// A variant without a dedicated mapping; see `describe_other`.
+    Other { code: u32 },
 }
 
 impl From<tao::event::DeviceEvent> for TaoDeviceEvent {
     fn from(value: tao::event::DeviceEvent) -> Self {
         use tao::event::DeviceEvent as NativeDeviceEvent;
         match value {
+            NativeDeviceEvent::Added => TaoDeviceEvent::Added,
+            NativeDeviceEvent::Removed => TaoDeviceEvent::Removed,
             NativeDeviceEvent::MouseMotion { delta, .. } => TaoDeviceEvent::MouseMotion {
                 delta_x: delta.0,
                 delta_y: delta.1,
@@ -147,9 +300,7 @@ impl From<tao::event::DeviceEvent> for TaoDeviceEvent {
                 state: state.into(),
             },
             NativeDeviceEvent::Key(event) => TaoDeviceEvent::Key { event: event.into() },
-            other => TaoDeviceEvent::Other {
-                value: format!("{other:?}"),
-            },
+            other => TaoDeviceEvent::Other { code: intern_variant(&other) },
         }
     }
 }
@@ -158,22 +309,155 @@ impl From<tao::event::DeviceEvent> for TaoDeviceEvent {
 pub enum TaoEvent {
     NewEvents { cause: TaoStartCause },
     WindowEvent { window_id: u64, event: TaoWindowEvent },
-    DeviceEvent { event: TaoDeviceEvent },
+    DeviceEvent { device_id: u64, event: TaoDeviceEvent },
     UserEvent { event: TaoUserEvent },
     MainEventsCleared,
-    RedrawRequested { window_id: u64 },
+    /// `dirty_rects` holds the areas passed to `Window::request_redraw_rect` since the last
+    /// redraw. Empty means the whole window must be repainted: a full redraw was requested,
+    /// or the system asked for one (e.g. after a resize).
+    RedrawRequested { window_id: u64, dirty_rects: Vec<PhysicalRect> },
+    /// A Wayland frame callback requested with `Window::request_frame_callback`, or by a frame paced with
+    /// `Window::request_redraw_at_next_vblank`, fired.
+    ///
+    /// `timestamp_ms` is the compositor's clock in milliseconds, with an undefined base.
+    FramePresented { window_id: u64, timestamp_ms: u32 },
+    /// The display showing a window with `Window::set_display_link(true, true)` refreshed (macOS).
+    /// Delivered before the `RedrawRequested` of a pending `request_redraw_at_next_vblank`.
+    ///
+    /// Both timestamps are host time (`mach_absolute_time`) in nanoseconds: `timestamp_ns` is this
+    /// refresh and `target_timestamp_ns` when the frame drawn now will reach the screen.
+    DisplayLinkFrame {
+        window_id: u64,
+        timestamp_ns: u64,
+        target_timestamp_ns: u64,
+    },
     RedrawEventsCleared,
+    /// The application went to the background (Android, iOS). On Android the window surface is
+    /// destroyed: drop graphics surfaces created for it.
+    Suspended,
+    /// The application came to the foreground (Android, iOS). On Android the window surface is
+    /// available again: create graphics surfaces now.
+    Resumed,
     Reopen { has_visible_windows: bool },
+    /// A monitor's scale factor changed, e.g. after the user changed display scaling.
+    ///
+    /// Sent whether or not a window is on that monitor; windows still get `ScaleFactorChanged`.
+    MonitorScaleChanged {
+        monitor_name: Option<String>,
+        position: PhysicalPositionI32,
+        scale_factor: f64,
+        previous_scale_factor: f64,
+    },
+    /// The application was asked to open URLs (custom schemes, files on macOS/iOS).
+    Opened { urls: Vec<String> },
+    /// A menu item created with `id` was activated.
+    MenuEvent { id: String },
+    /// A jump list item with `id` was clicked; see `EventLoopProxy::activate_jump_list_task`.
+    JumpListTask { id: String },
+    /// A shortcut registered with `GlobalShortcutManager::register` was pressed or released,
+    /// whether or not the application has focus.
+    GlobalShortcut { id: u32, state: ShortcutState },
+    /// A notification shown with `App::show_notification` was clicked. `action` is the id of
+    /// the button clicked, or `None` for the notification itself.
+    NotificationActivated {
+        id: String,
+        action: Option<String>,
+        window_id: Option<u64>,
+    },
+    /// No input device was used anywhere on the system for the threshold set with
+    /// `App::set_idle_threshold` (`idle`), or input resumed after that.
+    IdleChanged { idle: bool, idle_seconds: f64 },
+    /// The user changed high contrast, reduce motion or reduce transparency; see
+    /// `App::set_watch_accessibility`.
+    AccessibilityChanged { preferences: AccessibilityPreferences },
+    /// The user is logging out or shutting down (Windows, Linux). Save unsaved work now; hold
+    /// a `SessionEndBlock` (see `App::block_session_end`) beforehand to get time for it.
+    SessionEnding { reason: SessionEndReason },
+    /// Cmd+Q, Quit in the Dock or a `PredefinedMenuItem` quit was cancelled because of
+    /// `App::set_intercept_quit` (macOS only). Call `App::request_exit` to quit after all.
+    QuitRequested,
+    /// The Android back button or gesture was consumed for the application; see
+    /// `App::set_handle_back`. Navigate back within the UI.
+    BackPressed,
+    /// The system bars, display cutout or on-screen keyboard now cover a different part of the
+    /// window (Android).
+    InsetsChanged { insets: WindowInsets },
+    /// The device was rotated or the window resized by a configuration change (Android).
+    /// `rotation_degrees` is clockwise from the natural orientation: 0, 90, 180 or 270.
+    ConfigurationChanged { size: PhysicalSizeU32, rotation_degrees: u32 },
+    /// The on-screen keyboard appeared, disappeared or changed height (Android, iOS). `height`
+    /// is the part of the window it covers from the bottom, in physical pixels.
+    SoftKeyboardChanged { visible: bool, height: u32 },
+    /// Another process sent `data` with `send_ipc_message` to the channel opened with
+    /// `App::listen_ipc`.
+    IpcMessage { data: Vec<u8> },
     LoopDestroyed,
-    Other { value: String },
+    /// The handler took longer than the configured stall threshold for an event of `event_kind`.
+    HandlerStall { event_kind: String, duration_ms: u64 },
+    /// The handler, an event listener or a task queued with `App::run_on_event_loop` panicked or
+    /// threw; the loop keeps running.
+    HandlerError { message: String },
+    /// A variant without a dedicated mapping; see `describe_other`.
+    Other { code: u32 },
+}
+
+impl TaoEvent {
+    /// The window a window or redraw event belongs to.
+    pub(crate) fn window_id(&self) -> Option<u64> {
+        match self {
+            TaoEvent::WindowEvent { window_id, .. }
+            | TaoEvent::RedrawRequested { window_id, .. }
+            | TaoEvent::FramePresented { window_id, .. }
+            | TaoEvent::DisplayLinkFrame { window_id, .. } => Some(*window_id),
+            _ => None,
+        }
+    }
+}
+
+/// Event classes dropped before they reach the handler. `true` means suppressed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, uniffi::Record)]
+pub struct EventMask {
+    #[uniffi(default = false)]
+    pub device_mouse_motion: bool,
+    #[uniffi(default = false)]
+    pub cursor_moved: bool,
+    #[uniffi(default = false)]
+    pub redraw_events_cleared: bool,
+    /// Events, window events and device events without a dedicated mapping.
+    #[uniffi(default = false)]
+    pub other: bool,
+}
+
+impl EventMask {
+    pub(crate) fn suppresses(&self, event: &TaoEvent) -> bool {
+        match event {
+            TaoEvent::DeviceEvent {
+                event: TaoDeviceEvent::MouseMotion { .. },
+                ..
+            } => self.device_mouse_motion,
+            TaoEvent::WindowEvent {
+                event: TaoWindowEvent::CursorMoved { .. },
+                ..
+            } => self.cursor_moved,
+            TaoEvent::RedrawEventsCleared => self.redraw_events_cleared,
+            TaoEvent::Other { .. }
+            | TaoEvent::WindowEvent {
+                event: TaoWindowEvent::Other { .. },
+                ..
+            }
+            | TaoEvent::DeviceEvent {
+                event: TaoDeviceEvent::Other { .. },
+                ..
+            } => self.other,
+            _ => false,
+        }
+    }
 }
 
-pub(crate) fn convert_event<F>(
-    event: tao::event::Event<'_, TaoUserEvent>,
-    map_window_id: F,
-) -> TaoEvent
+/// Converts every native event except `UserEvent`, which the caller expands from the user-event queue.
+pub(crate) fn convert_event<T>(event: tao::event::Event<'_, T>, ids: &impl IdMap) -> TaoEvent
 where
-    F: Fn(tao::window::WindowId) -> u64,
+    T: std::fmt::Debug,
 {
     use tao::event::Event as NativeEvent;
     match event {
@@ -183,24 +467,31 @@ where
         NativeEvent::WindowEvent {
             window_id, event, ..
         } => TaoEvent::WindowEvent {
-            window_id: map_window_id(window_id),
+            window_id: ids.window_id(window_id),
+            event: TaoWindowEvent::convert(event, ids),
+        },
+        NativeEvent::DeviceEvent { device_id, event, .. } => TaoEvent::DeviceEvent {
+            device_id: ids.device_id(device_id),
             event: event.into(),
         },
-        NativeEvent::DeviceEvent { event, .. } => TaoEvent::DeviceEvent { event: event.into() },
-        NativeEvent::UserEvent(event) => TaoEvent::UserEvent { event },
         NativeEvent::MainEventsCleared => TaoEvent::MainEventsCleared,
         NativeEvent::RedrawRequested(window_id) => TaoEvent::RedrawRequested {
-            window_id: map_window_id(window_id),
+            window_id: ids.window_id(window_id),
+            // Filled in by the app, which tracks the requested areas.
+            dirty_rects: Vec::new(),
         },
         NativeEvent::RedrawEventsCleared => TaoEvent::RedrawEventsCleared,
+        NativeEvent::Suspended => TaoEvent::Suspended,
+        NativeEvent::Resumed => TaoEvent::Resumed,
         NativeEvent::Reopen {
             has_visible_windows,
             ..
         } => TaoEvent::Reopen { has_visible_windows },
-        NativeEvent::LoopDestroyed => TaoEvent::LoopDestroyed,
-        other => TaoEvent::Other {
-            value: format!("{other:?}"),
+        NativeEvent::Opened { urls } => TaoEvent::Opened {
+            urls: urls.into_iter().map(|url| url.to_string()).collect(),
         },
+        NativeEvent::LoopDestroyed => TaoEvent::LoopDestroyed,
+        other => TaoEvent::Other { code: intern_variant(&other) },
     }
 }
 
@@ -0,0 +1,237 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// Refresh rate assumed for frame pacing when no `target_fps` is configured.
+const DEFAULT_FPS: u32 = 60;
+
+/// Spaces out redraws requested with `Window::request_redraw_at_next_vblank`.
+pub(crate) struct FramePacer {
+    interval: Duration,
+    last_frame: Option<Instant>,
+    /// Whether the interval came from `target_fps` rather than the display.
+    fixed: bool,
+}
+
+impl FramePacer {
+    pub(crate) fn new(target_fps: Option<u32>) -> Self {
+        let fps = target_fps.unwrap_or(DEFAULT_FPS).max(1);
+        Self {
+            interval: Duration::from_secs(1) / fps,
+            last_frame: None,
+            fixed: target_fps.is_some(),
+        }
+    }
+
+    /// Follows the display's refresh rate unless a `target_fps` was configured.
+    pub(crate) fn set_refresh_rate(&mut self, millihertz: u32) {
+        if self.fixed || millihertz == 0 {
+            return;
+        }
+        self.interval = Duration::from_secs_f64(1000.0 / millihertz as f64);
+    }
+
+    /// When the next frame may start; never earlier than `now`.
+    pub(crate) fn next_frame(&self, now: Instant) -> Instant {
+        match self.last_frame {
+            Some(last) => (last + self.interval).max(now),
+            None => now,
+        }
+    }
+
+    pub(crate) fn interval(&self) -> Duration {
+        self.interval
+    }
+
+    pub(crate) fn is_due(&self, now: Instant) -> bool {
+        self.next_frame(now) <= now
+    }
+
+    /// Puts the cadence in phase with the display, given its latest vertical blank at or before
+    /// the frame started at `now`.
+    pub(crate) fn align(&mut self, now: Instant, vblank: Instant) {
+        if !self.fixed && vblank <= now && now.duration_since(vblank) < self.interval {
+            self.last_frame = Some(vblank);
+        }
+    }
+
+    /// Records a frame started at `now`, keeping the cadence when the loop woke up on time.
+    pub(crate) fn start_frame(&mut self, now: Instant) {
+        let start = match self.last_frame {
+            Some(last) if now < last + self.interval * 2 => last + self.interval,
+            _ => now,
+        };
+        self.last_frame = Some(start.min(now));
+    }
+}
+
+/// Time of the compositor's latest vertical blank, from DWM's composition timing (Windows only).
+pub(crate) fn last_vblank(now: Instant) -> Option<Instant> {
+    #[cfg(target_os = "windows")]
+    unsafe {
+        use windows_sys::Win32::{
+            Graphics::Dwm::{DwmGetCompositionTimingInfo, DWM_TIMING_INFO},
+            System::Performance::{QueryPerformanceCounter, QueryPerformanceFrequency},
+        };
+
+        let mut info: DWM_TIMING_INFO = std::mem::zeroed();
+        info.cbSize = std::mem::size_of::<DWM_TIMING_INFO>() as u32;
+        let (mut counter, mut frequency) = (0, 0);
+        if DwmGetCompositionTimingInfo(std::ptr::null_mut(), &mut info) < 0
+            || QueryPerformanceCounter(&mut counter) == 0
+            || QueryPerformanceFrequency(&mut frequency) == 0
+            || frequency <= 0
+        {
+            return None;
+        }
+        // Both are performance counter ticks.
+        let age = (counter as u64).checked_sub(info.qpcVBlank)?;
+        return now.checked_sub(Duration::from_secs_f64(age as f64 / frequency as f64));
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    {
+        let _ = now;
+        None
+    }
+}
+
+/// One `FramePacer` per monitor, for `RunConfig::vsync_groups`: windows on the same monitor
+/// redraw in one batch at that monitor's refresh rate.
+pub(crate) struct FrameGroups {
+    target_fps: Option<u32>,
+    pacers: HashMap<String, FramePacer>,
+}
+
+impl FrameGroups {
+    pub(crate) fn new(target_fps: Option<u32>) -> Self {
+        Self {
+            target_fps,
+            pacers: HashMap::new(),
+        }
+    }
+
+    /// Starts a frame on each monitor in `monitors` (key and refresh rate in millihertz) whose
+    /// slot is reached, and returns their keys. Monitors missing from `monitors` are forgotten.
+    pub(crate) fn start_due(&mut self, now: Instant, monitors: &[(String, u32)]) -> Vec<String> {
+        self.pacers.retain(|key, _| monitors.iter().any(|(monitor, _)| monitor == key));
+        for (key, millihertz) in monitors {
+            let target_fps = self.target_fps;
+            self.pacers
+                .entry(key.clone())
+                .or_insert_with(|| FramePacer::new(target_fps))
+                .set_refresh_rate(*millihertz);
+        }
+        self.pacers
+            .iter_mut()
+            .filter(|(_, pacer)| pacer.is_due(now))
+            .map(|(key, pacer)| {
+                pacer.start_frame(now);
+                key.clone()
+            })
+            .collect()
+    }
+
+    /// When the first of `monitors` may start its next frame; `now` for a monitor without a
+    /// frame yet.
+    pub(crate) fn next_frame<'a>(&self, now: Instant, monitors: impl IntoIterator<Item = &'a str>) -> Option<Instant> {
+        monitors
+            .into_iter()
+            .map(|key| self.pacers.get(key).map_or(now, |pacer| pacer.next_frame(now)))
+            .min()
+    }
+}
+
+/// Timing of a frame passed to `TaoRunReturnHandler::render`.
+#[derive(Debug, Clone, Copy, PartialEq, uniffi::Record)]
+pub struct FrameInfo {
+    /// Seconds since the previous frame; zero for the first one.
+    pub delta_seconds: f64,
+    /// Seconds since the loop started, from a monotonic clock.
+    pub timestamp_seconds: f64,
+    /// Zero-based number of the frame.
+    pub frame_index: u64,
+    /// Expected time between frames, in seconds.
+    pub refresh_interval_seconds: f64,
+}
+
+/// Produces `FrameInfo` for successive frames.
+pub(crate) struct FrameClock {
+    start: Instant,
+    last: Option<Instant>,
+    index: u64,
+}
+
+impl FrameClock {
+    pub(crate) fn new(start: Instant) -> Self {
+        Self {
+            start,
+            last: None,
+            index: 0,
+        }
+    }
+
+    pub(crate) fn tick(&mut self, now: Instant, refresh_interval: Duration) -> FrameInfo {
+        let info = FrameInfo {
+            delta_seconds: self.last.map_or(0.0, |last| now.saturating_duration_since(last).as_secs_f64()),
+            timestamp_seconds: now.saturating_duration_since(self.start).as_secs_f64(),
+            frame_index: self.index,
+            refresh_interval_seconds: refresh_interval.as_secs_f64(),
+        };
+        self.last = Some(now);
+        self.index += 1;
+        info
+    }
+}
+
+/// Raises the Windows timer resolution to 1 ms while the loop waits for a deadline, so
+/// `WaitUntil` wakes up on time instead of on the next 15.6 ms system tick.
+pub(crate) struct TimerResolution {
+    enabled: bool,
+    raised: bool,
+}
+
+impl TimerResolution {
+    pub(crate) fn new(enabled: bool) -> Self {
+        Self { enabled, raised: false }
+    }
+
+    /// Raises or restores the resolution for a loop that is about to wait with `control_flow`.
+    pub(crate) fn update(&mut self, control_flow: &tao::event_loop::ControlFlow) {
+        let raise = self.enabled && matches!(control_flow, tao::event_loop::ControlFlow::WaitUntil(_));
+        if raise != self.raised {
+            set_period(raise);
+            self.raised = raise;
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn raised(&self) -> bool {
+        self.raised
+    }
+}
+
+impl Drop for TimerResolution {
+    fn drop(&mut self) {
+        if self.raised {
+            set_period(false);
+        }
+    }
+}
+
+/// `timeBeginPeriod`/`timeEndPeriod` calls must pair up; `TimerResolution` keeps track.
+fn set_period(raise: bool) {
+    #[cfg(target_os = "windows")]
+    unsafe {
+        use windows_sys::Win32::Media::{timeBeginPeriod, timeEndPeriod};
+        if raise {
+            timeBeginPeriod(1);
+        } else {
+            timeEndPeriod(1);
+        }
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    let _ = raise;
+}
@@ -0,0 +1,207 @@
+//! Trackpad pinch, rotate and smart zoom gestures.
+//!
+//! tao does not report gestures. TaoKt reads them from AppKit gesture events on macOS and
+//! GDK touchpad pinch events on Linux. Windows precision touchpads report pinches as
+//! Ctrl + mouse wheel, which arrives as `MouseWheel` with the control modifier held.
+
+use crate::events::NativeEventQueue;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum GesturePhase {
+    Began,
+    Changed,
+    Ended,
+    Cancelled,
+}
+
+/// Converts a cumulative scale (1.0 at the start of the gesture) into per-event deltas.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    test
+))]
+#[derive(Debug)]
+pub(crate) struct PinchTracker {
+    scale: f64,
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    test
+))]
+impl Default for PinchTracker {
+    fn default() -> Self {
+        Self { scale: 1.0 }
+    }
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    test
+))]
+impl PinchTracker {
+    /// Magnification since the previous update: positive when zooming in, e.g. 0.1 for 10%.
+    pub(crate) fn delta(&mut self, phase: GesturePhase, scale: f64) -> f64 {
+        if phase == GesturePhase::Began {
+            self.scale = 1.0;
+        }
+        if scale <= 0.0 || !scale.is_finite() {
+            return 0.0;
+        }
+        let delta = scale / self.scale - 1.0;
+        self.scale = scale;
+        delta
+    }
+}
+
+/// Starts capturing gestures for `window` into `queue`. Call on the event loop thread.
+pub(crate) fn attach(window: &tao::window::Window, queue: &NativeEventQueue) {
+    #[cfg(target_os = "macos")]
+    appkit::attach(window, queue);
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    gdk::attach(window, queue);
+
+    #[cfg(not(any(
+        target_os = "macos",
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )))]
+    let _ = (window, queue);
+}
+
+#[cfg(target_os = "macos")]
+mod appkit {
+    use std::{ptr::NonNull, sync::Arc};
+
+    use block2::RcBlock;
+    use objc2::{msg_send, runtime::AnyObject};
+    use tao::platform::macos::WindowExtMacOS;
+
+    use super::GesturePhase;
+    use crate::{events::NativeEventQueue, monitor::cocoa::add_window_event_monitor, TaoWindowEvent};
+
+    const ROTATE: usize = 18;
+    const MAGNIFY: usize = 30;
+    const SMART_MAGNIFY: usize = 32;
+
+    /// `NSEventPhase` bits.
+    fn phase(bits: usize) -> GesturePhase {
+        match bits {
+            1 => GesturePhase::Began,
+            8 => GesturePhase::Ended,
+            16 => GesturePhase::Cancelled,
+            _ => GesturePhase::Changed,
+        }
+    }
+
+    pub(super) fn attach(window: &tao::window::Window, queue: &NativeEventQueue) {
+        let ns_window = window.ns_window() as *mut AnyObject;
+        let window_number: isize = unsafe { msg_send![&*ns_window, windowNumber] };
+        let queue = Arc::downgrade(queue);
+        let mask = (1u64 << ROTATE) | (1u64 << MAGNIFY) | (1u64 << SMART_MAGNIFY);
+
+        let handler = RcBlock::new(move |event: NonNull<AnyObject>| -> *mut AnyObject {
+            let event_ptr = event.as_ptr();
+            let event = unsafe { event.as_ref() };
+            let number: isize = unsafe { msg_send![event, windowNumber] };
+            let Some(queue) = queue.upgrade().filter(|_| number == window_number) else {
+                return event_ptr;
+            };
+            let kind: usize = unsafe { msg_send![event, r#type] };
+            let gesture = match kind {
+                MAGNIFY => {
+                    let delta: f64 = unsafe { msg_send![event, magnification] };
+                    let bits: usize = unsafe { msg_send![event, phase] };
+                    TaoWindowEvent::PinchGesture {
+                        delta,
+                        phase: phase(bits),
+                    }
+                }
+                ROTATE => {
+                    let delta: f32 = unsafe { msg_send![event, rotation] };
+                    let bits: usize = unsafe { msg_send![event, phase] };
+                    TaoWindowEvent::RotateGesture {
+                        delta: delta as f64,
+                        phase: phase(bits),
+                    }
+                }
+                _ => TaoWindowEvent::SmartZoom,
+            };
+            queue.lock().unwrap().push(gesture);
+            event_ptr
+        });
+        add_window_event_monitor(ns_window, mask, &handler);
+    }
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+mod gdk {
+    use std::{cell::RefCell, sync::Arc};
+
+    use gtk::{
+        gdk::{EventMask, EventTouchpadPinch, EventType, TouchpadGesturePhase},
+        glib::Propagation,
+        prelude::WidgetExt,
+    };
+    use tao::platform::unix::WindowExtUnix;
+
+    use super::{GesturePhase, PinchTracker};
+    use crate::{events::NativeEventQueue, TaoWindowEvent};
+
+    pub(super) fn attach(window: &tao::window::Window, queue: &NativeEventQueue) {
+        let gtk_window = window.gtk_window();
+        gtk_window.add_events(EventMask::TOUCHPAD_GESTURE_MASK);
+        let queue = Arc::downgrade(queue);
+        let tracker = RefCell::new(PinchTracker::default());
+        gtk_window.connect_event(move |_, event| {
+            if event.event_type() != EventType::TouchpadPinch {
+                return Propagation::Proceed;
+            }
+            let (Some(queue), Some(pinch)) = (queue.upgrade(), event.downcast_ref::<EventTouchpadPinch>()) else {
+                return Propagation::Proceed;
+            };
+            let phase = match pinch.phase() {
+                TouchpadGesturePhase::Begin => GesturePhase::Began,
+                TouchpadGesturePhase::End => GesturePhase::Ended,
+                TouchpadGesturePhase::Cancel => GesturePhase::Cancelled,
+                _ => GesturePhase::Changed,
+            };
+            let delta = tracker.borrow_mut().delta(phase, pinch.scale());
+            let mut events = queue.lock().unwrap();
+            events.push(TaoWindowEvent::PinchGesture { delta, phase });
+            // GDK measures clockwise; report counterclockwise degrees like AppKit.
+            events.push(TaoWindowEvent::RotateGesture {
+                delta: -pinch.angle_delta().to_degrees(),
+                phase,
+            });
+            Propagation::Proceed
+        });
+    }
+}
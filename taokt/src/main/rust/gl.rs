@@ -0,0 +1,293 @@
+//! OpenGL context creation on top of glutin (EGL, WGL or CGL depending on the platform).
+
+use std::{
+    ffi::{c_char, CStr, CString},
+    num::NonZeroU32,
+    sync::{Arc, OnceLock},
+};
+
+use glutin::{
+    config::{Config, ConfigTemplateBuilder},
+    context::{ContextApi, ContextAttributesBuilder, PossiblyCurrentContext, Version},
+    display::{Display, DisplayApiPreference},
+    prelude::*,
+    surface::{Surface, SurfaceAttributesBuilder, SwapInterval, WindowSurface},
+};
+use raw_window_handle::{HasDisplayHandle, HasWindowHandle, RawDisplayHandle, RawWindowHandle};
+
+use crate::{graphics::WindowHandleSource, thread::ThreadBound, SurfaceConfig, TaoError, Window};
+
+/// Requested OpenGL API version; framebuffer options live in `SurfaceConfig`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Record)]
+pub struct GlConfig {
+    #[uniffi(default = 3)]
+    pub major_version: u8,
+    #[uniffi(default = 3)]
+    pub minor_version: u8,
+    /// Request an OpenGL ES context instead of desktop OpenGL.
+    #[uniffi(default = false)]
+    pub gles: bool,
+}
+
+impl Default for GlConfig {
+    fn default() -> Self {
+        Self {
+            major_version: 3,
+            minor_version: 3,
+            gles: false,
+        }
+    }
+}
+
+fn gl_error(error: glutin::error::Error) -> TaoError {
+    TaoError::message(error.to_string())
+}
+
+/// `GL_VERSION` of the first context made current in this process, for `Graphics::probe`.
+static PROBED_VERSION: OnceLock<String> = OnceLock::new();
+
+pub(crate) fn probed_version() -> Option<&'static str> {
+    PROBED_VERSION.get().map(String::as_str)
+}
+
+/// Reads `GL_VERSION` from the context current on this thread.
+fn current_version(display: &Display) -> Option<String> {
+    const GL_VERSION: u32 = 0x1F02;
+    type GetString = unsafe extern "system" fn(u32) -> *const c_char;
+
+    let address = display.get_proc_address(c"glGetString");
+    if address.is_null() {
+        return None;
+    }
+    // Safety: glGetString has this signature, and a context is current.
+    unsafe {
+        let get_string: GetString = std::mem::transmute(address);
+        let version = get_string(GL_VERSION);
+        (!version.is_null()).then(|| CStr::from_ptr(version).to_string_lossy().into_owned())
+    }
+}
+
+struct GlState {
+    display: Display,
+    config: Config,
+    context: PossiblyCurrentContext,
+    surface: Surface<WindowSurface>,
+}
+
+/// An OpenGL context bound to a window surface.
+///
+/// The context is current on the creating thread after `Window::create_gl_context`, and all
+/// methods must be called from that thread. Dropped on another thread, the GL objects are
+/// leaked; see `ThreadBound`.
+#[derive(uniffi::Object)]
+pub struct GlContext {
+    config: GlConfig,
+    surface_config: SurfaceConfig,
+    /// `GL_VERSION` as the driver reported it, which may be newer than requested.
+    version: String,
+    // Keeps the window (and its native surface) alive as long as the context.
+    window: Arc<Window>,
+    state: ThreadBound<GlState>,
+}
+
+impl GlContext {
+    /// Creates a context for `window`, sharing objects with `share` when given.
+    ///
+    /// A shared context reuses the display and framebuffer configuration of `share`, since
+    /// share groups require both.
+    pub(crate) fn new(
+        window: Arc<Window>,
+        config: GlConfig,
+        surface_config: SurfaceConfig,
+        share: Option<&GlContext>,
+    ) -> Result<Self, TaoError> {
+        let source = WindowHandleSource(window.clone());
+        let raw_window = source
+            .window_handle()
+            .map_err(|e| TaoError::message(e.to_string()))?
+            .as_raw();
+
+        let (display, gl_config) = match share {
+            Some(share) => share.with_state(|state| Ok((state.display.clone(), state.config.clone())))?,
+            None => {
+                let raw_display = source
+                    .display_handle()
+                    .map_err(|e| TaoError::message(e.to_string()))?
+                    .as_raw();
+                let display = create_display(raw_display, raw_window)?;
+                let gl_config = choose_config(&display, raw_window, &surface_config)?;
+                (display, gl_config)
+            }
+        };
+
+        let version = Some(Version::new(config.major_version, config.minor_version));
+        let api = if config.gles {
+            ContextApi::Gles(version)
+        } else {
+            ContextApi::OpenGl(version)
+        };
+        let context_attributes = ContextAttributesBuilder::new().with_context_api(api);
+        let context_attributes = match share {
+            Some(share) => share.with_state(|state| Ok(context_attributes.with_sharing(&state.context)))?,
+            None => context_attributes,
+        }
+        .build(Some(raw_window));
+        let context = unsafe { display.create_context(&gl_config, &context_attributes) }.map_err(gl_error)?;
+
+        let size = window.inner_size();
+        let (width, height) = (
+            NonZeroU32::new(size.width).unwrap_or(NonZeroU32::MIN),
+            NonZeroU32::new(size.height).unwrap_or(NonZeroU32::MIN),
+        );
+        let surface_attributes = SurfaceAttributesBuilder::<WindowSurface>::new()
+            .with_srgb(Some(surface_config.srgb))
+            .build(raw_window, width, height);
+        let surface = unsafe { display.create_window_surface(&gl_config, &surface_attributes) }.map_err(gl_error)?;
+        let context = context.make_current(&surface).map_err(gl_error)?;
+        let version = current_version(&display).unwrap_or_default();
+        if !version.is_empty() {
+            let _ = PROBED_VERSION.set(version.clone());
+        }
+
+        let state = GlState {
+            display,
+            config: gl_config,
+            context,
+            surface,
+        };
+        state
+            .surface
+            .set_swap_interval(&state.context, swap_interval(surface_config.swap_interval))
+            .map_err(gl_error)?;
+
+        Ok(Self {
+            config,
+            surface_config,
+            version,
+            window,
+            state: ThreadBound::new(state),
+        })
+    }
+
+    fn with_state<R>(&self, f: impl FnOnce(&GlState) -> Result<R, TaoError>) -> Result<R, TaoError> {
+        let state = self
+            .state
+            .get()
+            .map_err(|_| TaoError::message("GlContext must be used on the thread that created it"))?;
+        f(state)
+    }
+}
+
+fn create_display(raw_display: RawDisplayHandle, raw_window: RawWindowHandle) -> Result<Display, TaoError> {
+    #[cfg(target_os = "windows")]
+    let preference = DisplayApiPreference::WglThenEgl(Some(raw_window));
+    #[cfg(target_os = "macos")]
+    let preference = DisplayApiPreference::Cgl;
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let preference = DisplayApiPreference::Egl;
+    #[cfg(not(target_os = "windows"))]
+    let _ = raw_window;
+
+    // Safety: the handles belong to a window that outlives the display.
+    unsafe { Display::new(raw_display, preference) }.map_err(gl_error)
+}
+
+fn choose_config(display: &Display, raw_window: RawWindowHandle, config: &SurfaceConfig) -> Result<Config, TaoError> {
+    let template = ConfigTemplateBuilder::new()
+        .with_alpha_size(8)
+        .with_depth_size(config.depth_bits)
+        .with_stencil_size(config.stencil_bits)
+        .compatible_with_native_window(raw_window);
+    let template = if config.samples > 0 {
+        template.with_multisampling(config.samples)
+    } else {
+        template
+    };
+    unsafe { display.find_configs(template.build()) }
+        .map_err(gl_error)?
+        .reduce(|best, candidate| {
+            if candidate.num_samples() > best.num_samples() {
+                candidate
+            } else {
+                best
+            }
+        })
+        .ok_or_else(|| TaoError::message("No matching OpenGL configuration"))
+}
+
+fn swap_interval(interval: u32) -> SwapInterval {
+    match NonZeroU32::new(interval) {
+        Some(interval) => SwapInterval::Wait(interval),
+        None => SwapInterval::DontWait,
+    }
+}
+
+#[uniffi::export]
+impl GlContext {
+    pub fn make_current(&self) -> Result<(), TaoError> {
+        self.with_state(|state| state.context.make_current(&state.surface).map_err(gl_error))
+    }
+
+    pub fn swap_buffers(&self) -> Result<(), TaoError> {
+        self.with_state(|state| state.surface.swap_buffers(&state.context).map_err(gl_error))
+    }
+
+    /// Sets the vertical blanks to wait per swap; 0 disables vsync.
+    pub fn set_swap_interval(&self, interval: u32) -> Result<(), TaoError> {
+        self.with_state(|state| {
+            state
+                .surface
+                .set_swap_interval(&state.context, swap_interval(interval))
+                .map_err(gl_error)
+        })
+    }
+
+    /// Resizes the surface; call it on `Resized` events.
+    pub fn resize(&self, width: u32, height: u32) -> Result<(), TaoError> {
+        let (Some(width), Some(height)) = (NonZeroU32::new(width), NonZeroU32::new(height)) else {
+            return Ok(());
+        };
+        self.with_state(|state| {
+            state.surface.resize(&state.context, width, height);
+            Ok(())
+        })
+    }
+
+    /// Address of the GL function `name`, or zero if it is not available.
+    pub fn get_proc_address(&self, name: String) -> Result<u64, TaoError> {
+        let name = CString::new(name).map_err(|e| TaoError::message(e.to_string()))?;
+        self.with_state(|state| Ok(state.display.get_proc_address(&name) as u64))
+    }
+
+    /// The `GL_VERSION` string, e.g. "4.6.0 NVIDIA 550.54" or "OpenGL ES 3.2 Mesa 24.0"; empty if
+    /// the driver did not report one.
+    pub fn version(&self) -> String {
+        self.version.clone()
+    }
+
+    pub fn window(&self) -> Arc<Window> {
+        self.window.clone()
+    }
+}
+
+#[uniffi::export]
+impl Window {
+    /// Creates an OpenGL context for this window and makes it current on the calling thread.
+    ///
+    /// `surface_config.triple_buffering` is left to the driver.
+    pub fn create_gl_context(
+        self: Arc<Self>,
+        config: GlConfig,
+        surface_config: SurfaceConfig,
+    ) -> Result<Arc<GlContext>, TaoError> {
+        GlContext::new(self, config, surface_config, None).map(Arc::new)
+    }
+
+    /// Creates an OpenGL context for this window that shares textures, buffers and other
+    /// objects with `other`, and makes it current on the calling thread.
+    ///
+    /// Must be called on the thread owning `other`; the new context uses its configuration.
+    pub fn create_shared_gl_context(self: Arc<Self>, other: Arc<GlContext>) -> Result<Arc<GlContext>, TaoError> {
+        GlContext::new(self, other.config, other.surface_config, Some(&other)).map(Arc::new)
+    }
+}
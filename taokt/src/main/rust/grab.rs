@@ -0,0 +1,222 @@
+//! Cursor grab modes, emulated where the platform only offers one of them.
+//!
+//! tao's `set_cursor_grab(true)` confines the cursor on Windows, locks it on macOS and grabs the
+//! pointer without confining it on Linux. The missing behaviors are emulated by warping the
+//! cursor back after it moved, except on Wayland, which does not let clients warp the cursor.
+//! tao resets the `ClipCursor` rectangle on Windows whenever the window is focused, moved or
+//! resized, so a lock is narrowed again after each of those.
+
+use crate::{PhysicalPositionF64, PhysicalSizeU32, TaoError};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, uniffi::Enum)]
+pub enum CursorGrabMode {
+    /// The cursor moves freely.
+    #[default]
+    None,
+    /// The cursor cannot leave the window's client area.
+    Confined,
+    /// The cursor stays where it is; read movement from `DeviceEvent::MouseMotion`.
+    Locked,
+}
+
+/// How the cursor is kept in place on top of the native grab.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) enum GrabEmulation {
+    /// The native grab does everything.
+    #[default]
+    Native,
+    /// Warp back to where the cursor was when the grab started.
+    Lock,
+    /// Warp back inside the window when the cursor leaves it.
+    Confine,
+}
+
+/// A cursor update that may need correcting.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum GrabInput {
+    Moved(PhysicalPositionF64),
+    Left,
+}
+
+/// Emulation state for a window's current grab.
+#[derive(Debug, Default)]
+pub(crate) struct CursorGrab {
+    mode: CursorGrabMode,
+    emulation: GrabEmulation,
+    /// Lock position, or last position inside the window when confining.
+    anchor: Option<PhysicalPositionF64>,
+}
+
+impl CursorGrab {
+    pub(crate) fn new(mode: CursorGrabMode, emulation: GrabEmulation) -> Self {
+        Self {
+            mode,
+            emulation,
+            anchor: None,
+        }
+    }
+
+    pub(crate) fn mode(&self) -> CursorGrabMode {
+        self.mode
+    }
+
+    pub(crate) fn is_emulated(&self) -> bool {
+        self.emulation != GrabEmulation::Native
+    }
+
+    /// Where to warp the cursor after `input`, for a client area of `size`.
+    pub(crate) fn correction(&mut self, input: GrabInput, size: PhysicalSizeU32) -> Option<PhysicalPositionF64> {
+        match (self.emulation, input) {
+            (GrabEmulation::Native, _) => None,
+            (GrabEmulation::Lock, GrabInput::Moved(position)) => match self.anchor {
+                Some(anchor) if anchor != position => Some(anchor),
+                Some(_) => None,
+                None => {
+                    self.anchor = Some(position);
+                    None
+                }
+            },
+            (GrabEmulation::Lock, GrabInput::Left) => self.anchor,
+            (GrabEmulation::Confine, GrabInput::Moved(position)) => {
+                let inside = PhysicalPositionF64 {
+                    x: position.x.clamp(0.0, size.width.saturating_sub(1) as f64),
+                    y: position.y.clamp(0.0, size.height.saturating_sub(1) as f64),
+                };
+                self.anchor = Some(inside);
+                (inside != position).then_some(inside)
+            }
+            (GrabEmulation::Confine, GrabInput::Left) => self.anchor,
+        }
+    }
+}
+
+/// Applies `mode` with tao's native grab and returns what has to be emulated on top of it.
+pub(crate) fn apply_grab(window: &tao::window::Window, mode: CursorGrabMode) -> Result<GrabEmulation, TaoError> {
+    window.set_cursor_grab(mode != CursorGrabMode::None)?;
+
+    #[cfg(target_os = "windows")]
+    {
+        if mode == CursorGrabMode::Locked {
+            confine_to_cursor();
+        }
+        return Ok(GrabEmulation::Native);
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        if mode == CursorGrabMode::Confined {
+            // The native grab locks; confining is emulated without it.
+            window.set_cursor_grab(false)?;
+            return Ok(GrabEmulation::Confine);
+        }
+        return Ok(GrabEmulation::Native);
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    {
+        use tao::platform::unix::WindowExtUnix;
+        if mode != CursorGrabMode::None && window.wayland_display().is_some() {
+            // Emulation needs cursor warps, which Wayland does not allow.
+            window.set_cursor_grab(false)?;
+            return Err(TaoError::Unsupported);
+        }
+    }
+
+    #[allow(unreachable_code)]
+    Ok(match mode {
+        CursorGrabMode::None => GrabEmulation::Native,
+        CursorGrabMode::Confined => GrabEmulation::Confine,
+        CursorGrabMode::Locked => GrabEmulation::Lock,
+    })
+}
+
+/// Restores a lock after tao replaced the `ClipCursor` rectangle with the window's client area.
+pub(crate) fn restore_grab(mode: CursorGrabMode) {
+    #[cfg(target_os = "windows")]
+    if mode == CursorGrabMode::Locked {
+        confine_to_cursor();
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    let _ = mode;
+}
+
+/// Narrows the `ClipCursor` rectangle tao set up to the pixel under the cursor.
+#[cfg(target_os = "windows")]
+fn confine_to_cursor() {
+    use windows_sys::Win32::{
+        Foundation::{POINT, RECT},
+        UI::WindowsAndMessaging::{ClipCursor, GetCursorPos},
+    };
+
+    let mut point = POINT { x: 0, y: 0 };
+    unsafe {
+        if GetCursorPos(&mut point) != 0 {
+            let rect = RECT {
+                left: point.x,
+                top: point.y,
+                right: point.x + 1,
+                bottom: point.y + 1,
+            };
+            ClipCursor(&rect);
+        }
+    }
+}
+
+/// Mouse capture that outlasts button releases, for `Window::set_mouse_capture`.
+///
+/// tao calls `ReleaseCapture` whenever a mouse button goes up, so the window is subclassed to
+/// capture the mouse again right after tao handled the release.
+#[cfg(target_os = "windows")]
+pub(crate) mod capture {
+    use windows_sys::Win32::{
+        Foundation::{HWND, LPARAM, LRESULT, WPARAM},
+        UI::{
+            Input::KeyboardAndMouse::{ReleaseCapture, SetCapture},
+            Shell::{DefSubclassProc, RemoveWindowSubclass, SetWindowSubclass},
+            WindowsAndMessaging::{WM_LBUTTONUP, WM_MBUTTONUP, WM_NCDESTROY, WM_RBUTTONUP, WM_XBUTTONUP},
+        },
+    };
+
+    const SUBCLASS_ID: usize = 0x6361_7074;
+
+    pub(crate) fn set(hwnd: HWND, capture: bool) {
+        unsafe {
+            if capture {
+                SetWindowSubclass(hwnd, Some(capture_proc), SUBCLASS_ID, 0);
+                SetCapture(hwnd);
+            } else {
+                RemoveWindowSubclass(hwnd, Some(capture_proc), SUBCLASS_ID);
+                ReleaseCapture();
+            }
+        }
+    }
+
+    unsafe extern "system" fn capture_proc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+        _id: usize,
+        _data: usize,
+    ) -> LRESULT {
+        match msg {
+            WM_LBUTTONUP | WM_RBUTTONUP | WM_MBUTTONUP | WM_XBUTTONUP => unsafe {
+                let result = DefSubclassProc(hwnd, msg, wparam, lparam);
+                SetCapture(hwnd);
+                result
+            },
+            WM_NCDESTROY => unsafe {
+                RemoveWindowSubclass(hwnd, Some(capture_proc), SUBCLASS_ID);
+                DefSubclassProc(hwnd, msg, wparam, lparam)
+            },
+            _ => unsafe { DefSubclassProc(hwnd, msg, wparam, lparam) },
+        }
+    }
+}
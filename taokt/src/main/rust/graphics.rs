@@ -3,8 +3,13 @@
 //! This module provides platform-agnostic window handle types that can be used
 //! by rendering backends (Metal, Vulkan, DirectX12, OpenGL).
 
-use crate::TaoError;
-use std::sync::Arc;
+use crate::{thread::ThreadBound, TaoError};
+use raw_window_handle::{HasDisplayHandle as _, HasWindowHandle as _};
+use std::{
+    cell::RefCell,
+    num::NonZeroU32,
+    sync::{Arc, Mutex},
+};
 
 /// Supported graphics backends.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
@@ -84,6 +89,8 @@ pub struct RawWindowHandle {
     pub ui_view: Option<u64>,
     /// UIWindow pointer (iOS).
     pub ui_window: Option<u64>,
+    /// UIViewController pointer (iOS) - owns `ui_view`, e.g. for status bar appearance.
+    pub ui_view_controller: Option<u64>,
 
     // Windows handles
     /// HWND handle (Windows) - the window handle.
@@ -94,18 +101,19 @@ pub struct RawWindowHandle {
     // Linux X11 handles
     /// X11 Window ID (Linux X11).
     pub xlib_window: Option<u64>,
-    /// X11 Display pointer (Linux X11).
-    pub xlib_display: Option<u64>,
     /// X11 Visual ID (Linux X11) - for OpenGL context creation.
+    ///
+    /// Transparent windows get a 32-bit ARGB visual; GL and Vulkan configurations must use
+    /// the same visual for the alpha channel to reach the compositor.
     pub xlib_visual_id: Option<u64>,
-    /// X11 Screen number (Linux X11).
-    pub xlib_screen: Option<i32>,
+    /// X11 Colormap matching `xlib_visual_id` (Linux X11).
+    pub xlib_colormap: Option<u64>,
+    /// Bit depth of `xlib_visual_id` (Linux X11); 32 for ARGB visuals.
+    pub xlib_depth: Option<u8>,
 
     // Linux Wayland handles
     /// Wayland surface pointer (Linux Wayland).
     pub wayland_surface: Option<u64>,
-    /// Wayland display pointer (Linux Wayland).
-    pub wayland_display: Option<u64>,
 
     // Android handles
     /// ANativeWindow pointer (Android).
@@ -118,6 +126,77 @@ pub struct RawWindowHandle {
     pub height: u32,
     /// Scale factor (DPI scaling).
     pub scale_factor: f64,
+
+    /// Display connection the window belongs to.
+    pub display: RawDisplayHandle,
+}
+
+/// Raw display connection, the counterpart of `RawWindowHandle`.
+///
+/// Only X11 and Wayland have display-level data; on other platforms every field is `None`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, uniffi::Record)]
+pub struct RawDisplayHandle {
+    /// X11 Display pointer (Linux X11).
+    pub xlib_display: Option<u64>,
+    /// X11 Screen number (Linux X11).
+    pub xlib_screen: Option<i32>,
+    /// Wayland display pointer (Linux Wayland).
+    pub wayland_display: Option<u64>,
+}
+
+impl RawDisplayHandle {
+    pub(crate) fn from_rwh(handle: raw_window_handle::RawDisplayHandle) -> Self {
+        use raw_window_handle::RawDisplayHandle as Rwh;
+
+        match handle {
+            Rwh::Xlib(xlib) => Self {
+                xlib_display: xlib.display.map(|d| d.as_ptr() as u64),
+                xlib_screen: Some(xlib.screen),
+                ..Self::default()
+            },
+            Rwh::Wayland(wayland) => Self {
+                wayland_display: Some(wayland.display.as_ptr() as u64),
+                ..Self::default()
+            },
+            _ => Self::default(),
+        }
+    }
+}
+
+/// Windowing system the event loop is connected to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum DisplayBackend {
+    X11,
+    Wayland,
+    Quartz,
+    Win32,
+    Android,
+    UiKit,
+}
+
+impl DisplayBackend {
+    pub(crate) fn from_rwh(handle: raw_window_handle::RawDisplayHandle) -> Option<Self> {
+        use raw_window_handle::RawDisplayHandle as Rwh;
+
+        match handle {
+            Rwh::Xlib(_) | Rwh::Xcb(_) => Some(Self::X11),
+            Rwh::Wayland(_) => Some(Self::Wayland),
+            Rwh::AppKit(_) => Some(Self::Quartz),
+            Rwh::Windows(_) => Some(Self::Win32),
+            Rwh::Android(_) => Some(Self::Android),
+            Rwh::UiKit(_) => Some(Self::UiKit),
+            _ => None,
+        }
+    }
+
+    /// Value for `gdk_set_allowed_backends`; only X11 and Wayland can be chosen.
+    pub(crate) fn gdk_backend(self) -> Option<&'static str> {
+        match self {
+            Self::X11 => Some("x11"),
+            Self::Wayland => Some("wayland"),
+            _ => None,
+        }
+    }
 }
 
 impl RawWindowHandle {
@@ -129,18 +208,19 @@ impl RawWindowHandle {
             ns_window: None,
             ui_view: None,
             ui_window: None,
+            ui_view_controller: None,
             hwnd: None,
             hinstance: None,
             xlib_window: None,
-            xlib_display: None,
             xlib_visual_id: None,
-            xlib_screen: None,
+            xlib_colormap: None,
+            xlib_depth: None,
             wayland_surface: None,
-            wayland_display: None,
             android_native_window: None,
             width: 0,
             height: 0,
             scale_factor: 1.0,
+            display: RawDisplayHandle::default(),
         }
     }
 
@@ -175,6 +255,12 @@ pub trait WindowGraphicsExt {
         &self,
         backend: GraphicsBackend,
     ) -> Result<RawWindowHandle, TaoError>;
+
+    /// Gets the Wayland scale of the window surface.
+    fn wayland_scale(&self) -> Result<WaylandScale, TaoError>;
+
+    /// Gets the color capabilities of the display the window is on.
+    fn surface_color_info(&self) -> SurfaceColorInfo;
 }
 
 impl WindowGraphicsExt for crate::Window {
@@ -196,18 +282,19 @@ impl WindowGraphicsExt for crate::Window {
             ns_window: None,
             ui_view: None,
             ui_window: None,
+            ui_view_controller: None,
             hwnd: None,
             hinstance: None,
             xlib_window: None,
-            xlib_display: None,
             xlib_visual_id: None,
-            xlib_screen: None,
+            xlib_colormap: None,
+            xlib_depth: None,
             wayland_surface: None,
-            wayland_display: None,
             android_native_window: None,
             width: size.width,
             height: size.height,
             scale_factor: scale,
+            display: RawDisplayHandle::default(),
         };
 
         #[cfg(target_os = "macos")]
@@ -222,6 +309,7 @@ impl WindowGraphicsExt for crate::Window {
             use tao::platform::ios::WindowExtIOS;
             handle.ui_view = Some(window.ui_view() as u64);
             handle.ui_window = Some(window.ui_window() as u64);
+            handle.ui_view_controller = Some(window.ui_view_controller() as u64);
         }
 
         #[cfg(target_os = "windows")]
@@ -246,10 +334,17 @@ impl WindowGraphicsExt for crate::Window {
                 handle.xlib_window = Some(xlib_window as u64);
             }
             if let Some(xlib_display) = window.xlib_display() {
-                handle.xlib_display = Some(xlib_display as u64);
+                handle.display.xlib_display = Some(xlib_display as u64);
             }
             if let Some(xlib_screen) = window.xlib_screen_id() {
-                handle.xlib_screen = Some(xlib_screen);
+                handle.display.xlib_screen = Some(xlib_screen);
+            }
+            if let (Some(display), Some(xlib_window)) = (window.xlib_display(), window.xlib_window()) {
+                if let Some(visual) = x11_visual(display as _, xlib_window as _) {
+                    handle.xlib_visual_id = Some(visual.id);
+                    handle.xlib_colormap = Some(visual.colormap);
+                    handle.xlib_depth = Some(visual.depth);
+                }
             }
 
             // Try Wayland
@@ -257,14 +352,19 @@ impl WindowGraphicsExt for crate::Window {
                 handle.wayland_surface = Some(wayland_surface as u64);
             }
             if let Some(wayland_display) = window.wayland_display() {
-                handle.wayland_display = Some(wayland_display as u64);
+                handle.display.wayland_display = Some(wayland_display as u64);
             }
         }
 
         #[cfg(target_os = "android")]
         {
-            use tao::platform::android::WindowExtAndroid;
-            // Note: ANativeWindow access may require additional setup
+            use raw_window_handle::HasWindowHandle;
+            // The ANativeWindow only exists between `TaoEvent::Resumed` and `TaoEvent::Suspended`.
+            if let Ok(native) = window.window_handle() {
+                if let raw_window_handle::RawWindowHandle::AndroidNdk(native) = native.as_raw() {
+                    handle.android_native_window = Some(native.a_native_window.as_ptr() as u64);
+                }
+            }
         }
 
         if !handle.is_valid() {
@@ -273,12 +373,676 @@ impl WindowGraphicsExt for crate::Window {
 
         Ok(handle)
     }
+
+    fn wayland_scale(&self) -> Result<WaylandScale, TaoError> {
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        {
+            use tao::platform::unix::WindowExtUnix;
+
+            let (buffer_scale, size) = {
+                let window = self.inner.lock().unwrap();
+                if window.wayland_surface().is_none() {
+                    return Err(TaoError::Unsupported);
+                }
+                (window.scale_factor().round().max(1.0) as i32, window.inner_size())
+            };
+            let fractional = self.fractional_scale();
+            let (buffer_width, buffer_height) = scaled_buffer_size(
+                size.width / buffer_scale as u32,
+                size.height / buffer_scale as u32,
+                fractional.unwrap_or(buffer_scale as f64),
+            );
+            return Ok(WaylandScale {
+                scale_factor: fractional.unwrap_or(buffer_scale as f64),
+                fractional: fractional.is_some(),
+                preferred_buffer_scale: buffer_scale,
+                buffer_width,
+                buffer_height,
+            });
+        }
+
+        #[allow(unreachable_code)]
+        Err(TaoError::Unsupported)
+    }
+
+    fn surface_color_info(&self) -> SurfaceColorInfo {
+        surface_color_info(&self.inner.lock().unwrap())
+    }
+}
+
+/// Visual an X11 window was created with.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+struct X11Visual {
+    id: u64,
+    colormap: u64,
+    depth: u8,
+}
+
+/// Reads the visual of `window`. tao creates transparent windows with the screen's RGBA
+/// visual, so this reports a 32-bit ARGB visual for them.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+fn x11_visual(display: *mut std::ffi::c_void, window: std::ffi::c_ulong) -> Option<X11Visual> {
+    use std::ffi::{c_int, c_long, c_ulong, c_void};
+
+    // Leading fields of Xlib's `Visual`.
+    #[repr(C)]
+    struct Visual {
+        ext_data: *mut c_void,
+        visualid: c_ulong,
+    }
+
+    #[repr(C)]
+    struct XWindowAttributes {
+        x: c_int,
+        y: c_int,
+        width: c_int,
+        height: c_int,
+        border_width: c_int,
+        depth: c_int,
+        visual: *mut Visual,
+        root: c_ulong,
+        class: c_int,
+        bit_gravity: c_int,
+        win_gravity: c_int,
+        backing_store: c_int,
+        backing_planes: c_ulong,
+        backing_pixel: c_ulong,
+        save_under: c_int,
+        colormap: c_ulong,
+        map_installed: c_int,
+        map_state: c_int,
+        all_event_masks: c_long,
+        your_event_mask: c_long,
+        do_not_propagate_mask: c_long,
+        override_redirect: c_int,
+        screen: *mut c_void,
+    }
+
+    #[link(name = "X11")]
+    unsafe extern "C" {
+        fn XGetWindowAttributes(display: *mut c_void, window: c_ulong, attributes: *mut XWindowAttributes) -> c_int;
+    }
+
+    if display.is_null() || window == 0 {
+        return None;
+    }
+    let mut attributes: XWindowAttributes = unsafe { std::mem::zeroed() };
+    if unsafe { XGetWindowAttributes(display, window, &mut attributes) } == 0 || attributes.visual.is_null() {
+        return None;
+    }
+    Some(X11Visual {
+        id: unsafe { (*attributes.visual).visualid } as u64,
+        colormap: attributes.colormap as u64,
+        depth: attributes.depth as u8,
+    })
+}
+
+/// Borrows the native handles of a `Window` for libraries built on raw-window-handle.
+#[derive(Clone)]
+pub(crate) struct WindowHandleSource(pub(crate) Arc<crate::Window>);
+
+impl raw_window_handle::HasWindowHandle for WindowHandleSource {
+    fn window_handle(&self) -> Result<raw_window_handle::WindowHandle<'_>, raw_window_handle::HandleError> {
+        let raw = self.0.inner.lock().unwrap().window_handle()?.as_raw();
+        // Safety: the handle stays valid while the `Window` kept alive by `self` exists.
+        Ok(unsafe { raw_window_handle::WindowHandle::borrow_raw(raw) })
+    }
+}
+
+impl raw_window_handle::HasDisplayHandle for WindowHandleSource {
+    fn display_handle(&self) -> Result<raw_window_handle::DisplayHandle<'_>, raw_window_handle::HandleError> {
+        let raw = self.0.inner.lock().unwrap().display_handle()?.as_raw();
+        // Safety: as above, the display outlives the window.
+        Ok(unsafe { raw_window_handle::DisplayHandle::borrow_raw(raw) })
+    }
+}
+
+/// CPU-side pixel buffer shown in a window, for drawing without a GPU API.
+///
+/// Dropped on another thread, the surface and the window it keeps alive are leaked; see
+/// `ThreadBound`.
+#[derive(uniffi::Object)]
+pub struct Framebuffer {
+    surface: ThreadBound<RefCell<softbuffer::Surface<WindowHandleSource, WindowHandleSource>>>,
+}
+
+impl Framebuffer {
+    pub(crate) fn new(window: Arc<crate::Window>) -> Result<Self, TaoError> {
+        let source = WindowHandleSource(window);
+        let context = softbuffer::Context::new(source.clone()).map_err(|e| TaoError::message(e.to_string()))?;
+        let surface = softbuffer::Surface::new(&context, source).map_err(|e| TaoError::message(e.to_string()))?;
+        Ok(Self {
+            surface: ThreadBound::new(RefCell::new(surface)),
+        })
+    }
+}
+
+#[uniffi::export]
+impl Framebuffer {
+    /// Shows `pixels` in the window.
+    ///
+    /// `pixels` holds `width * height` values in row-major order, each `0x00RRGGBB`.
+    /// Must be called on the thread that created the framebuffer.
+    pub fn present(&self, pixels: Vec<u32>, width: u32, height: u32) -> Result<(), TaoError> {
+        let surface = self
+            .surface
+            .get()
+            .map_err(|_| TaoError::message("Framebuffer must be presented on the thread that created it"))?;
+        let (Some(w), Some(h)) = (NonZeroU32::new(width), NonZeroU32::new(height)) else {
+            return Err(TaoError::message("Framebuffer size must not be zero"));
+        };
+        if pixels.len() != width as usize * height as usize {
+            return Err(TaoError::message(format!(
+                "Expected {} pixels for {width}x{height}, got {}",
+                width as usize * height as usize,
+                pixels.len()
+            )));
+        }
+
+        let mut surface = surface.borrow_mut();
+        surface.resize(w, h).map_err(|e| TaoError::message(e.to_string()))?;
+        let mut buffer = surface.buffer_mut().map_err(|e| TaoError::message(e.to_string()))?;
+        buffer.copy_from_slice(&pixels);
+        buffer.present().map_err(|e| TaoError::message(e.to_string()))
+    }
+}
+
+/// Framebuffer and presentation options for GL contexts and wgpu surfaces.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Record)]
+pub struct SurfaceConfig {
+    /// MSAA sample count; zero disables multisampling.
+    #[uniffi(default = 0)]
+    pub samples: u8,
+    #[uniffi(default = 24)]
+    pub depth_bits: u8,
+    #[uniffi(default = 8)]
+    pub stencil_bits: u8,
+    /// Use an sRGB color buffer, so the GPU encodes linear output.
+    #[uniffi(default = true)]
+    pub srgb: bool,
+    /// Vertical blanks to wait per swap: 0 disables vsync, 1 syncs to every refresh.
+    #[uniffi(default = 1)]
+    pub swap_interval: u32,
+    /// Allow one more queued frame for smoother output at the cost of latency.
+    ///
+    /// Only wgpu honors this; OpenGL drivers decide on their own.
+    #[uniffi(default = false)]
+    pub triple_buffering: bool,
+}
+
+impl Default for SurfaceConfig {
+    fn default() -> Self {
+        Self {
+            samples: 0,
+            depth_bits: 24,
+            stencil_bits: 8,
+            srgb: true,
+            swap_interval: 1,
+            triple_buffering: false,
+        }
+    }
+}
+
+impl SurfaceConfig {
+    /// Frames the presentation engine may queue ahead of the display.
+    pub(crate) fn frame_latency(&self) -> u32 {
+        if self.triple_buffering { 2 } else { 1 }
+    }
+}
+
+/// Color space of a display, for picking swapchain formats.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum ColorSpace {
+    Srgb,
+    DisplayP3,
+    /// Linear extended sRGB, used for HDR output with half-float buffers.
+    ScRgb,
+}
+
+/// Color capabilities of the display a window is on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Record)]
+pub struct SurfaceColorInfo {
+    /// Bits per color channel, e.g. 8 or 10.
+    pub bits_per_channel: u8,
+    /// Whether HDR (Windows advanced color, macOS EDR) is currently available.
+    pub hdr_enabled: bool,
+    pub color_space: ColorSpace,
+}
+
+impl Default for SurfaceColorInfo {
+    fn default() -> Self {
+        Self {
+            bits_per_channel: 8,
+            hdr_enabled: false,
+            color_space: ColorSpace::Srgb,
+        }
+    }
+}
+
+/// Color capabilities of the monitor `window` is on; sRGB at 8 bits when unknown.
+pub(crate) fn surface_color_info(window: &tao::window::Window) -> SurfaceColorInfo {
+    platform_color_info(window).unwrap_or_default()
+}
+
+#[cfg(target_os = "windows")]
+fn platform_color_info(window: &tao::window::Window) -> Option<SurfaceColorInfo> {
+    use std::mem::{size_of, zeroed};
+    use tao::platform::windows::MonitorHandleExtWindows;
+    use windows_sys::Win32::{
+        Devices::Display::{
+            DisplayConfigGetDeviceInfo, GetDisplayConfigBufferSizes, QueryDisplayConfig,
+            DISPLAYCONFIG_DEVICE_INFO_GET_ADVANCED_COLOR_INFO, DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME,
+            DISPLAYCONFIG_GET_ADVANCED_COLOR_INFO, DISPLAYCONFIG_MODE_INFO, DISPLAYCONFIG_PATH_INFO,
+            DISPLAYCONFIG_SOURCE_DEVICE_NAME,
+        },
+        Graphics::Gdi::QDC_ONLY_ACTIVE_PATHS,
+    };
+
+    let device = window.current_monitor()?.native_id();
+    unsafe {
+        let (mut path_count, mut mode_count) = (0, 0);
+        if GetDisplayConfigBufferSizes(QDC_ONLY_ACTIVE_PATHS, &mut path_count, &mut mode_count) != 0 {
+            return None;
+        }
+        let mut paths = vec![zeroed::<DISPLAYCONFIG_PATH_INFO>(); path_count as usize];
+        let mut modes = vec![zeroed::<DISPLAYCONFIG_MODE_INFO>(); mode_count as usize];
+        if QueryDisplayConfig(
+            QDC_ONLY_ACTIVE_PATHS,
+            &mut path_count,
+            paths.as_mut_ptr(),
+            &mut mode_count,
+            modes.as_mut_ptr(),
+            std::ptr::null_mut(),
+        ) != 0
+        {
+            return None;
+        }
+        paths.truncate(path_count as usize);
+
+        // Find the path whose source is the window's monitor, then ask its target.
+        for path in &paths {
+            let mut source: DISPLAYCONFIG_SOURCE_DEVICE_NAME = zeroed();
+            source.header.r#type = DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME;
+            source.header.size = size_of::<DISPLAYCONFIG_SOURCE_DEVICE_NAME>() as u32;
+            source.header.adapterId = path.sourceInfo.adapterId;
+            source.header.id = path.sourceInfo.id;
+            if DisplayConfigGetDeviceInfo(&mut source.header) != 0 {
+                continue;
+            }
+            let name = String::from_utf16_lossy(&source.viewGdiDeviceName);
+            if name.trim_end_matches('\0') != device {
+                continue;
+            }
+
+            let mut color: DISPLAYCONFIG_GET_ADVANCED_COLOR_INFO = zeroed();
+            color.header.r#type = DISPLAYCONFIG_DEVICE_INFO_GET_ADVANCED_COLOR_INFO;
+            color.header.size = size_of::<DISPLAYCONFIG_GET_ADVANCED_COLOR_INFO>() as u32;
+            color.header.adapterId = path.targetInfo.adapterId;
+            color.header.id = path.targetInfo.id;
+            if DisplayConfigGetDeviceInfo(&mut color.header) != 0 {
+                return None;
+            }
+            // Bit 1 of the flags is advancedColorEnabled.
+            let hdr_enabled = color.Anonymous.value & 0b10 != 0;
+            return Some(SurfaceColorInfo {
+                bits_per_channel: color.bitsPerColorChannel as u8,
+                hdr_enabled,
+                color_space: if hdr_enabled { ColorSpace::ScRgb } else { ColorSpace::Srgb },
+            });
+        }
+    }
+    None
+}
+
+#[cfg(target_os = "macos")]
+fn platform_color_info(window: &tao::window::Window) -> Option<SurfaceColorInfo> {
+    use objc2::{msg_send, runtime::AnyObject, sel};
+    use tao::platform::macos::WindowExtMacOS;
+
+    #[link(name = "AppKit", kind = "framework")]
+    unsafe extern "C" {
+        fn NSBitsPerSampleFromDepth(depth: i32) -> isize;
+    }
+
+    /// `NSDisplayGamutP3`.
+    const DISPLAY_GAMUT_P3: isize = 2;
+
+    unsafe {
+        let ns_window = window.ns_window() as *mut AnyObject;
+        let screen: *mut AnyObject = msg_send![&*ns_window, screen];
+        if screen.is_null() {
+            return None;
+        }
+        let depth: i32 = msg_send![&*screen, depth];
+        let p3: bool = msg_send![&*screen, canRepresentDisplayGamut: DISPLAY_GAMUT_P3];
+        let edr_aware: bool = msg_send![
+            &*screen,
+            respondsToSelector: sel!(maximumPotentialExtendedDynamicRangeColorComponentValue)
+        ];
+        let hdr_enabled = edr_aware && {
+            let headroom: f64 = msg_send![&*screen, maximumPotentialExtendedDynamicRangeColorComponentValue];
+            headroom > 1.0
+        };
+        Some(SurfaceColorInfo {
+            bits_per_channel: NSBitsPerSampleFromDepth(depth) as u8,
+            hdr_enabled,
+            color_space: if p3 { ColorSpace::DisplayP3 } else { ColorSpace::Srgb },
+        })
+    }
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+fn platform_color_info(_window: &tao::window::Window) -> Option<SurfaceColorInfo> {
+    // GTK 3 has no HDR or wide-gamut support; only the visual depth is known.
+    let visual = gtk::gdk::Screen::default()?.system_visual()?;
+    Some(SurfaceColorInfo {
+        bits_per_channel: bits_per_channel(visual.depth()),
+        ..SurfaceColorInfo::default()
+    })
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+)))]
+fn platform_color_info(_window: &tao::window::Window) -> Option<SurfaceColorInfo> {
+    None
+}
+
+/// Has the loop re-check the color info of every window when the display configuration or its
+/// color settings change, e.g. when HDR is switched on without the window moving.
+pub(crate) fn watch_color_info(window: &tao::window::Window, proxy: &crate::LoopProxy) {
+    #[cfg(target_os = "windows")]
+    color_watch::attach(window, proxy);
+
+    #[cfg(target_os = "macos")]
+    {
+        static WATCH: std::sync::Once = std::sync::Once::new();
+        WATCH.call_once(|| color_watch::watch(proxy.clone()));
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let _ = proxy;
+    let _ = window;
+}
+
+/// `WM_DISPLAYCHANGE` and `WM_SETTINGCHANGE` are sent to top-level windows only, so each window
+/// is subclassed to forward them.
+#[cfg(target_os = "windows")]
+mod color_watch {
+    use tao::platform::windows::WindowExtWindows;
+    use windows_sys::Win32::{
+        Foundation::{HWND, LPARAM, LRESULT, WPARAM},
+        UI::{
+            Shell::{DefSubclassProc, RemoveWindowSubclass, SetWindowSubclass},
+            WindowsAndMessaging::{WM_DISPLAYCHANGE, WM_NCDESTROY, WM_SETTINGCHANGE},
+        },
+    };
+
+    use crate::{LoopMessage, LoopProxy};
+
+    const SUBCLASS_ID: usize = 0x636f_6c72;
+
+    pub(super) fn attach(window: &tao::window::Window, proxy: &LoopProxy) {
+        let proxy = Box::into_raw(Box::new(proxy.clone()));
+        unsafe { SetWindowSubclass(window.hwnd() as HWND, Some(color_proc), SUBCLASS_ID, proxy as usize) };
+    }
+
+    unsafe extern "system" fn color_proc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+        _id: usize,
+        proxy: usize,
+    ) -> LRESULT {
+        let proxy = proxy as *mut LoopProxy;
+        match msg {
+            WM_DISPLAYCHANGE | WM_SETTINGCHANGE => {
+                let _ = unsafe { &*proxy }.send_event(LoopMessage::DisplaySettingsChanged);
+            }
+            WM_NCDESTROY => unsafe {
+                RemoveWindowSubclass(hwnd, Some(color_proc), SUBCLASS_ID);
+                drop(Box::from_raw(proxy));
+            },
+            _ => {}
+        }
+        unsafe { DefSubclassProc(hwnd, msg, wparam, lparam) }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod color_watch {
+    use std::ptr::NonNull;
+
+    use block2::RcBlock;
+    use objc2::{class, msg_send, runtime::AnyObject};
+
+    use crate::{LoopMessage, LoopProxy};
+
+    /// Screen changes, including HDR being switched, and color profile changes of any window.
+    const NOTIFICATIONS: [&std::ffi::CStr; 2] = [
+        c"NSApplicationDidChangeScreenParametersNotification",
+        c"NSWindowDidChangeScreenProfileNotification",
+    ];
+
+    pub(super) fn watch(proxy: LoopProxy) {
+        let handler = RcBlock::new(move |_notification: NonNull<AnyObject>| {
+            let _ = proxy.send_event(LoopMessage::DisplaySettingsChanged);
+        });
+        unsafe {
+            let center: *mut AnyObject = msg_send![class!(NSNotificationCenter), defaultCenter];
+            for name in NOTIFICATIONS {
+                let name: *mut AnyObject = msg_send![class!(NSString), stringWithUTF8String: name.as_ptr()];
+                // The notification center keeps the observer (and the block) for the rest of the process.
+                let _: *mut AnyObject = msg_send![
+                    &*center,
+                    addObserverForName: name,
+                    object: std::ptr::null_mut::<AnyObject>(),
+                    queue: std::ptr::null_mut::<AnyObject>(),
+                    usingBlock: &*handler
+                ];
+            }
+        }
+    }
+}
+
+/// Bits per channel of an RGB visual of `depth` bits; alpha in 32-bit visuals does not count.
+pub(crate) fn bits_per_channel(depth: i32) -> u8 {
+    match depth {
+        30 => 10,
+        36 => 12,
+        48 | 64 => 16,
+        _ => 8,
+    }
+}
+
+/// Scale of a Wayland window surface.
+///
+/// GTK renders at the integer `preferred_buffer_scale`. On compositors with
+/// wp_fractional_scale_v1, renderers get crisp output at 125 % or 150 % by drawing a
+/// `buffer_width` x `buffer_height` buffer and scaling it to the window with wp_viewporter.
+#[derive(Debug, Clone, Copy, PartialEq, uniffi::Record)]
+pub struct WaylandScale {
+    /// Fractional scale preferred by the compositor, or the integer buffer scale.
+    pub scale_factor: f64,
+    /// Whether `scale_factor` comes from wp_fractional_scale_v1.
+    pub fractional: bool,
+    /// Integer scale for `wl_surface.set_buffer_scale`.
+    pub preferred_buffer_scale: i32,
+    /// Buffer size matching `scale_factor`, in physical pixels.
+    pub buffer_width: u32,
+    pub buffer_height: u32,
+}
+
+/// Size of a buffer covering a `width` x `height` logical surface at `scale`.
+///
+/// Rounds half away from zero, as wp_fractional_scale_v1 asks.
+pub(crate) fn scaled_buffer_size(width: u32, height: u32, scale: f64) -> (u32, u32) {
+    (
+        (width as f64 * scale).round() as u32,
+        (height as f64 * scale).round() as u32,
+    )
+}
+
+/// Surface size and scale factor as one consistent pair.
+#[derive(Debug, Clone, Copy, PartialEq, uniffi::Record)]
+pub struct SurfaceExtent {
+    pub width: u32,
+    pub height: u32,
+    pub scale_factor: f64,
+}
+
+struct SurfaceState {
+    extent: SurfaceExtent,
+    dirty: bool,
+}
+
+impl SurfaceState {
+    fn apply(&mut self, event: &crate::TaoWindowEvent) {
+        match event {
+            crate::TaoWindowEvent::Resized { size } => {
+                self.extent.width = size.width;
+                self.extent.height = size.height;
+            }
+            crate::TaoWindowEvent::ScaleFactorChanged {
+                scale_factor,
+                new_inner_size,
+            } => {
+                self.extent = SurfaceExtent {
+                    width: new_inner_size.width,
+                    height: new_inner_size.height,
+                    scale_factor: *scale_factor,
+                };
+            }
+            _ => return,
+        }
+        self.dirty = true;
+    }
+}
+
+struct SurfaceListener(Arc<Mutex<SurfaceState>>);
+
+impl crate::TaoEventListener for SurfaceListener {
+    fn on_event(&self, event: crate::TaoEvent) {
+        if let crate::TaoEvent::WindowEvent { event, .. } = event {
+            self.0.lock().unwrap().apply(&event);
+        }
+    }
+}
+
+/// Follows a window's `Resized`/`ScaleFactorChanged` events so renderers read the
+/// surface size from the same events the loop delivered instead of querying the window.
+#[derive(uniffi::Object)]
+pub struct SurfaceTracker {
+    app: Arc<crate::App>,
+    listener_id: u64,
+    state: Arc<Mutex<SurfaceState>>,
+}
+
+#[uniffi::export]
+impl SurfaceTracker {
+    /// Starts tracking `window`, initialised from its current size and scale factor.
+    #[uniffi::constructor]
+    pub fn new(app: Arc<crate::App>, window: Arc<crate::Window>) -> Self {
+        let size = window.inner_size();
+        let state = Arc::new(Mutex::new(SurfaceState {
+            extent: SurfaceExtent {
+                width: size.width,
+                height: size.height,
+                scale_factor: window.scale_factor(),
+            },
+            dirty: true,
+        }));
+        let listener_id = app.add_event_listener(Arc::new(SurfaceListener(state.clone())), Some(window.id));
+        Self {
+            app,
+            listener_id,
+            state,
+        }
+    }
+
+    pub fn current_extent(&self) -> SurfaceExtent {
+        self.state.lock().unwrap().extent
+    }
+
+    /// True if the extent changed since the last `take_extent` (and right after creation).
+    pub fn is_dirty(&self) -> bool {
+        self.state.lock().unwrap().dirty
+    }
+
+    /// Returns the current extent and clears the dirty flag; call it before reconfiguring.
+    pub fn take_extent(&self) -> SurfaceExtent {
+        let mut state = self.state.lock().unwrap();
+        state.dirty = false;
+        state.extent
+    }
+}
+
+impl Drop for SurfaceTracker {
+    fn drop(&mut self) {
+        self.app.remove_event_listener(self.listener_id);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_bits_per_channel_from_visual_depth() {
+        assert_eq!(bits_per_channel(24), 8);
+        assert_eq!(bits_per_channel(32), 8);
+        assert_eq!(bits_per_channel(30), 10);
+    }
+
+    #[test]
+    fn test_surface_config_frame_latency() {
+        let config = SurfaceConfig::default();
+        assert_eq!(config.frame_latency(), 1);
+        let triple = SurfaceConfig {
+            triple_buffering: true,
+            ..config
+        };
+        assert_eq!(triple.frame_latency(), 2);
+    }
+
+    #[test]
+    fn test_scaled_buffer_size() {
+        assert_eq!(scaled_buffer_size(800, 600, 1.25), (1000, 750));
+        assert_eq!(scaled_buffer_size(801, 601, 1.5), (1202, 902));
+        assert_eq!(scaled_buffer_size(800, 600, 2.0), (1600, 1200));
+    }
+
     #[test]
     fn test_graphics_backend_default() {
         let backend = GraphicsBackend::default_for_platform();
@@ -290,4 +1054,64 @@ mod tests {
         let handle = RawWindowHandle::empty();
         assert!(!handle.is_valid());
     }
+
+    #[test]
+    fn test_surface_state_follows_window_events() {
+        let mut state = SurfaceState {
+            extent: SurfaceExtent {
+                width: 100,
+                height: 100,
+                scale_factor: 1.0,
+            },
+            dirty: false,
+        };
+        state.apply(&crate::TaoWindowEvent::Moved {
+            position: crate::PhysicalPositionI32 { x: 1, y: 1 },
+        });
+        assert!(!state.dirty);
+
+        state.apply(&crate::TaoWindowEvent::ScaleFactorChanged {
+            scale_factor: 2.0,
+            new_inner_size: crate::PhysicalSizeU32 {
+                width: 200,
+                height: 300,
+            },
+        });
+        assert!(state.dirty);
+        assert_eq!(
+            state.extent,
+            SurfaceExtent {
+                width: 200,
+                height: 300,
+                scale_factor: 2.0,
+            }
+        );
+    }
+
+    #[test]
+    fn test_raw_display_handle_from_rwh() {
+        let display = std::ptr::NonNull::<std::ffi::c_void>::dangling();
+        let handle = RawDisplayHandle::from_rwh(raw_window_handle::RawDisplayHandle::Wayland(
+            raw_window_handle::WaylandDisplayHandle::new(display),
+        ));
+        assert_eq!(handle.wayland_display, Some(display.as_ptr() as u64));
+        assert_eq!(handle.xlib_display, None);
+
+        let handle = RawDisplayHandle::from_rwh(raw_window_handle::RawDisplayHandle::AppKit(
+            raw_window_handle::AppKitDisplayHandle::new(),
+        ));
+        assert_eq!(handle, RawDisplayHandle::default());
+    }
+
+    #[test]
+    fn test_display_backend_from_rwh() {
+        let display = std::ptr::NonNull::<std::ffi::c_void>::dangling();
+        let wayland =
+            raw_window_handle::RawDisplayHandle::Wayland(raw_window_handle::WaylandDisplayHandle::new(display));
+        assert_eq!(DisplayBackend::from_rwh(wayland), Some(DisplayBackend::Wayland));
+        let xcb = raw_window_handle::RawDisplayHandle::Xcb(raw_window_handle::XcbDisplayHandle::new(None, 0));
+        assert_eq!(DisplayBackend::from_rwh(xcb), Some(DisplayBackend::X11));
+        assert_eq!(DisplayBackend::X11.gdk_backend(), Some("x11"));
+        assert_eq!(DisplayBackend::Quartz.gdk_backend(), None);
+    }
 }
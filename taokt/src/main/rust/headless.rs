@@ -0,0 +1,88 @@
+//! Event loop simulation for tests that must run without a display.
+
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+use tao::event::{Event, StartCause};
+
+use crate::{App, LoopMessage, LoopProxy, RunConfig, TaoEventHandler};
+
+/// Drives an `App` step by step without a windowing system.
+///
+/// Each `step` runs one loop iteration: `NewEvents`, everything sent through the app's
+/// proxies (user events, `App::inject_event`, timers due), then `MainEventsCleared` and
+/// `RedrawEventsCleared`. Window creation fails since there is no display, so tests inject
+/// window events for made-up window ids instead.
+#[derive(uniffi::Object)]
+pub struct TestEventLoop {
+    app: Arc<App>,
+    messages: Arc<Mutex<VecDeque<LoopMessage>>>,
+    started: AtomicBool,
+    destroyed: AtomicBool,
+}
+
+#[uniffi::export]
+impl TestEventLoop {
+    #[uniffi::constructor]
+    pub fn new(config: RunConfig) -> Self {
+        let messages = Arc::new(Mutex::new(VecDeque::new()));
+        Self {
+            app: App::with_proxy(LoopProxy::Headless(messages.clone()), &config),
+            messages,
+            started: AtomicBool::new(false),
+            destroyed: AtomicBool::new(false),
+        }
+    }
+
+    pub fn app(&self) -> Arc<App> {
+        self.app.clone()
+    }
+
+    /// Runs one loop iteration. Returns the exit code once the handler (or a request) exits.
+    pub fn step(&self, handler: Box<dyn TaoEventHandler>) -> Option<i32> {
+        let cause = if self.started.swap(true, Ordering::SeqCst) {
+            StartCause::Poll
+        } else {
+            StartCause::Init
+        };
+        let mut control_flow = tao::event_loop::ControlFlow::Poll;
+        let mut dispatch = |event: Event<'_, LoopMessage>| {
+            self.app.dispatch(event, &mut control_flow, |event| {
+                handler.handle_event(event, self.app.clone())
+            });
+        };
+
+        dispatch(Event::NewEvents(cause));
+        // Messages sent while handling these are picked up in the same iteration.
+        loop {
+            let Some(message) = self.messages.lock().unwrap().pop_front() else {
+                break;
+            };
+            dispatch(Event::UserEvent(message));
+        }
+        dispatch(Event::MainEventsCleared);
+        dispatch(Event::RedrawEventsCleared);
+
+        match control_flow {
+            tao::event_loop::ControlFlow::ExitWithCode(code) => Some(code),
+            _ => None,
+        }
+    }
+
+    /// Delivers `LoopDestroyed` and runs the exit hooks. Only the first call has an effect.
+    pub fn destroy(&self, handler: Box<dyn TaoEventHandler>) {
+        if self.destroyed.swap(true, Ordering::SeqCst) {
+            return;
+        }
+        let mut control_flow = tao::event_loop::ControlFlow::Exit;
+        self.app
+            .dispatch(Event::LoopDestroyed, &mut control_flow, |event| {
+                handler.handle_event(event, self.app.clone())
+            });
+    }
+}
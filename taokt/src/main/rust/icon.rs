@@ -1,18 +1,30 @@
-use std::path::Path;
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+use image::{
+    imageops::{self, FilterType},
+    RgbaImage,
+};
 
 use crate::TaoError;
 
 #[derive(uniffi::Object)]
 pub struct Icon {
     pub(crate) inner: tao::window::Icon,
+    /// Source pixels for processing; `None` for icons loaded from native resources.
+    pixels: Option<RgbaImage>,
 }
 
 #[uniffi::export]
 impl Icon {
     #[uniffi::constructor]
     pub fn from_rgba(rgba: Vec<u8>, width: u32, height: u32) -> Result<Self, TaoError> {
+        let inner = tao::window::Icon::from_rgba(rgba.clone(), width, height)?;
         Ok(Self {
-            inner: tao::window::Icon::from_rgba(rgba, width, height)?,
+            inner,
+            pixels: RgbaImage::from_raw(width, height, rgba),
         })
     }
 
@@ -24,6 +36,326 @@ impl Icon {
         let (rgba, width, height) = load_rgba(path.as_ref())?;
         Self::from_rgba(rgba, width, height)
     }
+
+    /// Decode a PNG, ICO, ICNS or JPEG image held in memory, e.g. a resource read from a JAR.
+    ///
+    /// For ICO and ICNS the largest image is used.
+    #[uniffi::constructor]
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self, TaoError> {
+        let (rgba, width, height) = decode_rgba(&data)?;
+        Self::from_rgba(rgba, width, height)
+    }
+
+    /// Load an icon resource from the running executable by its ordinal id (Windows only).
+    ///
+    /// `size` picks the resolution to load; `None` uses the system's default icon size.
+    #[uniffi::constructor]
+    pub fn from_resource(id: u16, size: Option<u32>) -> Result<Self, TaoError> {
+        #[cfg(target_os = "windows")]
+        {
+            use tao::platform::windows::IconExtWindows;
+
+            let size = size.map(|size| tao::dpi::PhysicalSize::new(size, size));
+            return Ok(Self {
+                inner: tao::window::Icon::from_resource(id, size)?,
+                pixels: None,
+            });
+        }
+
+        #[allow(unreachable_code)]
+        {
+            let _ = (id, size);
+            Err(TaoError::Unsupported)
+        }
+    }
+
+    /// Load a named image from the app bundle, e.g. an asset-catalog entry or an `.icns`
+    /// resource without its extension (macOS only). The largest representation is used.
+    #[uniffi::constructor]
+    pub fn from_named_image(name: String) -> Result<Self, TaoError> {
+        #[cfg(target_os = "macos")]
+        {
+            let png = cocoa::named_image_png(&name)
+                .ok_or_else(|| TaoError::message(format!("No image named {name:?} in the app bundle")))?;
+            return Self::from_bytes(png);
+        }
+
+        #[allow(unreachable_code)]
+        {
+            let _ = name;
+            Err(TaoError::Unsupported)
+        }
+    }
+
+    /// Load an icon from the current icon theme at `size` pixels, e.g. `"org.example.App"`
+    /// (Linux only; call it after the event loop was created).
+    #[uniffi::constructor]
+    pub fn from_theme(name: String, size: u32) -> Result<Self, TaoError> {
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        {
+            use gtk::{prelude::IconThemeExt, IconLookupFlags, IconTheme};
+
+            let theme = IconTheme::default().ok_or_else(|| TaoError::message("No default icon theme"))?;
+            let pixbuf = theme
+                .load_icon(&name, size as i32, IconLookupFlags::FORCE_SIZE)
+                .map_err(|e| TaoError::message(format!("Failed to load theme icon {name:?}: {e}")))?
+                .ok_or_else(|| TaoError::message(format!("No theme icon named {name:?}")))?;
+            let (width, height) = (pixbuf.width() as u32, pixbuf.height() as u32);
+            let rgba = packed_rgba(
+                &pixbuf.read_pixel_bytes(),
+                width,
+                height,
+                pixbuf.rowstride() as usize,
+                pixbuf.n_channels() as usize,
+            );
+            return Self::from_rgba(rgba, width, height);
+        }
+
+        #[allow(unreachable_code)]
+        {
+            let _ = (name, size);
+            Err(TaoError::Unsupported)
+        }
+    }
+
+    /// A copy scaled to `width` x `height` pixels.
+    pub fn resized(&self, width: u32, height: u32) -> Result<Arc<Icon>, TaoError> {
+        let pixels = imageops::resize(self.pixels()?, width.max(1), height.max(1), FilterType::Lanczos3);
+        Self::from_image(pixels)
+    }
+
+    /// A copy cut to the circle inscribed in the icon, e.g. for avatar badges.
+    pub fn with_circular_mask(&self) -> Result<Arc<Icon>, TaoError> {
+        Self::from_image(circular_mask(self.pixels()?))
+    }
+
+    /// A copy with `padding` transparent pixels added on every side.
+    ///
+    /// Combine with `resized` to shrink the artwork inside a fixed-size tray or badge icon.
+    pub fn with_padding(&self, padding: u32) -> Result<Arc<Icon>, TaoError> {
+        Self::from_image(pad(self.pixels()?, padding))
+    }
+}
+
+impl Icon {
+    /// The RGBA pixels, width and height, unless loaded from native resources.
+    pub(crate) fn rgba(&self) -> Option<(Vec<u8>, u32, u32)> {
+        let pixels = self.pixels.as_ref()?;
+        Some((pixels.as_raw().clone(), pixels.width(), pixels.height()))
+    }
+
+    fn pixels(&self) -> Result<&RgbaImage, TaoError> {
+        self.pixels
+            .as_ref()
+            .ok_or_else(|| TaoError::message("Icons loaded from native resources cannot be processed"))
+    }
+
+    fn from_image(pixels: RgbaImage) -> Result<Arc<Icon>, TaoError> {
+        let (width, height) = pixels.dimensions();
+        Ok(Arc::new(Self::from_rgba(pixels.into_raw(), width, height)?))
+    }
+}
+
+struct IconVariant {
+    size: u32,
+    icon: Arc<Icon>,
+}
+
+/// The same icon at several resolutions.
+///
+/// Attached with `Window::set_window_icon_set`, the window picks the variant matching its
+/// current scale factor and re-selects it when the scale factor or monitor changes.
+#[derive(uniffi::Object)]
+pub struct IconSet {
+    logical_size: u32,
+    variants: Mutex<Vec<IconVariant>>,
+}
+
+#[uniffi::export]
+impl IconSet {
+    /// `logical_size` is the icon size at a scale factor of 1.0 (typically 32).
+    #[uniffi::constructor]
+    pub fn new(logical_size: u32) -> Self {
+        Self {
+            logical_size: logical_size.max(1),
+            variants: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn add_rgba(&self, rgba: Vec<u8>, width: u32, height: u32) -> Result<(), TaoError> {
+        let icon = Icon::from_rgba(rgba, width, height)?;
+        self.add(icon, width.max(height));
+        Ok(())
+    }
+
+    pub fn add_file(&self, path: String) -> Result<(), TaoError> {
+        let (rgba, width, height) = load_rgba(path.as_ref())?;
+        self.add_rgba(rgba, width, height)
+    }
+
+    /// Returns the variant best suited for `scale_factor`, if any were added.
+    pub fn best_for_scale(&self, scale_factor: f64) -> Option<Arc<Icon>> {
+        let variants = self.variants.lock().unwrap();
+        let sizes: Vec<u32> = variants.iter().map(|v| v.size).collect();
+        select_icon_size(&sizes, self.desired_size(scale_factor)).map(|i| variants[i].icon.clone())
+    }
+}
+
+impl IconSet {
+    fn add(&self, icon: Icon, size: u32) {
+        let mut variants = self.variants.lock().unwrap();
+        variants.retain(|v| v.size != size);
+        variants.push(IconVariant {
+            size,
+            icon: Arc::new(icon),
+        });
+    }
+
+    pub(crate) fn desired_size(&self, scale_factor: f64) -> u32 {
+        (self.logical_size as f64 * scale_factor).round() as u32
+    }
+}
+
+/// Picks the smallest size that is at least `desired`, falling back to the largest one.
+pub(crate) fn select_icon_size(sizes: &[u32], desired: u32) -> Option<usize> {
+    let larger = sizes
+        .iter()
+        .enumerate()
+        .filter(|(_, size)| **size >= desired)
+        .min_by_key(|(_, size)| **size);
+    larger
+        .or_else(|| sizes.iter().enumerate().max_by_key(|(_, size)| **size))
+        .map(|(i, _)| i)
+}
+
+/// `image` with pixels outside its inscribed circle made transparent; the edge is anti-aliased.
+pub(crate) fn circular_mask(image: &RgbaImage) -> RgbaImage {
+    let (width, height) = image.dimensions();
+    let (cx, cy) = (width as f64 / 2.0, height as f64 / 2.0);
+    let radius = cx.min(cy);
+    let mut masked = image.clone();
+    for (x, y, pixel) in masked.enumerate_pixels_mut() {
+        let distance = (x as f64 + 0.5 - cx).hypot(y as f64 + 0.5 - cy);
+        let coverage = (radius - distance + 0.5).clamp(0.0, 1.0);
+        pixel[3] = (pixel[3] as f64 * coverage).round() as u8;
+    }
+    masked
+}
+
+/// `image` centered on a transparent canvas `padding` pixels larger on every side.
+pub(crate) fn pad(image: &RgbaImage, padding: u32) -> RgbaImage {
+    let (width, height) = image.dimensions();
+    let mut padded = RgbaImage::new(width + 2 * padding, height + 2 * padding);
+    imageops::replace(&mut padded, image, padding as i64, padding as i64);
+    padded
+}
+
+/// A red badge disc of `size` x `size` pixels with `text` in white, for taskbar overlays.
+///
+/// Uses a built-in 3x5 pixel font covering digits and `+ - ! ?`; other characters are skipped.
+pub(crate) fn render_badge(text: &str, size: u32) -> RgbaImage {
+    let size = size.max(1);
+    let disc = circular_mask(&RgbaImage::from_pixel(size, size, image::Rgba([220, 38, 38, 255])));
+    let glyphs: Vec<[u8; 5]> = text.chars().filter_map(badge_glyph).take(4).collect();
+    if glyphs.is_empty() {
+        return disc;
+    }
+
+    let mut badge = disc;
+    let columns = glyphs.len() as u32 * 4 - 1;
+    let unit = ((size as f64 * 0.7 / columns as f64).min(size as f64 * 0.6 / 5.0) as u32).max(1);
+    let left = size.saturating_sub(columns * unit) / 2;
+    let top = size.saturating_sub(5 * unit) / 2;
+    for (index, rows) in glyphs.iter().enumerate() {
+        for (row, bits) in rows.iter().enumerate() {
+            for column in 0..3 {
+                if bits & (0b100 >> column) == 0 {
+                    continue;
+                }
+                let x0 = left + (index as u32 * 4 + column) * unit;
+                let y0 = top + row as u32 * unit;
+                for y in y0..(y0 + unit).min(size) {
+                    for x in x0..(x0 + unit).min(size) {
+                        badge.put_pixel(x, y, image::Rgba([255, 255, 255, 255]));
+                    }
+                }
+            }
+        }
+    }
+    badge
+}
+
+/// Rows of a 3x5 glyph, most significant of the low three bits on the left.
+fn badge_glyph(c: char) -> Option<[u8; 5]> {
+    Some(match c {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        '+' => [0b000, 0b010, 0b111, 0b010, 0b000],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '!' => [0b010, 0b010, 0b010, 0b000, 0b010],
+        '?' => [0b111, 0b001, 0b010, 0b000, 0b010],
+        _ => return None,
+    })
+}
+
+fn decode_rgba(data: &[u8]) -> Result<(Vec<u8>, u32, u32), TaoError> {
+    // The image crate has no ICNS decoder, but modern ICNS entries are PNG streams.
+    let data = largest_icns_png(data).unwrap_or(data);
+    let image = image::load_from_memory(data)
+        .map_err(|e| TaoError::message(format!("Failed to decode icon: {e}")))?
+        .into_rgba8();
+    let (width, height) = image.dimensions();
+    Ok((image.into_raw(), width, height))
+}
+
+/// The largest PNG-encoded entry of an ICNS container, or `None` if `data` is not one.
+pub(crate) fn largest_icns_png(data: &[u8]) -> Option<&[u8]> {
+    const PNG_SIGNATURE: &[u8] = b"\x89PNG\r\n\x1a\n";
+
+    if data.get(..4)? != b"icns" {
+        return None;
+    }
+    let mut best: Option<(u32, &[u8])> = None;
+    let mut offset = 8;
+    while let Some(header) = data.get(offset..offset + 8) {
+        let len = u32::from_be_bytes(header[4..8].try_into().unwrap()) as usize;
+        let entry = data.get(offset + 8..offset + len.max(8))?;
+        if entry.starts_with(PNG_SIGNATURE) && entry.len() >= 24 {
+            // IHDR follows the signature: length, type, then big-endian width.
+            let width = u32::from_be_bytes(entry[16..20].try_into().unwrap());
+            if best.is_none_or(|(size, _)| width > size) {
+                best = Some((width, entry));
+            }
+        }
+        offset += len.max(8);
+    }
+    best.map(|(_, entry)| entry)
+}
+
+/// Tightly packed RGBA from rows of `channels`-byte RGB or RGBA pixels, `rowstride` bytes apart.
+pub(crate) fn packed_rgba(pixels: &[u8], width: u32, height: u32, rowstride: usize, channels: usize) -> Vec<u8> {
+    let mut rgba = Vec::with_capacity(width as usize * height as usize * 4);
+    for row in pixels.chunks(rowstride).take(height as usize) {
+        for pixel in row.chunks_exact(channels).take(width as usize) {
+            rgba.extend_from_slice(&pixel[..3]);
+            rgba.push(if channels >= 4 { pixel[3] } else { u8::MAX });
+        }
+    }
+    rgba
 }
 
 fn load_rgba(path: &Path) -> Result<(Vec<u8>, u32, u32), TaoError> {
@@ -34,3 +366,45 @@ fn load_rgba(path: &Path) -> Result<(Vec<u8>, u32, u32), TaoError> {
     Ok((image.into_raw(), width, height))
 }
 
+
+#[cfg(target_os = "macos")]
+mod cocoa {
+    use std::ffi::CString;
+
+    use objc2::{class, msg_send, runtime::AnyObject};
+
+    const NS_BITMAP_IMAGE_FILE_TYPE_PNG: usize = 4;
+
+    /// PNG encoding of the largest bitmap representation of `[NSImage imageNamed:name]`.
+    pub(super) fn named_image_png(name: &str) -> Option<Vec<u8>> {
+        let name = CString::new(name).ok()?;
+        unsafe {
+            let name: *mut AnyObject = msg_send![class!(NSString), stringWithUTF8String: name.as_ptr()];
+            let image: *mut AnyObject = msg_send![class!(NSImage), imageNamed: name];
+            if image.is_null() {
+                return None;
+            }
+            let tiff: *mut AnyObject = msg_send![&*image, TIFFRepresentation];
+            if tiff.is_null() {
+                return None;
+            }
+            let reps: *mut AnyObject = msg_send![class!(NSBitmapImageRep), imageRepsWithData: tiff];
+            let count: usize = if reps.is_null() { 0 } else { msg_send![&*reps, count] };
+            let rep: *mut AnyObject = (0..count)
+                .map(|i| -> *mut AnyObject { msg_send![&*reps, objectAtIndex: i] })
+                .max_by_key(|rep| -> isize { msg_send![&**rep, pixelsWide] })?;
+            let properties: *mut AnyObject = msg_send![class!(NSDictionary), dictionary];
+            let png: *mut AnyObject = msg_send![
+                &*rep,
+                representationUsingType: NS_BITMAP_IMAGE_FILE_TYPE_PNG,
+                properties: properties
+            ];
+            if png.is_null() {
+                return None;
+            }
+            let length: usize = msg_send![&*png, length];
+            let bytes: *const u8 = msg_send![&*png, bytes];
+            Some(std::slice::from_raw_parts(bytes, length).to_vec())
+        }
+    }
+}
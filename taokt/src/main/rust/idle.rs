@@ -0,0 +1,148 @@
+//! Time since the user last used any input device, system-wide.
+
+use std::{
+    sync::{atomic::AtomicBool, Arc},
+    time::Duration,
+};
+
+use crate::TaoError;
+
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Reports crossings of an idle threshold.
+#[derive(Debug)]
+pub(crate) struct IdleTracker {
+    threshold_seconds: f64,
+    idle: bool,
+}
+
+impl IdleTracker {
+    pub(crate) fn new(threshold_seconds: u32) -> Self {
+        Self {
+            threshold_seconds: threshold_seconds as f64,
+            idle: false,
+        }
+    }
+
+    /// The new idle state if `idle_seconds` crossed the threshold since the last update.
+    pub(crate) fn update(&mut self, idle_seconds: f64) -> Option<bool> {
+        let idle = idle_seconds >= self.threshold_seconds;
+        (idle != self.idle).then(|| {
+            self.idle = idle;
+            idle
+        })
+    }
+}
+
+/// Polls the idle time until `stop` is set or `on_change` returns false.
+pub(crate) fn watch(
+    threshold_seconds: u32,
+    stop: Arc<AtomicBool>,
+    on_change: impl Fn(bool, f64) -> bool + Send + 'static,
+) {
+    let mut tracker = IdleTracker::new(threshold_seconds);
+    crate::poll::poll("taokt-idle", POLL_INTERVAL, stop, system_idle_seconds, move |seconds| {
+        match tracker.update(seconds) {
+            Some(idle) => on_change(idle, seconds),
+            None => true,
+        }
+    });
+}
+
+pub(crate) fn system_idle_seconds() -> Result<f64, TaoError> {
+    #[cfg(target_os = "windows")]
+    {
+        use windows_sys::Win32::{
+            System::SystemInformation::GetTickCount,
+            UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO},
+        };
+        let mut info = LASTINPUTINFO {
+            cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32,
+            dwTime: 0,
+        };
+        if unsafe { GetLastInputInfo(&mut info) } == 0 {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        // Both are 32-bit tick counts that wrap after 49.7 days.
+        let elapsed = unsafe { GetTickCount() }.wrapping_sub(info.dwTime);
+        return Ok(elapsed as f64 / 1000.0);
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        #[link(name = "CoreGraphics", kind = "framework")]
+        unsafe extern "C" {
+            fn CGEventSourceSecondsSinceLastEventType(state_id: i32, event_type: u32) -> f64;
+        }
+        const COMBINED_SESSION_STATE: i32 = 0;
+        const ANY_INPUT_EVENT_TYPE: u32 = !0;
+        return Ok(unsafe { CGEventSourceSecondsSinceLastEventType(COMBINED_SESSION_STATE, ANY_INPUT_EVENT_TYPE) });
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    {
+        return dbus::idle_seconds();
+    }
+
+    #[allow(unreachable_code)]
+    Err(TaoError::Unsupported)
+}
+
+/// Linux has no compositor-independent idle API; ask GNOME's idle monitor, then the
+/// freedesktop screensaver service that KDE and others implement.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+mod dbus {
+    use gtk::gio::{self, DBusCallFlags};
+
+    use crate::TaoError;
+
+    pub(super) fn idle_seconds() -> Result<f64, TaoError> {
+        let connection = crate::dbus::session()?;
+        let call = |name: &str, path: &str, interface: &str, method: &str| {
+            connection
+                .call_sync(
+                    Some(name),
+                    path,
+                    interface,
+                    method,
+                    None,
+                    None,
+                    DBusCallFlags::NONE,
+                    1000,
+                    gio::Cancellable::NONE,
+                )
+                .ok()
+        };
+        let mutter = call(
+            "org.gnome.Mutter.IdleMonitor",
+            "/org/gnome/Mutter/IdleMonitor/Core",
+            "org.gnome.Mutter.IdleMonitor",
+            "GetIdletime",
+        );
+        if let Some(milliseconds) = mutter.and_then(|reply| reply.child_value(0).get::<u64>()) {
+            return Ok(milliseconds as f64 / 1000.0);
+        }
+        let screensaver = call(
+            "org.freedesktop.ScreenSaver",
+            "/org/freedesktop/ScreenSaver",
+            "org.freedesktop.ScreenSaver",
+            "GetSessionIdleTime",
+        );
+        if let Some(seconds) = screensaver.and_then(|reply| reply.child_value(0).get::<u32>()) {
+            return Ok(seconds as f64);
+        }
+        Err(TaoError::Unsupported)
+    }
+}
@@ -0,0 +1,312 @@
+//! Keeping the system or display awake: power requests on Windows, IOKit power assertions on
+//! macOS and the freedesktop inhibit portal on Linux.
+
+use std::sync::Mutex;
+
+use crate::TaoError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum SleepInhibition {
+    /// Keep the system running, e.g. for a long export; the display may still turn off.
+    System,
+    /// Keep the display on and the screensaver off, e.g. for video playback. Implies `System`.
+    Display,
+}
+
+/// Flags for `org.freedesktop.portal.Inhibit.Inhibit`: 1 is logout, 4 is suspend, 8 is idle.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    test
+))]
+pub(crate) fn portal_flags(inhibition: SleepInhibition) -> u32 {
+    match inhibition {
+        SleepInhibition::System => 4,
+        SleepInhibition::Display => 4 | 8,
+    }
+}
+
+/// Keeps the inhibition active until released or dropped.
+#[derive(uniffi::Object)]
+pub struct InhibitHandle {
+    token: Mutex<Option<platform::Token>>,
+}
+
+impl InhibitHandle {
+    pub(crate) fn new(reason: &str, inhibition: SleepInhibition) -> Result<Self, TaoError> {
+        Ok(Self {
+            token: Mutex::new(Some(platform::inhibit(reason, inhibition)?)),
+        })
+    }
+
+    /// Makes the desktop ask the user before logging out.
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    pub(crate) fn logout(reason: &str) -> Result<Self, TaoError> {
+        Ok(Self {
+            token: Mutex::new(Some(platform::inhibit_flags(reason, 1)?)),
+        })
+    }
+}
+
+#[uniffi::export]
+impl InhibitHandle {
+    /// Lets the system sleep again. Later calls do nothing.
+    pub fn release(&self) {
+        if let Some(token) = self.token.lock().unwrap().take() {
+            platform::release(token);
+        }
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.token.lock().unwrap().is_some()
+    }
+}
+
+impl Drop for InhibitHandle {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod platform {
+    use windows_sys::Win32::{
+        Foundation::{CloseHandle, HANDLE, INVALID_HANDLE_VALUE},
+        System::{
+            Power::{
+                PowerClearRequest, PowerCreateRequest, PowerRequestDisplayRequired, PowerRequestSystemRequired,
+                PowerSetRequest, POWER_REQUEST_TYPE,
+            },
+            Threading::{POWER_REQUEST_CONTEXT_SIMPLE_STRING, REASON_CONTEXT, REASON_CONTEXT_0},
+        },
+    };
+
+    use super::SleepInhibition;
+    use crate::TaoError;
+
+    pub(super) struct Token {
+        request: HANDLE,
+        types: Vec<POWER_REQUEST_TYPE>,
+    }
+
+    // Safety: power request handles may be used and closed from any thread.
+    unsafe impl Send for Token {}
+
+    pub(super) fn inhibit(reason: &str, inhibition: SleepInhibition) -> Result<Token, TaoError> {
+        let mut reason: Vec<u16> = reason.encode_utf16().chain(std::iter::once(0)).collect();
+        let context = REASON_CONTEXT {
+            Version: 0,
+            Flags: POWER_REQUEST_CONTEXT_SIMPLE_STRING,
+            Reason: REASON_CONTEXT_0 {
+                SimpleReasonString: reason.as_mut_ptr(),
+            },
+        };
+        let request = unsafe { PowerCreateRequest(&context) };
+        if request == INVALID_HANDLE_VALUE {
+            return Err(std::io::Error::last_os_error().into());
+        }
+        let types = match inhibition {
+            SleepInhibition::System => vec![PowerRequestSystemRequired],
+            SleepInhibition::Display => vec![PowerRequestSystemRequired, PowerRequestDisplayRequired],
+        };
+        for &kind in &types {
+            if unsafe { PowerSetRequest(request, kind) } == 0 {
+                let error = std::io::Error::last_os_error();
+                unsafe { CloseHandle(request) };
+                return Err(error.into());
+            }
+        }
+        Ok(Token { request, types })
+    }
+
+    pub(super) fn release(token: Token) {
+        unsafe {
+            for kind in token.types {
+                PowerClearRequest(token.request, kind);
+            }
+            CloseHandle(token.request);
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use std::ffi::c_void;
+
+    use super::SleepInhibition;
+    use crate::TaoError;
+
+    type CFStringRef = *const c_void;
+
+    #[link(name = "CoreFoundation", kind = "framework")]
+    unsafe extern "C" {
+        fn CFStringCreateWithBytes(
+            alloc: *const c_void,
+            bytes: *const u8,
+            len: isize,
+            encoding: u32,
+            is_external: u8,
+        ) -> CFStringRef;
+        fn CFRelease(cf: *const c_void);
+    }
+
+    #[link(name = "IOKit", kind = "framework")]
+    unsafe extern "C" {
+        fn IOPMAssertionCreateWithName(
+            assertion_type: CFStringRef,
+            level: u32,
+            name: CFStringRef,
+            assertion_id: *mut u32,
+        ) -> i32;
+        fn IOPMAssertionRelease(assertion_id: u32) -> i32;
+    }
+
+    const UTF8: u32 = 0x0800_0100;
+    const ASSERTION_LEVEL_ON: u32 = 255;
+
+    pub(super) struct Token {
+        assertions: Vec<u32>,
+    }
+
+    fn cf_string(value: &str) -> CFStringRef {
+        unsafe { CFStringCreateWithBytes(std::ptr::null(), value.as_ptr(), value.len() as isize, UTF8, 0) }
+    }
+
+    pub(super) fn inhibit(reason: &str, inhibition: SleepInhibition) -> Result<Token, TaoError> {
+        let types: &[&str] = match inhibition {
+            SleepInhibition::System => &["PreventUserIdleSystemSleep"],
+            SleepInhibition::Display => &["PreventUserIdleSystemSleep", "PreventUserIdleDisplaySleep"],
+        };
+        let name = cf_string(reason);
+        let mut token = Token { assertions: Vec::new() };
+        let mut result = Ok(());
+        for assertion_type in types {
+            let assertion_type = cf_string(assertion_type);
+            let mut id = 0;
+            let status = unsafe { IOPMAssertionCreateWithName(assertion_type, ASSERTION_LEVEL_ON, name, &mut id) };
+            unsafe { CFRelease(assertion_type) };
+            if status != 0 {
+                result = Err(TaoError::message(format!("IOPMAssertionCreateWithName failed: {status:#x}")));
+                break;
+            }
+            token.assertions.push(id);
+        }
+        unsafe { CFRelease(name) };
+        match result {
+            Ok(()) => Ok(token),
+            Err(e) => {
+                release(token);
+                Err(e)
+            }
+        }
+    }
+
+    pub(super) fn release(token: Token) {
+        for id in token.assertions {
+            unsafe { IOPMAssertionRelease(id) };
+        }
+    }
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+mod platform {
+    use std::collections::HashMap;
+
+    use gtk::{
+        gio::{self, DBusCallFlags},
+        glib::{ToVariant, Variant},
+    };
+
+    use super::{portal_flags, SleepInhibition};
+    use crate::TaoError;
+
+    const PORTAL: &str = "org.freedesktop.portal.Desktop";
+
+    pub(super) struct Token {
+        connection: gio::DBusConnection,
+        /// Object path of the portal request; closing it ends the inhibition.
+        request: String,
+    }
+
+    pub(super) fn inhibit(reason: &str, inhibition: SleepInhibition) -> Result<Token, TaoError> {
+        inhibit_flags(reason, portal_flags(inhibition))
+    }
+
+    pub(super) fn inhibit_flags(reason: &str, flags: u32) -> Result<Token, TaoError> {
+        let error = |e: gtk::glib::Error| TaoError::message(format!("Inhibit portal: {e}"));
+        let connection = crate::dbus::session()?;
+        let options = HashMap::from([("reason".to_string(), reason.to_variant())]);
+        let reply = connection
+            .call_sync(
+                Some(PORTAL),
+                "/org/freedesktop/portal/desktop",
+                "org.freedesktop.portal.Inhibit",
+                "Inhibit",
+                Some(&("", flags, options).to_variant()),
+                None,
+                DBusCallFlags::NONE,
+                -1,
+                gio::Cancellable::NONE,
+            )
+            .map_err(error)?;
+        let request = reply
+            .child_value(0)
+            .str()
+            .map(str::to_string)
+            .ok_or_else(|| TaoError::message("Inhibit portal returned no request handle"))?;
+        Ok(Token { connection, request })
+    }
+
+    pub(super) fn release(token: Token) {
+        let _: Result<Variant, _> = token.connection.call_sync(
+            Some(PORTAL),
+            &token.request,
+            "org.freedesktop.portal.Request",
+            "Close",
+            None,
+            None,
+            DBusCallFlags::NONE,
+            -1,
+            gio::Cancellable::NONE,
+        );
+    }
+}
+
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+)))]
+mod platform {
+    use super::SleepInhibition;
+    use crate::TaoError;
+
+    pub(super) enum Token {}
+
+    pub(super) fn inhibit(_reason: &str, _inhibition: SleepInhibition) -> Result<Token, TaoError> {
+        Err(TaoError::Unsupported)
+    }
+
+    pub(super) fn release(token: Token) {
+        match token {}
+    }
+}
@@ -0,0 +1,60 @@
+//! Numeric codes for native variants TaoKt has no dedicated mapping for.
+//!
+//! `Other` event variants carry a code instead of a formatted string, so unknown
+//! high-frequency events cross the FFI boundary without allocating. The
+//! description is formatted once per variant and fetched on demand with
+//! `describe_other`.
+//!
+//! `Key::Other` and `KeyCode::Other` keep their name: it is matched by
+//! accelerators and built by callers for synthesized input, so a code assigned
+//! at runtime would not do, and key presses come at typing rate. The
+//! `debug_string` methods are likewise left alone, being called on demand only.
+
+use std::{
+    collections::HashMap,
+    fmt::Debug,
+    hash::{DefaultHasher, Hash, Hasher},
+    sync::{LazyLock, Mutex},
+};
+
+#[derive(Default)]
+struct Names {
+    codes: HashMap<(&'static str, u64), u32>,
+    descriptions: Vec<String>,
+}
+
+static NAMES: LazyLock<Mutex<Names>> = LazyLock::new(|| Mutex::new(Names::default()));
+
+/// Returns the code for `value`'s variant, registering it on first sight.
+pub(crate) fn intern_variant<T: Debug>(value: &T) -> u32 {
+    let mut hasher = DefaultHasher::new();
+    std::mem::discriminant(value).hash(&mut hasher);
+    let key = (std::any::type_name::<T>(), hasher.finish());
+
+    let mut names = NAMES.lock().unwrap();
+    if let Some(code) = names.codes.get(&key) {
+        return *code;
+    }
+    let code = names.descriptions.len() as u32;
+    names.descriptions.push(describe::<T>(&format!("{value:?}")));
+    names.codes.insert(key, code);
+    code
+}
+
+/// Builds `Type::Variant` from a type name and a `Debug` rendering.
+pub(crate) fn describe<T>(debug: &str) -> String {
+    let type_name = std::any::type_name::<T>();
+    let type_name = type_name.split('<').next().unwrap_or(type_name);
+    let type_name = type_name.rsplit("::").next().unwrap_or(type_name);
+    let variant = debug
+        .split(|c: char| c == '(' || c == '{' || c.is_whitespace())
+        .next()
+        .unwrap_or(debug);
+    format!("{type_name}::{variant}")
+}
+
+/// Returns the description of a code found in an `Other` variant.
+#[uniffi::export]
+pub fn describe_other(code: u32) -> Option<String> {
+    NAMES.lock().unwrap().descriptions.get(code as usize).cloned()
+}
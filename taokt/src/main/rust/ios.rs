@@ -0,0 +1,230 @@
+//! UIKit settings that tao does not expose, and the on-screen keyboard.
+//!
+//! tao's view does not take text input, so showing the keyboard makes it a `UIKeyInput`
+//! responder that reports typed text as `KeyboardInput` events, like `Window::inject_text`.
+
+use std::{
+    ffi::CStr,
+    ptr::NonNull,
+    sync::{
+        atomic::{AtomicIsize, Ordering},
+        Arc, Mutex, Once, Weak,
+    },
+};
+
+use block2::RcBlock;
+use objc2::{
+    class, ffi, msg_send,
+    runtime::{AnyClass, AnyObject, AnyProtocol, Bool, Imp, Sel},
+    sel, Encode, Encoding,
+};
+use tao::platform::ios::WindowExtIOS;
+
+use crate::{events::NativeEventQueue, ElementState, KeyCode, LoopMessage, LoopProxy, StatusBarStyle, TaoWindowEvent};
+
+/// `UIStatusBarStyle` returned by the view controller.
+static STYLE: AtomicIsize = AtomicIsize::new(0);
+
+type PreferredStyle = unsafe extern "C-unwind" fn(*mut AnyObject, Sel) -> isize;
+
+unsafe extern "C-unwind" fn preferred_status_bar_style(_this: *mut AnyObject, _cmd: Sel) -> isize {
+    STYLE.load(Ordering::SeqCst)
+}
+
+/// Call on the main thread.
+pub(crate) fn set_status_bar_style(window: &tao::window::Window, style: StatusBarStyle) {
+    static HOOK: Once = Once::new();
+
+    let controller = window.ui_view_controller() as *mut AnyObject;
+    if controller.is_null() {
+        return;
+    }
+    let controller = unsafe { &*controller };
+    HOOK.call_once(|| unsafe {
+        let class: *const AnyClass = msg_send![controller, class];
+        let imp: Imp = std::mem::transmute(preferred_status_bar_style as PreferredStyle);
+        ffi::class_replaceMethod(class as *mut AnyClass, sel!(preferredStatusBarStyle), imp, c"q@:".as_ptr());
+    });
+    let value = match style {
+        StatusBarStyle::Default => 0,
+        StatusBarStyle::Light => 1,
+        StatusBarStyle::Dark => 3,
+    };
+    STYLE.store(value, Ordering::SeqCst);
+    let _: () = unsafe { msg_send![controller, setNeedsStatusBarAppearanceUpdate] };
+}
+
+/// Call on the main thread.
+pub(crate) fn set_idle_timer_disabled(disabled: bool) {
+    unsafe {
+        let app: *mut AnyObject = msg_send![class!(UIApplication), sharedApplication];
+        let _: () = msg_send![&*app, setIdleTimerDisabled: disabled];
+    }
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct CGPoint {
+    x: f64,
+    y: f64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct CGSize {
+    width: f64,
+    height: f64,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct CGRect {
+    origin: CGPoint,
+    size: CGSize,
+}
+
+unsafe impl Encode for CGPoint {
+    const ENCODING: Encoding = Encoding::Struct("CGPoint", &[f64::ENCODING, f64::ENCODING]);
+}
+
+unsafe impl Encode for CGSize {
+    const ENCODING: Encoding = Encoding::Struct("CGSize", &[f64::ENCODING, f64::ENCODING]);
+}
+
+unsafe impl Encode for CGRect {
+    const ENCODING: Encoding = Encoding::Struct("CGRect", &[CGPoint::ENCODING, CGSize::ENCODING]);
+}
+
+fn ns_string(value: &CStr) -> *mut AnyObject {
+    unsafe { msg_send![class!(NSString), stringWithUTF8String: value.as_ptr()] }
+}
+
+/// Whether the keyboard will be shown and how much of the screen it covers, in physical pixels.
+fn keyboard_change(notification: &AnyObject) -> Option<(bool, u32)> {
+    unsafe {
+        let info: *mut AnyObject = msg_send![notification, userInfo];
+        let value: *mut AnyObject = msg_send![&*info, objectForKey: ns_string(c"UIKeyboardFrameEndUserInfoKey")];
+        if value.is_null() {
+            return None;
+        }
+        let frame: CGRect = msg_send![&*value, CGRectValue];
+        let screen: *mut AnyObject = msg_send![class!(UIScreen), mainScreen];
+        let bounds: CGRect = msg_send![&*screen, bounds];
+        let scale: f64 = msg_send![&*screen, scale];
+        let height = (bounds.size.height - frame.origin.y).clamp(0.0, frame.size.height);
+        Some((height > 0.0, (height * scale).round() as u32))
+    }
+}
+
+/// Reports keyboard changes to `proxy` as `TaoEvent::SoftKeyboardChanged`.
+pub(crate) fn install(proxy: &LoopProxy) {
+    let proxy = proxy.clone();
+    let handler = RcBlock::new(move |notification: NonNull<AnyObject>| {
+        if let Some((visible, height)) = keyboard_change(unsafe { notification.as_ref() }) {
+            let _ = proxy.send_event(LoopMessage::SoftKeyboardChanged { visible, height });
+        }
+    });
+    unsafe {
+        let center: *mut AnyObject = msg_send![class!(NSNotificationCenter), defaultCenter];
+        // The notification center keeps the observer (and the block) for the rest of the process.
+        let _: *mut AnyObject = msg_send![
+            &*center,
+            addObserverForName: ns_string(c"UIKeyboardWillChangeFrameNotification"),
+            object: std::ptr::null_mut::<AnyObject>(),
+            queue: std::ptr::null_mut::<AnyObject>(),
+            usingBlock: &*handler
+        ];
+    }
+}
+
+/// A view showing the keyboard, the queue its text goes to and the proxy that wakes the loop
+/// to deliver it.
+type TextInput = (usize, Weak<Mutex<Vec<TaoWindowEvent>>>, LoopProxy);
+
+static TEXT_INPUTS: Mutex<Vec<TextInput>> = Mutex::new(Vec::new());
+
+fn push_input(view: *mut AnyObject, events: Vec<TaoWindowEvent>) {
+    let inputs = TEXT_INPUTS.lock().unwrap();
+    let Some((_, queue, proxy)) = inputs.iter().find(|(v, ..)| *v == view as usize) else {
+        return;
+    };
+    if let Some(queue) = queue.upgrade() {
+        queue.lock().unwrap().extend(events);
+        // The queue is drained when the loop finishes an iteration, which a key press alone
+        // does not start.
+        let _ = proxy.send_event(LoopMessage::Resume);
+    }
+}
+
+unsafe extern "C-unwind" fn yes(_this: *mut AnyObject, _cmd: Sel) -> Bool {
+    Bool::YES
+}
+
+unsafe extern "C-unwind" fn insert_text(this: *mut AnyObject, _cmd: Sel, text: *mut AnyObject) {
+    let utf8: *const std::ffi::c_char = unsafe { msg_send![&*text, UTF8String] };
+    if utf8.is_null() {
+        return;
+    }
+    let text = unsafe { CStr::from_ptr(utf8) }.to_string_lossy();
+    push_input(this, crate::synth::text_events(&text));
+}
+
+unsafe extern "C-unwind" fn delete_backward(this: *mut AnyObject, _cmd: Sel) {
+    let backspace = || KeyCode::Other {
+        value: "Backspace".to_string(),
+    };
+    push_input(
+        this,
+        vec![
+            crate::synth::key_event(backspace(), ElementState::Pressed),
+            crate::synth::key_event(backspace(), ElementState::Released),
+        ],
+    );
+}
+
+/// Turns tao's view class into a `UIKeyInput` responder.
+fn hook_text_input(view: &AnyObject) {
+    type Answer = unsafe extern "C-unwind" fn(*mut AnyObject, Sel) -> Bool;
+    type InsertText = unsafe extern "C-unwind" fn(*mut AnyObject, Sel, *mut AnyObject);
+    type DeleteBackward = unsafe extern "C-unwind" fn(*mut AnyObject, Sel);
+
+    unsafe {
+        let class: *const AnyClass = msg_send![view, class];
+        let class = class as *mut AnyClass;
+        let answer: Imp = std::mem::transmute(yes as Answer);
+        ffi::class_replaceMethod(class, sel!(canBecomeFirstResponder), answer, c"B@:".as_ptr());
+        // Always true so that backspace is reported on an empty field too.
+        ffi::class_replaceMethod(class, sel!(hasText), answer, c"B@:".as_ptr());
+        let insert: Imp = std::mem::transmute(insert_text as InsertText);
+        ffi::class_replaceMethod(class, sel!(insertText:), insert, c"v@:@".as_ptr());
+        let delete: Imp = std::mem::transmute(delete_backward as DeleteBackward);
+        ffi::class_replaceMethod(class, sel!(deleteBackward), delete, c"v@:".as_ptr());
+        if let Some(protocol) = AnyProtocol::get(c"UIKeyInput") {
+            ffi::class_addProtocol(class, protocol);
+        }
+    }
+}
+
+/// Call on the main thread.
+pub(crate) fn show_soft_keyboard(window: &tao::window::Window, queue: &NativeEventQueue, proxy: LoopProxy) {
+    static HOOK: Once = Once::new();
+
+    let view = window.ui_view() as *mut AnyObject;
+    if view.is_null() {
+        return;
+    }
+    HOOK.call_once(|| hook_text_input(unsafe { &*view }));
+    let mut inputs = TEXT_INPUTS.lock().unwrap();
+    inputs.retain(|(v, queue, _)| *v != view as usize && queue.strong_count() > 0);
+    inputs.push((view as usize, Arc::downgrade(queue), proxy));
+    drop(inputs);
+    let _: Bool = unsafe { msg_send![&*view, becomeFirstResponder] };
+}
+
+/// Call on the main thread.
+pub(crate) fn hide_soft_keyboard(window: &tao::window::Window) {
+    let view = window.ui_view() as *mut AnyObject;
+    if !view.is_null() {
+        let _: Bool = unsafe { msg_send![&*view, resignFirstResponder] };
+    }
+}
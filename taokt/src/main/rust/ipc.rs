@@ -0,0 +1,292 @@
+//! Messages from other processes to the running instance, e.g. a second launch forwarding its
+//! arguments before exiting.
+//!
+//! On Windows the instance owns a message-only window named after the channel and receives
+//! `WM_COPYDATA`; elsewhere it listens on a Unix domain socket in a directory private to the user.
+//! Each message arrives as one `TaoEvent::IpcMessage`.
+
+use crate::{LoopProxy, TaoError};
+
+/// Largest message accepted; senders get an error beyond it.
+pub(crate) const MAX_MESSAGE_LEN: usize = 16 * 1024 * 1024;
+
+/// Channel names end up in file and window names: 1 to 64 ASCII letters, digits, `-`, `_`, `.`.
+pub(crate) fn is_valid_channel(name: &str) -> bool {
+    (1..=64).contains(&name.len())
+        && !name.starts_with('.')
+        && name.bytes().all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.'))
+}
+
+fn check(name: &str) -> Result<(), TaoError> {
+    if is_valid_channel(name) {
+        Ok(())
+    } else {
+        Err(TaoError::message(format!("Invalid IPC channel name: {name:?}")))
+    }
+}
+
+pub(crate) fn listen(name: &str, proxy: &LoopProxy) -> Result<(), TaoError> {
+    check(name)?;
+
+    #[cfg(target_os = "windows")]
+    return win32::listen(name, proxy);
+
+    #[cfg(unix)]
+    return socket::listen(name, proxy);
+
+    #[allow(unreachable_code)]
+    {
+        let _ = proxy;
+        Err(TaoError::Unsupported)
+    }
+}
+
+/// Sends `data` to the instance listening on `name` with `App::listen_ipc`. Fails if there is
+/// none, so a second launch can tell whether it is the first. Empty messages are rejected.
+#[uniffi::export]
+pub fn send_ipc_message(name: String, data: Vec<u8>) -> Result<(), TaoError> {
+    check(&name)?;
+    if data.is_empty() || data.len() > MAX_MESSAGE_LEN {
+        return Err(TaoError::message(format!(
+            "IPC messages must be 1 to {MAX_MESSAGE_LEN} bytes, got {}",
+            data.len()
+        )));
+    }
+
+    #[cfg(target_os = "windows")]
+    return win32::send(&name, &data);
+
+    #[cfg(unix)]
+    return socket::send(&name, &data);
+
+    #[allow(unreachable_code)]
+    Err(TaoError::Unsupported)
+}
+
+#[cfg(target_os = "windows")]
+mod win32 {
+    use windows_sys::Win32::{
+        Foundation::{CloseHandle, GetLastError, ERROR_ALREADY_EXISTS, HANDLE, HWND, LPARAM, LRESULT, WPARAM},
+        System::{DataExchange::COPYDATASTRUCT, LibraryLoader::GetModuleHandleW, Threading::CreateMutexW},
+        UI::WindowsAndMessaging::{
+            CreateWindowExW, DefWindowProcW, FindWindowExW, GetWindowLongPtrW, RegisterClassW, SendMessageW,
+            SetWindowLongPtrW, GWLP_USERDATA, HWND_MESSAGE, WM_COPYDATA, WM_NCDESTROY, WNDCLASSW,
+        },
+    };
+
+    use super::MAX_MESSAGE_LEN;
+    use crate::{LoopMessage, LoopProxy, TaoError};
+
+    const CLASS: &str = "TaoKtIpc";
+    /// `COPYDATASTRUCT::dwData` of TaoKt messages; anything else is passed on.
+    const MAGIC: usize = 0x7461_6f6b;
+
+    fn wide(value: &str) -> Vec<u16> {
+        value.encode_utf16().chain(std::iter::once(0)).collect()
+    }
+
+    fn find(name: &str) -> HWND {
+        let (class, name) = (wide(CLASS), wide(name));
+        unsafe { FindWindowExW(HWND_MESSAGE, std::ptr::null_mut(), class.as_ptr(), name.as_ptr()) }
+    }
+
+    /// Claims the channel with a named mutex, so that of two instances starting at once only one
+    /// wins. The mutex is held until the process exits, unless `listen` fails and closes it.
+    fn claim(name: &str) -> Result<HANDLE, TaoError> {
+        let mutex_name = wide(&format!("Local\\TaoKtIpc-{name}"));
+        unsafe {
+            let mutex = CreateMutexW(std::ptr::null(), 0, mutex_name.as_ptr());
+            if mutex.is_null() {
+                return Err(std::io::Error::last_os_error().into());
+            }
+            if GetLastError() == ERROR_ALREADY_EXISTS {
+                CloseHandle(mutex);
+                return Err(TaoError::message(format!("IPC channel {name} is already in use")));
+            }
+            Ok(mutex)
+        }
+    }
+
+    /// Creates the channel window on the current thread, which must be the event loop's.
+    pub(super) fn listen(name: &str, proxy: &LoopProxy) -> Result<(), TaoError> {
+        let mutex = claim(name)?;
+        let class = wide(CLASS);
+        let title = wide(name);
+        unsafe {
+            let instance = GetModuleHandleW(std::ptr::null());
+            let mut window_class: WNDCLASSW = std::mem::zeroed();
+            window_class.lpfnWndProc = Some(ipc_proc);
+            window_class.hInstance = instance;
+            window_class.lpszClassName = class.as_ptr();
+            // Fails harmlessly once the class is registered.
+            RegisterClassW(&window_class);
+            let hwnd = CreateWindowExW(
+                0,
+                class.as_ptr(),
+                title.as_ptr(),
+                0,
+                0,
+                0,
+                0,
+                0,
+                HWND_MESSAGE,
+                std::ptr::null_mut(),
+                instance,
+                std::ptr::null(),
+            );
+            if hwnd.is_null() {
+                let error = std::io::Error::last_os_error();
+                // Releases the channel for a later attempt.
+                CloseHandle(mutex);
+                return Err(error.into());
+            }
+            let proxy = Box::into_raw(Box::new(proxy.clone()));
+            SetWindowLongPtrW(hwnd, GWLP_USERDATA, proxy as isize);
+        }
+        Ok(())
+    }
+
+    pub(super) fn send(name: &str, data: &[u8]) -> Result<(), TaoError> {
+        let hwnd = find(name);
+        if hwnd.is_null() {
+            return Err(TaoError::message(format!("No instance is listening on IPC channel {name}")));
+        }
+        let message = COPYDATASTRUCT {
+            dwData: MAGIC,
+            cbData: data.len() as u32,
+            lpData: data.as_ptr() as *mut _,
+        };
+        let accepted = unsafe { SendMessageW(hwnd, WM_COPYDATA, 0, &message as *const _ as LPARAM) };
+        if accepted == 0 {
+            return Err(TaoError::message(format!("IPC channel {name} did not accept the message")));
+        }
+        Ok(())
+    }
+
+    unsafe extern "system" fn ipc_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+        let proxy = unsafe { GetWindowLongPtrW(hwnd, GWLP_USERDATA) } as *mut LoopProxy;
+        match msg {
+            WM_COPYDATA if !proxy.is_null() => {
+                let message = unsafe { &*(lparam as *const COPYDATASTRUCT) };
+                let len = message.cbData as usize;
+                if message.dwData != MAGIC || len == 0 || len > MAX_MESSAGE_LEN {
+                    return 0;
+                }
+                let data = unsafe { std::slice::from_raw_parts(message.lpData as *const u8, len) }.to_vec();
+                return unsafe { &*proxy }.send_event(LoopMessage::IpcMessage(data)).is_ok() as LRESULT;
+            }
+            WM_NCDESTROY if !proxy.is_null() => unsafe {
+                SetWindowLongPtrW(hwnd, GWLP_USERDATA, 0);
+                drop(Box::from_raw(proxy));
+            },
+            _ => {}
+        }
+        unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) }
+    }
+}
+
+#[cfg(unix)]
+mod socket {
+    use std::{
+        fs::DirBuilder,
+        io::{ErrorKind, Read, Write},
+        os::unix::{
+            fs::{DirBuilderExt, MetadataExt},
+            net::{UnixListener, UnixStream},
+        },
+        path::{Path, PathBuf},
+        time::Duration,
+    };
+
+    use super::MAX_MESSAGE_LEN;
+    use crate::{LoopMessage, LoopProxy, TaoError};
+
+    /// How long a client may take to send its message.
+    const READ_TIMEOUT: Duration = Duration::from_secs(5);
+
+    fn uid() -> u32 {
+        unsafe { libc::getuid() }
+    }
+
+    /// A directory only the current user can write to: the runtime directory, or a 0700 directory
+    /// of our own in the shared temp directory, which must not be someone else's.
+    fn directory() -> Result<PathBuf, TaoError> {
+        if let Some(dir) = std::env::var_os("XDG_RUNTIME_DIR") {
+            return Ok(PathBuf::from(dir));
+        }
+        let dir = std::env::temp_dir().join(format!("taokt-{}", uid()));
+        if let Err(e) = DirBuilder::new().mode(0o700).create(&dir) {
+            if e.kind() != ErrorKind::AlreadyExists {
+                return Err(e.into());
+            }
+        }
+        let metadata = std::fs::symlink_metadata(&dir)?;
+        if !metadata.is_dir() || metadata.uid() != uid() || metadata.mode() & 0o077 != 0 {
+            return Err(TaoError::message(format!("{} is not a private directory", dir.display())));
+        }
+        Ok(dir)
+    }
+
+    fn path(name: &str) -> Result<PathBuf, TaoError> {
+        Ok(directory()?.join(format!("taokt-{name}.sock")))
+    }
+
+    /// Refuses sockets created by another user.
+    fn check_owner(path: &Path) -> Result<(), TaoError> {
+        match std::fs::symlink_metadata(path) {
+            Ok(metadata) if metadata.uid() != uid() => Err(TaoError::message(format!(
+                "{} belongs to another user",
+                path.display()
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    pub(super) fn listen(name: &str, proxy: &LoopProxy) -> Result<(), TaoError> {
+        let path = path(name)?;
+        let listener = match UnixListener::bind(&path) {
+            Ok(listener) => listener,
+            Err(e) if e.kind() == ErrorKind::AddrInUse => {
+                check_owner(&path)?;
+                if UnixStream::connect(&path).is_ok() {
+                    return Err(TaoError::message(format!("IPC channel {name} is already in use")));
+                }
+                // Left behind by an instance that did not exit cleanly.
+                std::fs::remove_file(&path)?;
+                UnixListener::bind(&path)?
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let proxy = proxy.clone();
+        let thread = std::thread::Builder::new().name("taokt-ipc".to_string());
+        thread.spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else {
+                    continue;
+                };
+                // A client that never finishes must not block the channel.
+                if stream.set_read_timeout(Some(READ_TIMEOUT)).is_err() {
+                    continue;
+                }
+                let mut data = Vec::new();
+                if stream.take(MAX_MESSAGE_LEN as u64).read_to_end(&mut data).is_err() || data.is_empty() {
+                    // Liveness probes from `listen` connect without sending anything.
+                    continue;
+                }
+                if proxy.send_event(LoopMessage::IpcMessage(data)).is_err() {
+                    break;
+                }
+            }
+        })?;
+        Ok(())
+    }
+
+    pub(super) fn send(name: &str, data: &[u8]) -> Result<(), TaoError> {
+        let path = path(name)?;
+        check_owner(&path)?;
+        let mut stream = UnixStream::connect(&path)
+            .map_err(|e| TaoError::message(format!("No instance is listening on IPC channel {name}: {e}")))?;
+        stream.write_all(data)?;
+        Ok(())
+    }
+}
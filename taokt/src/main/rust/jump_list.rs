@@ -0,0 +1,201 @@
+//! Windows taskbar jump lists.
+//!
+//! Every entry relaunches the current executable with a `--jump-list-task=<id>` argument.
+//! `launch_jump_list_task` returns that id at startup, and `EventLoopProxy::activate_jump_list_task`
+//! forwards ids received by a single-instance mechanism into the running loop as
+//! `TaoEvent::JumpListTask`.
+
+use crate::TaoError;
+
+const TASK_ARGUMENT: &str = "--jump-list-task=";
+
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct JumpListItem {
+    /// Reported in `TaoEvent::JumpListTask` when the item is clicked.
+    pub id: String,
+    pub title: String,
+    /// Tooltip text.
+    #[uniffi(default = None)]
+    pub description: Option<String>,
+    /// `.ico`, `.exe` or `.dll` holding the icon; defaults to the executable's icon.
+    #[uniffi(default = None)]
+    pub icon_path: Option<String>,
+    #[uniffi(default = 0)]
+    pub icon_index: i32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct JumpListCategory {
+    pub title: String,
+    pub items: Vec<JumpListItem>,
+}
+
+/// Categories maintained by the shell from the files the application opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum JumpListKnownCategory {
+    Recent,
+    Frequent,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, uniffi::Record)]
+pub struct JumpList {
+    /// Shown in the "Tasks" section at the bottom of the list.
+    #[uniffi(default = [])]
+    pub tasks: Vec<JumpListItem>,
+    #[uniffi(default = [])]
+    pub known_categories: Vec<JumpListKnownCategory>,
+    #[uniffi(default = [])]
+    pub custom_categories: Vec<JumpListCategory>,
+}
+
+/// Command-line argument that activates the item `id`.
+pub(crate) fn task_argument(id: &str) -> Result<String, TaoError> {
+    if id.is_empty() || id.contains(['"', '\0']) {
+        return Err(TaoError::message(format!("Invalid jump list item id: {id:?}")));
+    }
+    // Backslashes before the closing quote would escape it (CommandLineToArgvW); double them.
+    let trailing = &id[id.trim_end_matches('\\').len()..];
+    Ok(format!("\"{TASK_ARGUMENT}{id}{trailing}\""))
+}
+
+/// Returns the id of the jump list item in `args`, if any.
+pub(crate) fn task_in_args(args: impl IntoIterator<Item = String>) -> Option<String> {
+    args.into_iter()
+        .find_map(|arg| arg.strip_prefix(TASK_ARGUMENT).filter(|id| !id.is_empty()).map(str::to_string))
+}
+
+/// Returns the id of the jump list item this process was launched from.
+#[uniffi::export]
+pub fn launch_jump_list_task() -> Option<String> {
+    task_in_args(std::env::args().skip(1))
+}
+
+/// Replaces the application's jump list. Call on the main thread.
+#[uniffi::export]
+pub fn set_jump_list(jump_list: JumpList) -> Result<(), TaoError> {
+    let items = jump_list
+        .tasks
+        .iter()
+        .chain(jump_list.custom_categories.iter().flat_map(|category| &category.items));
+    for item in items {
+        task_argument(&item.id)?;
+    }
+
+    #[cfg(target_os = "windows")]
+    {
+        return shell::set(&jump_list);
+    }
+
+    #[allow(unreachable_code)]
+    Err(TaoError::Unsupported)
+}
+
+/// Removes the application's jump list.
+#[uniffi::export]
+pub fn clear_jump_list() -> Result<(), TaoError> {
+    #[cfg(target_os = "windows")]
+    {
+        return shell::clear();
+    }
+
+    #[allow(unreachable_code)]
+    Err(TaoError::Unsupported)
+}
+
+#[cfg(target_os = "windows")]
+mod shell {
+    use windows::{
+        core::{Interface, HSTRING, PROPVARIANT},
+        Win32::{
+            Storage::EnhancedStorage::PKEY_Title,
+            System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_INPROC_SERVER, COINIT_APARTMENTTHREADED},
+            UI::Shell::{
+                Common::{IObjectArray, IObjectCollection},
+                DestinationList, EnumerableObjectCollection, ICustomDestinationList, IShellLinkW,
+                PropertiesSystem::IPropertyStore,
+                ShellLink, KDC_FREQUENT, KDC_RECENT,
+            },
+        },
+    };
+
+    use super::{task_argument, JumpList, JumpListItem, JumpListKnownCategory};
+    use crate::TaoError;
+
+    impl From<windows::core::Error> for TaoError {
+        fn from(value: windows::core::Error) -> Self {
+            TaoError::message(value.message())
+        }
+    }
+
+    fn destination_list() -> Result<ICustomDestinationList, TaoError> {
+        unsafe {
+            // Already initialised (S_FALSE) or initialised as MTA by the host: both are fine.
+            let _ = CoInitializeEx(None, COINIT_APARTMENTTHREADED);
+            Ok(CoCreateInstance(&DestinationList, None, CLSCTX_INPROC_SERVER)?)
+        }
+    }
+
+    fn shell_link(item: &JumpListItem, exe: &HSTRING) -> Result<IShellLinkW, TaoError> {
+        unsafe {
+            let link: IShellLinkW = CoCreateInstance(&ShellLink, None, CLSCTX_INPROC_SERVER)?;
+            link.SetPath(exe)?;
+            link.SetArguments(&HSTRING::from(task_argument(&item.id)?))?;
+            if let Some(description) = &item.description {
+                link.SetDescription(&HSTRING::from(description))?;
+            }
+            if let Some(icon_path) = &item.icon_path {
+                link.SetIconLocation(&HSTRING::from(icon_path), item.icon_index)?;
+            }
+            let store: IPropertyStore = link.cast()?;
+            store.SetValue(&PKEY_Title, &PROPVARIANT::from(item.title.as_str()))?;
+            store.Commit()?;
+            Ok(link)
+        }
+    }
+
+    fn collection(items: &[JumpListItem], exe: &HSTRING) -> Result<IObjectArray, TaoError> {
+        unsafe {
+            let collection: IObjectCollection =
+                CoCreateInstance(&EnumerableObjectCollection, None, CLSCTX_INPROC_SERVER)?;
+            for item in items {
+                collection.AddObject(&shell_link(item, exe)?)?;
+            }
+            Ok(collection.cast()?)
+        }
+    }
+
+    pub(super) fn set(jump_list: &JumpList) -> Result<(), TaoError> {
+        let exe = HSTRING::from(std::env::current_exe()?.as_os_str());
+        let list = destination_list()?;
+        unsafe {
+            let mut max_slots = 0;
+            let _removed: IObjectArray = list.BeginList(&mut max_slots)?;
+            let result = (|| {
+                for category in &jump_list.custom_categories {
+                    list.AppendCategory(&HSTRING::from(&category.title), &collection(&category.items, &exe)?)?;
+                }
+                for category in &jump_list.known_categories {
+                    list.AppendKnownCategory(match category {
+                        JumpListKnownCategory::Recent => KDC_RECENT,
+                        JumpListKnownCategory::Frequent => KDC_FREQUENT,
+                    })?;
+                }
+                if !jump_list.tasks.is_empty() {
+                    list.AddUserTasks(&collection(&jump_list.tasks, &exe)?)?;
+                }
+                Ok::<_, TaoError>(())
+            })();
+            match result {
+                Ok(()) => Ok(list.CommitList()?),
+                Err(e) => {
+                    let _ = list.AbortList();
+                    Err(e)
+                }
+            }
+        }
+    }
+
+    pub(super) fn clear() -> Result<(), TaoError> {
+        unsafe { Ok(destination_list()?.DeleteList(None)?) }
+    }
+}
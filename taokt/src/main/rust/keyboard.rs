@@ -0,0 +1,61 @@
+//! Keyboard state that tao does not track itself.
+
+/// Toggle state of the lock keys.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, uniffi::Record)]
+pub struct LockKeysState {
+    pub caps_lock: bool,
+    /// Always false on macOS, which has no Num Lock.
+    pub num_lock: bool,
+}
+
+/// Queries the current lock key state from the OS.
+///
+/// On Linux this must be called from the event loop thread.
+#[uniffi::export]
+pub fn lock_keys_state() -> LockKeysState {
+    #[cfg(target_os = "windows")]
+    {
+        use windows_sys::Win32::UI::Input::KeyboardAndMouse::{GetKeyState, VK_CAPITAL, VK_NUMLOCK};
+        unsafe {
+            return LockKeysState {
+                caps_lock: GetKeyState(VK_CAPITAL as i32) & 1 != 0,
+                num_lock: GetKeyState(VK_NUMLOCK as i32) & 1 != 0,
+            };
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        #[link(name = "CoreGraphics", kind = "framework")]
+        unsafe extern "C" {
+            fn CGEventSourceFlagsState(state_id: i32) -> u64;
+        }
+        const COMBINED_SESSION_STATE: i32 = 0;
+        const ALPHA_SHIFT_MASK: u64 = 1 << 16;
+        let flags = unsafe { CGEventSourceFlagsState(COMBINED_SESSION_STATE) };
+        return LockKeysState {
+            caps_lock: flags & ALPHA_SHIFT_MASK != 0,
+            num_lock: false,
+        };
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    {
+        return match gtk::gdk::Keymap::default() {
+            Some(keymap) => LockKeysState {
+                caps_lock: keymap.caps_lock_state(),
+                num_lock: keymap.num_lock_state(),
+            },
+            None => LockKeysState::default(),
+        };
+    }
+
+    #[allow(unreachable_code)]
+    LockKeysState::default()
+}
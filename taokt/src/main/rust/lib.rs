@@ -1,21 +1,126 @@
+mod accelerator;
+mod accessibility;
+#[cfg(target_os = "android")]
+mod android;
 mod app;
+mod app_id;
+mod click;
+mod coalesce;
+mod color_scheme;
+mod cursor;
+mod damage;
+mod dbus;
+mod deep_link;
+mod devices;
+#[cfg(target_os = "macos")]
+mod display_link;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod dialog;
+mod dpi;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod drag_source;
 mod events;
+mod frame;
+mod gesture;
+#[cfg(feature = "opengl")]
+mod gl;
+mod grab;
 mod graphics;
+#[cfg(feature = "headless")]
+mod headless;
 mod icon;
+mod idle;
+mod inhibit;
+mod intern;
+mod ipc;
+#[cfg(target_os = "ios")]
+mod ios;
+mod jump_list;
+mod keyboard;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod menu;
+mod metrics;
 mod monitor;
+mod msg_hook;
+mod notification;
+#[cfg(feature = "wgpu")]
+mod offscreen;
+mod pen;
+mod poll;
+mod probe;
+mod queue;
+mod quit;
+mod session;
+mod shortcut;
+mod synth;
+mod thread;
+mod timer;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+mod tray;
 mod types;
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+mod wayland;
 mod window;
+#[cfg(feature = "wgpu")]
+mod wgpu_surface;
 
 #[cfg(test)]
 mod tests;
 
+pub use accelerator::*;
+pub use accessibility::*;
+#[cfg(target_os = "android")]
+pub use android::*;
 pub use app::*;
+#[cfg(feature = "dbus")]
+pub use dbus::{DBusBus, DBusMethod};
+pub use deep_link::*;
+pub use devices::*;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+pub use dialog::*;
+pub use dpi::*;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+pub use drag_source::*;
 pub use events::*;
+pub use frame::*;
+pub use gesture::*;
+#[cfg(feature = "opengl")]
+pub use gl::*;
+pub use grab::*;
 pub use graphics::*;
+#[cfg(feature = "headless")]
+pub use headless::*;
 pub use icon::*;
+pub use inhibit::*;
+pub use intern::*;
+pub use ipc::*;
+pub use jump_list::*;
+pub use keyboard::*;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+pub use menu::*;
+pub use metrics::*;
 pub use monitor::*;
+pub use notification::*;
+#[cfg(feature = "wgpu")]
+pub use offscreen::*;
+pub use pen::*;
+pub use probe::*;
+pub use queue::*;
+pub use session::*;
+pub use shortcut::*;
+pub use thread::*;
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+pub use tray::*;
 pub use types::*;
 pub use window::*;
+#[cfg(feature = "wgpu")]
+pub use wgpu_surface::*;
 
 uniffi::setup_scaffolding!();
 
@@ -0,0 +1,420 @@
+//! Native menus backed by muda.
+//!
+//! muda menus are not thread-safe, so every object here belongs to the thread that created
+//! it (the event loop thread) and its methods fail elsewhere. Activations are delivered as
+//! `TaoEvent::MenuEvent` with the id the item was created with.
+
+use std::sync::Arc;
+
+use crate::{thread::ThreadBound, Accelerator, Icon, TaoError};
+
+impl From<muda::Error> for TaoError {
+    fn from(value: muda::Error) -> Self {
+        TaoError::message(value.to_string())
+    }
+}
+
+fn accelerator(accelerator: Option<String>) -> Result<Option<muda::accelerator::Accelerator>, TaoError> {
+    accelerator
+        .map(|accelerator| Accelerator::parse(accelerator)?.to_muda())
+        .transpose()
+}
+
+fn muda_icon(icon: Option<Arc<Icon>>) -> Result<Option<muda::Icon>, TaoError> {
+    icon.map(|icon| {
+        let (rgba, width, height) = icon
+            .rgba()
+            .ok_or_else(|| TaoError::message("Icons loaded from native resources cannot be used in menus"))?;
+        muda::Icon::from_rgba(rgba, width, height).map_err(|e| TaoError::message(e.to_string()))
+    })
+    .transpose()
+}
+
+/// A menu bar (Windows, Linux, app menu on macOS) or context menu.
+#[derive(uniffi::Object)]
+pub struct Menu {
+    pub(crate) inner: ThreadBound<muda::Menu>,
+}
+
+#[uniffi::export]
+impl Menu {
+    #[uniffi::constructor]
+    pub fn new() -> Self {
+        Self {
+            inner: ThreadBound::new(muda::Menu::new()),
+        }
+    }
+
+    /// The standard macOS App, Edit and Window menus.
+    ///
+    /// Without an Edit menu, Cmd+C, Cmd+V and the other editing shortcuts do nothing in text
+    /// fields on macOS. Append further submenus after these.
+    #[uniffi::constructor]
+    pub fn default_macos_app_menu(app_name: String) -> Result<Self, TaoError> {
+        use muda::{AboutMetadata, PredefinedMenuItem as Item};
+
+        let about = AboutMetadata {
+            name: Some(app_name.clone()),
+            ..Default::default()
+        };
+        let app = muda::Submenu::with_items(
+            &app_name,
+            true,
+            &[
+                &Item::about(Some(&format!("About {app_name}")), Some(about)),
+                &Item::separator(),
+                &Item::services(None),
+                &Item::separator(),
+                &Item::hide(Some(&format!("Hide {app_name}"))),
+                &Item::hide_others(None),
+                &Item::show_all(None),
+                &Item::separator(),
+                &Item::quit(Some(&format!("Quit {app_name}"))),
+            ],
+        )?;
+        let edit = muda::Submenu::with_items(
+            "Edit",
+            true,
+            &[
+                &Item::undo(None),
+                &Item::redo(None),
+                &Item::separator(),
+                &Item::cut(None),
+                &Item::copy(None),
+                &Item::paste(None),
+                &Item::select_all(None),
+            ],
+        )?;
+        let window = muda::Submenu::with_items(
+            "Window",
+            true,
+            &[
+                &Item::minimize(None),
+                &Item::maximize(None),
+                &Item::fullscreen(None),
+                &Item::separator(),
+                &Item::bring_all_to_front(None),
+            ],
+        )?;
+        #[cfg(target_os = "macos")]
+        window.set_as_windows_menu_for_nsapp();
+
+        Ok(Self {
+            inner: ThreadBound::new(muda::Menu::with_items(&[&app, &edit, &window])?),
+        })
+    }
+
+    pub fn append_submenu(&self, submenu: Arc<Submenu>) -> Result<(), TaoError> {
+        Ok(self.inner.get()?.append(submenu.inner.get()?)?)
+    }
+
+    pub fn append_item(&self, item: Arc<MenuItem>) -> Result<(), TaoError> {
+        Ok(self.inner.get()?.append(item.inner.get()?)?)
+    }
+
+    pub fn append_check_item(&self, item: Arc<CheckMenuItem>) -> Result<(), TaoError> {
+        Ok(self.inner.get()?.append(item.inner.get()?)?)
+    }
+
+    pub fn append_icon_item(&self, item: Arc<IconMenuItem>) -> Result<(), TaoError> {
+        Ok(self.inner.get()?.append(item.inner.get()?)?)
+    }
+
+    pub fn append_separator(&self) -> Result<(), TaoError> {
+        Ok(self.inner.get()?.append(&muda::PredefinedMenuItem::separator())?)
+    }
+}
+
+impl Default for Menu {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A menu with a title, nested in a `Menu` or another `Submenu`.
+#[derive(uniffi::Object)]
+pub struct Submenu {
+    pub(crate) inner: ThreadBound<muda::Submenu>,
+}
+
+#[uniffi::export]
+impl Submenu {
+    #[uniffi::constructor]
+    pub fn new(text: String, enabled: bool) -> Self {
+        Self {
+            inner: ThreadBound::new(muda::Submenu::new(text, enabled)),
+        }
+    }
+
+    pub fn append_submenu(&self, submenu: Arc<Submenu>) -> Result<(), TaoError> {
+        Ok(self.inner.get()?.append(submenu.inner.get()?)?)
+    }
+
+    pub fn append_item(&self, item: Arc<MenuItem>) -> Result<(), TaoError> {
+        Ok(self.inner.get()?.append(item.inner.get()?)?)
+    }
+
+    pub fn append_check_item(&self, item: Arc<CheckMenuItem>) -> Result<(), TaoError> {
+        Ok(self.inner.get()?.append(item.inner.get()?)?)
+    }
+
+    pub fn append_icon_item(&self, item: Arc<IconMenuItem>) -> Result<(), TaoError> {
+        Ok(self.inner.get()?.append(item.inner.get()?)?)
+    }
+
+    pub fn append_separator(&self) -> Result<(), TaoError> {
+        Ok(self.inner.get()?.append(&muda::PredefinedMenuItem::separator())?)
+    }
+
+    pub fn set_text(&self, text: String) -> Result<(), TaoError> {
+        self.inner.get()?.set_text(text);
+        Ok(())
+    }
+
+    pub fn set_enabled(&self, enabled: bool) -> Result<(), TaoError> {
+        self.inner.get()?.set_enabled(enabled);
+        Ok(())
+    }
+}
+
+/// A clickable menu entry.
+///
+/// `accelerator` uses the `Accelerator` syntax, e.g. `"CmdOrCtrl+Shift+S"`.
+#[derive(uniffi::Object)]
+pub struct MenuItem {
+    id: String,
+    pub(crate) inner: ThreadBound<muda::MenuItem>,
+}
+
+#[uniffi::export]
+impl MenuItem {
+    #[uniffi::constructor]
+    pub fn new(id: String, text: String, enabled: bool, accelerator: Option<String>) -> Result<Self, TaoError> {
+        let item = muda::MenuItem::with_id(id.clone(), text, enabled, self::accelerator(accelerator)?);
+        Ok(Self {
+            id,
+            inner: ThreadBound::new(item),
+        })
+    }
+
+    /// The id reported in `TaoEvent::MenuEvent` when this item is activated.
+    pub fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    pub fn set_text(&self, text: String) -> Result<(), TaoError> {
+        self.inner.get()?.set_text(text);
+        Ok(())
+    }
+
+    pub fn set_enabled(&self, enabled: bool) -> Result<(), TaoError> {
+        self.inner.get()?.set_enabled(enabled);
+        Ok(())
+    }
+
+    pub fn set_accelerator(&self, accelerator: Option<String>) -> Result<(), TaoError> {
+        Ok(self.inner.get()?.set_accelerator(self::accelerator(accelerator)?)?)
+    }
+}
+
+/// A menu entry with a check mark that toggles when activated.
+#[derive(uniffi::Object)]
+pub struct CheckMenuItem {
+    id: String,
+    pub(crate) inner: ThreadBound<muda::CheckMenuItem>,
+}
+
+#[uniffi::export]
+impl CheckMenuItem {
+    #[uniffi::constructor]
+    pub fn new(
+        id: String,
+        text: String,
+        enabled: bool,
+        checked: bool,
+        accelerator: Option<String>,
+    ) -> Result<Self, TaoError> {
+        let item = muda::CheckMenuItem::with_id(id.clone(), text, enabled, checked, self::accelerator(accelerator)?);
+        Ok(Self {
+            id,
+            inner: ThreadBound::new(item),
+        })
+    }
+
+    pub fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    pub fn is_checked(&self) -> Result<bool, TaoError> {
+        Ok(self.inner.get()?.is_checked())
+    }
+
+    pub fn set_checked(&self, checked: bool) -> Result<(), TaoError> {
+        self.inner.get()?.set_checked(checked);
+        Ok(())
+    }
+
+    pub fn set_text(&self, text: String) -> Result<(), TaoError> {
+        self.inner.get()?.set_text(text);
+        Ok(())
+    }
+
+    pub fn set_enabled(&self, enabled: bool) -> Result<(), TaoError> {
+        self.inner.get()?.set_enabled(enabled);
+        Ok(())
+    }
+}
+
+/// A menu entry with an icon next to its text.
+#[derive(uniffi::Object)]
+pub struct IconMenuItem {
+    id: String,
+    pub(crate) inner: ThreadBound<muda::IconMenuItem>,
+}
+
+#[uniffi::export]
+impl IconMenuItem {
+    #[uniffi::constructor]
+    pub fn new(
+        id: String,
+        text: String,
+        enabled: bool,
+        icon: Option<Arc<Icon>>,
+        accelerator: Option<String>,
+    ) -> Result<Self, TaoError> {
+        let item =
+            muda::IconMenuItem::with_id(id.clone(), text, enabled, muda_icon(icon)?, self::accelerator(accelerator)?);
+        Ok(Self {
+            id,
+            inner: ThreadBound::new(item),
+        })
+    }
+
+    pub fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    pub fn set_icon(&self, icon: Option<Arc<Icon>>) -> Result<(), TaoError> {
+        self.inner.get()?.set_icon(muda_icon(icon)?);
+        Ok(())
+    }
+
+    pub fn set_text(&self, text: String) -> Result<(), TaoError> {
+        self.inner.get()?.set_text(text);
+        Ok(())
+    }
+
+    pub fn set_enabled(&self, enabled: bool) -> Result<(), TaoError> {
+        self.inner.get()?.set_enabled(enabled);
+        Ok(())
+    }
+}
+
+/// Attaches `menu` as the menu bar of `window` (Windows, Linux) or of the app (macOS).
+pub(crate) fn attach(menu: &muda::Menu, window: &tao::window::Window) -> Result<(), TaoError> {
+    #[cfg(target_os = "windows")]
+    {
+        use tao::platform::windows::WindowExtWindows;
+        unsafe { menu.init_for_hwnd(window.hwnd() as isize)? };
+        win32::MENUS.with_borrow_mut(|menus| menus.push((window.hwnd() as isize, menu.clone())));
+        return Ok(());
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let _ = window;
+        menu.init_for_nsapp();
+        return Ok(());
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    {
+        use tao::platform::unix::WindowExtUnix;
+        menu.init_for_gtk_window(window.gtk_window(), window.default_vbox())?;
+        return Ok(());
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let _ = (menu, window);
+        Err(TaoError::Unsupported)
+    }
+}
+
+/// Removes a menu attached with `attach`.
+pub(crate) fn detach(menu: &muda::Menu, window: &tao::window::Window) -> Result<(), TaoError> {
+    #[cfg(target_os = "windows")]
+    {
+        use tao::platform::windows::WindowExtWindows;
+        unsafe { menu.remove_for_hwnd(window.hwnd() as isize)? };
+        win32::MENUS.with_borrow_mut(|menus| menus.retain(|(hwnd, _)| *hwnd != window.hwnd() as isize));
+        return Ok(());
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        let _ = window;
+        menu.remove_for_nsapp();
+        return Ok(());
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    {
+        use tao::platform::unix::WindowExtUnix;
+        menu.remove_for_gtk_window(window.gtk_window())?;
+        return Ok(());
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let _ = (menu, window);
+        Err(TaoError::Unsupported)
+    }
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) use win32::translate_accelerator;
+
+#[cfg(target_os = "windows")]
+mod win32 {
+    use std::cell::RefCell;
+
+    use windows_sys::Win32::{
+        Foundation::HWND,
+        UI::WindowsAndMessaging::{GetAncestor, IsWindow, TranslateAcceleratorW, GA_ROOT, MSG},
+    };
+
+    thread_local! {
+        /// Menu bars attached on the event loop thread, by window handle.
+        pub(super) static MENUS: RefCell<Vec<(isize, muda::Menu)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    /// Runs the accelerator of the menu bar attached to `msg`'s top-level window, if any.
+    /// Returns true when the message was translated and must not be dispatched. Called from
+    /// the event loop's message hook.
+    pub(crate) fn translate_accelerator(msg: &MSG) -> bool {
+        if msg.hwnd.is_null() {
+            return false;
+        }
+        MENUS.with_borrow_mut(|menus| {
+            // Windows destroyed without detaching their menu.
+            menus.retain(|(hwnd, _)| unsafe { IsWindow(*hwnd as HWND) } != 0);
+            let root = unsafe { GetAncestor(msg.hwnd, GA_ROOT) };
+            menus.iter().find(|(hwnd, _)| *hwnd as HWND == root).is_some_and(|(hwnd, menu)| unsafe {
+                TranslateAcceleratorW(*hwnd as HWND, menu.haccel() as _, msg) != 0
+            })
+        })
+    }
+}
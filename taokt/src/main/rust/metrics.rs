@@ -0,0 +1,141 @@
+use std::{collections::HashMap, time::Duration, time::Instant};
+
+use crate::{describe_other, intern_variant, TaoEvent};
+
+/// Upper bounds of the callback latency buckets; the last bucket is open-ended.
+const LATENCY_BUCKETS_MICROS: [u64; 9] = [50, 100, 250, 500, 1_000, 2_500, 5_000, 10_000, 25_000];
+
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct EventTypeMetrics {
+    /// `Type::Variant`, e.g. `TaoWindowEvent::CursorMoved`.
+    pub event_type: String,
+    pub count: u64,
+    pub total_callback_micros: u64,
+    pub max_callback_micros: u64,
+    /// Counts per bucket of `DispatchMetrics::latency_bucket_bounds_micros`, plus one overflow bucket.
+    pub latency_histogram: Vec<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, uniffi::Record)]
+pub struct DispatchMetrics {
+    pub enabled: bool,
+    /// Time covered by the numbers below, since metrics were enabled or last reset.
+    pub elapsed_seconds: f64,
+    pub events_delivered: u64,
+    pub events_per_second: f64,
+    /// Events dropped by the `EventMask`.
+    pub suppressed_events: u64,
+    /// Pointer-motion events merged into another one.
+    pub coalesced_events: u64,
+    /// User events discarded or rejected because the queue was full (counted even when disabled).
+    pub dropped_user_events: u64,
+    pub latency_bucket_bounds_micros: Vec<u64>,
+    pub event_types: Vec<EventTypeMetrics>,
+}
+
+#[derive(Default)]
+struct TypeStats {
+    count: u64,
+    total_micros: u64,
+    max_micros: u64,
+    histogram: [u64; LATENCY_BUCKETS_MICROS.len() + 1],
+}
+
+/// Counters behind `App::metrics`. Only updated while enabled.
+pub(crate) struct Metrics {
+    enabled: bool,
+    since: Instant,
+    delivered: u64,
+    suppressed: u64,
+    coalesced: u64,
+    per_type: HashMap<u32, TypeStats>,
+}
+
+impl Metrics {
+    pub(crate) fn new(enabled: bool) -> Self {
+        Self {
+            enabled,
+            since: Instant::now(),
+            delivered: 0,
+            suppressed: 0,
+            coalesced: 0,
+            per_type: HashMap::new(),
+        }
+    }
+
+    pub(crate) fn enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub(crate) fn set_enabled(&mut self, enabled: bool) {
+        if enabled && !self.enabled {
+            self.reset();
+        }
+        self.enabled = enabled;
+    }
+
+    pub(crate) fn reset(&mut self) {
+        *self = Self::new(self.enabled);
+    }
+
+    pub(crate) fn record_delivery(&mut self, key: u32, callback: Duration) {
+        let micros = callback.as_micros().min(u64::MAX as u128) as u64;
+        let bucket = LATENCY_BUCKETS_MICROS
+            .iter()
+            .position(|bound| micros <= *bound)
+            .unwrap_or(LATENCY_BUCKETS_MICROS.len());
+
+        let stats = self.per_type.entry(key).or_default();
+        stats.count += 1;
+        stats.total_micros += micros;
+        stats.max_micros = stats.max_micros.max(micros);
+        stats.histogram[bucket] += 1;
+        self.delivered += 1;
+    }
+
+    pub(crate) fn record_suppressed(&mut self) {
+        self.suppressed += 1;
+    }
+
+    pub(crate) fn record_coalesced(&mut self, count: u64) {
+        self.coalesced += count;
+    }
+
+    pub(crate) fn snapshot(&self, now: Instant, dropped_user_events: u64) -> DispatchMetrics {
+        let elapsed = now.saturating_duration_since(self.since).as_secs_f64();
+        let mut event_types: Vec<_> = self
+            .per_type
+            .iter()
+            .map(|(key, stats)| EventTypeMetrics {
+                event_type: describe_other(*key).unwrap_or_default(),
+                count: stats.count,
+                total_callback_micros: stats.total_micros,
+                max_callback_micros: stats.max_micros,
+                latency_histogram: stats.histogram.to_vec(),
+            })
+            .collect();
+        event_types.sort_by(|a, b| b.total_callback_micros.cmp(&a.total_callback_micros));
+
+        DispatchMetrics {
+            enabled: self.enabled,
+            elapsed_seconds: elapsed,
+            events_delivered: self.delivered,
+            events_per_second: if elapsed > 0.0 { self.delivered as f64 / elapsed } else { 0.0 },
+            suppressed_events: self.suppressed,
+            coalesced_events: self.coalesced,
+            dropped_user_events,
+            latency_bucket_bounds_micros: LATENCY_BUCKETS_MICROS.to_vec(),
+            event_types,
+        }
+    }
+}
+
+/// Groups events by their innermost variant, reusing the codes of `describe_other`.
+pub(crate) fn event_key(event: &TaoEvent) -> u32 {
+    match event {
+        TaoEvent::WindowEvent { event, .. } => intern_variant(event),
+        TaoEvent::DeviceEvent { event, .. } => intern_variant(event),
+        TaoEvent::UserEvent { event } => intern_variant(event),
+        event => intern_variant(event),
+    }
+}
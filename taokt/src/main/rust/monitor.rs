@@ -1,6 +1,13 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
 
-use crate::{PhysicalPositionI32, PhysicalSizeU32};
+use crate::{PhysicalPositionI32, PhysicalRect, PhysicalSizeU32};
+
+#[cfg(target_os = "macos")]
+use cocoa::{ns_rect, ns_screen, ns_screen_at};
 
 #[derive(uniffi::Object)]
 pub struct Monitor {
@@ -32,16 +39,98 @@ impl Monitor {
             .collect()
     }
 
+    /// Refresh rate of the active display mode in millihertz, e.g. 59940 for 59.94 Hz.
+    pub fn refresh_rate_millihertz(&self) -> Option<u32> {
+        refresh_rate_millihertz(&self.inner)
+    }
+
+    /// The active display mode, picked from `video_modes` by resolution and refresh rate.
+    pub fn current_video_mode(&self) -> Option<Arc<VideoMode>> {
+        let modes: Vec<tao::monitor::VideoMode> = self.inner.video_modes().collect();
+        let candidates: Vec<ModeSummary> = modes.iter().map(ModeSummary::of).collect();
+        let index = pick_current_mode(&candidates, self.inner.size().into(), self.refresh_rate_millihertz())?;
+        let mode = modes.into_iter().nth(index)?;
+        Some(Arc::new(VideoMode { inner: Mutex::new(mode) }))
+    }
+
+    /// The video mode closest to `width` x `height`, e.g. for exclusive fullscreen.
+    ///
+    /// Modes are ranked by size first (an exact match, otherwise the smallest difference),
+    /// then refresh rate (closest to `refresh_hint`, or the highest without one), then the
+    /// highest bit depth. `None` only when the monitor reports no modes.
+    pub fn best_video_mode(&self, width: u32, height: u32, refresh_hint: Option<u16>) -> Option<Arc<VideoMode>> {
+        let modes: Vec<tao::monitor::VideoMode> = self.inner.video_modes().collect();
+        let candidates: Vec<ModeSummary> = modes.iter().map(ModeSummary::of).collect();
+        let index = pick_best_mode(&candidates, PhysicalSizeU32 { width, height }, refresh_hint)?;
+        let mode = modes.into_iter().nth(index)?;
+        Some(Arc::new(VideoMode { inner: Mutex::new(mode) }))
+    }
+
+    /// The part of the monitor not covered by taskbars, docks or menu bars, in physical pixels.
+    ///
+    /// Falls back to the whole monitor where the platform does not report it.
+    pub fn work_area(&self) -> PhysicalRect {
+        work_area(&self.inner).unwrap_or_else(|| {
+            let (position, size) = (self.inner.position(), self.inner.size());
+            PhysicalRect {
+                x: position.x,
+                y: position.y,
+                width: size.width,
+                height: size.height,
+            }
+        })
+    }
+
     pub fn debug_string(&self) -> String {
         format!("{:?}", self.inner)
     }
 }
 
+/// Compared by size, then bit depth, then refresh rate.
 #[derive(uniffi::Object)]
+#[uniffi::export(Eq, Hash, Ord)]
 pub struct VideoMode {
     pub(crate) inner: Mutex<tao::monitor::VideoMode>,
 }
 
+impl VideoMode {
+    fn key(&self) -> (u32, u32, u16, u16) {
+        let inner = self.inner.lock().unwrap();
+        let size = inner.size();
+        (size.width, size.height, inner.bit_depth(), inner.refresh_rate())
+    }
+}
+
+impl PartialEq for VideoMode {
+    fn eq(&self, other: &Self) -> bool {
+        // Comparing a mode with itself must not lock its mutex twice.
+        std::ptr::eq(self, other) || self.key() == other.key()
+    }
+}
+
+impl Eq for VideoMode {}
+
+impl std::hash::Hash for VideoMode {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.key().hash(state);
+    }
+}
+
+impl PartialOrd for VideoMode {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for VideoMode {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        if std::ptr::eq(self, other) {
+            return std::cmp::Ordering::Equal;
+        }
+        self.key().cmp(&other.key())
+    }
+}
+
 #[uniffi::export]
 impl VideoMode {
     pub fn size(&self) -> PhysicalSizeU32 {
@@ -76,3 +165,462 @@ impl VideoMode {
         format!("{:?}", inner)
     }
 }
+
+/// The parts of a video mode that identify the active one.
+pub(crate) struct ModeSummary {
+    pub(crate) size: PhysicalSizeU32,
+    pub(crate) bit_depth: u16,
+    pub(crate) refresh_rate_hz: u16,
+}
+
+impl ModeSummary {
+    fn of(mode: &tao::monitor::VideoMode) -> Self {
+        Self {
+            size: mode.size().into(),
+            bit_depth: mode.bit_depth(),
+            refresh_rate_hz: mode.refresh_rate(),
+        }
+    }
+}
+
+/// Index of the best mode for `size`; see `Monitor::best_video_mode` for the ranking.
+pub(crate) fn pick_best_mode(modes: &[ModeSummary], size: PhysicalSizeU32, refresh_hint: Option<u16>) -> Option<usize> {
+    modes
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, mode)| {
+            let size_distance = mode.size.width.abs_diff(size.width) as u64 + mode.size.height.abs_diff(size.height) as u64;
+            let refresh_distance = refresh_hint.map_or(0, |hint| mode.refresh_rate_hz.abs_diff(hint));
+            (
+                size_distance,
+                refresh_distance,
+                std::cmp::Reverse(mode.refresh_rate_hz),
+                std::cmp::Reverse(mode.bit_depth),
+            )
+        })
+        .map(|(index, _)| index)
+}
+
+/// Index of the mode matching the monitor's current size and refresh rate, preferring the
+/// deepest color. Without a known refresh rate the fastest mode of that size wins.
+pub(crate) fn pick_current_mode(
+    modes: &[ModeSummary],
+    size: PhysicalSizeU32,
+    refresh_rate_millihertz: Option<u32>,
+) -> Option<usize> {
+    let hz = refresh_rate_millihertz.map(|mhz| ((mhz + 500) / 1000) as u16);
+    modes
+        .iter()
+        .enumerate()
+        .filter(|(_, mode)| mode.size == size)
+        .filter(|(_, mode)| hz.is_none_or(|hz| mode.refresh_rate_hz == hz))
+        .max_by_key(|(_, mode)| (mode.refresh_rate_hz, mode.bit_depth))
+        .map(|(index, _)| index)
+}
+
+/// Last seen scale factor of each monitor, for `TaoEvent::MonitorScaleChanged`.
+///
+/// No platform reports scale changes for monitors without windows, so the loop polls.
+#[derive(Default)]
+pub(crate) struct ScaleTracker {
+    scales: HashMap<String, f64>,
+    checked: Option<Instant>,
+}
+
+impl ScaleTracker {
+    const INTERVAL: Duration = Duration::from_secs(1);
+
+    pub(crate) fn is_due(&self, now: Instant) -> bool {
+        self.checked.is_none_or(|checked| now.duration_since(checked) >= Self::INTERVAL)
+    }
+
+    /// When the next check is due; the event loop wakes up for it.
+    pub(crate) fn next_check(&self) -> Option<Instant> {
+        self.checked.map(|checked| checked + Self::INTERVAL)
+    }
+
+    /// Records `(key, scale, payload)` for every current monitor and returns the payload and
+    /// previous scale of those whose scale changed. Monitors seen for the first time are only recorded.
+    pub(crate) fn update<T>(&mut self, now: Instant, monitors: Vec<(String, f64, T)>) -> Vec<(T, f64)> {
+        self.checked = Some(now);
+        let mut scales = HashMap::with_capacity(monitors.len());
+        let mut changed = Vec::new();
+        for (key, scale, payload) in monitors {
+            if let Some(&previous) = self.scales.get(&key) {
+                if previous != scale {
+                    changed.push((payload, previous));
+                }
+            }
+            scales.insert(key, scale);
+        }
+        self.scales = scales;
+        changed
+    }
+}
+
+/// Identifies a monitor across enumerations: its native id on Windows and macOS, elsewhere its
+/// name and position, since identical monitors share a name.
+pub(crate) fn monitor_key(monitor: &tao::monitor::MonitorHandle) -> String {
+    #[cfg(target_os = "windows")]
+    {
+        use tao::platform::windows::MonitorHandleExtWindows;
+        return monitor.native_id();
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        use tao::platform::macos::MonitorHandleExtMacOS;
+        return monitor.native_id().to_string();
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let position = monitor.position();
+        format!("{}@{},{}", monitor.name().unwrap_or_default(), position.x, position.y)
+    }
+}
+
+/// Refresh rate of the monitor's current display mode in millihertz, if the platform reports one.
+pub(crate) fn refresh_rate_millihertz(monitor: &tao::monitor::MonitorHandle) -> Option<u32> {
+    #[cfg(target_os = "windows")]
+    {
+        use tao::platform::windows::MonitorHandleExtWindows;
+        use windows_sys::Win32::Graphics::Gdi::{DEVMODEW, ENUM_CURRENT_SETTINGS, EnumDisplaySettingsW};
+
+        // The rational rate, e.g. 59.94 Hz; the display mode only has whole hertz.
+        if let Some(rate) = win32::display_config_refresh_rate(&monitor.native_id()) {
+            return Some(rate);
+        }
+        let device: Vec<u16> = monitor.native_id().encode_utf16().chain(Some(0)).collect();
+        let mut mode: DEVMODEW = unsafe { std::mem::zeroed() };
+        mode.dmSize = std::mem::size_of::<DEVMODEW>() as u16;
+        if unsafe { EnumDisplaySettingsW(device.as_ptr(), ENUM_CURRENT_SETTINGS, &mut mode) } == 0 {
+            return None;
+        }
+        // 0 and 1 mean "hardware default".
+        return (mode.dmDisplayFrequency > 1).then(|| mode.dmDisplayFrequency * 1000);
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        use std::ffi::c_void;
+        use tao::platform::macos::MonitorHandleExtMacOS;
+
+        #[link(name = "CoreGraphics", kind = "framework")]
+        unsafe extern "C" {
+            fn CGDisplayCopyDisplayMode(display: u32) -> *mut c_void;
+            fn CGDisplayModeGetRefreshRate(mode: *mut c_void) -> f64;
+            fn CGDisplayModeRelease(mode: *mut c_void);
+        }
+
+        let mode = unsafe { CGDisplayCopyDisplayMode(monitor.native_id()) };
+        if mode.is_null() {
+            return None;
+        }
+        let hz = unsafe { CGDisplayModeGetRefreshRate(mode) };
+        unsafe { CGDisplayModeRelease(mode) };
+        // Built-in panels may report 0.
+        return (hz > 0.0).then(|| (hz * 1000.0).round() as u32);
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    {
+        use gtk::gdk::prelude::MonitorExt;
+
+        return gdk_monitor(monitor)
+            .map(|m| m.refresh_rate())
+            .filter(|rate| *rate > 0)
+            .map(|rate| rate as u32);
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let _ = monitor;
+        None
+    }
+}
+
+fn work_area(monitor: &tao::monitor::MonitorHandle) -> Option<PhysicalRect> {
+    #[cfg(target_os = "windows")]
+    {
+        use tao::platform::windows::MonitorHandleExtWindows;
+        use windows_sys::Win32::Graphics::Gdi::{GetMonitorInfoW, MONITORINFO};
+
+        let mut info: MONITORINFO = unsafe { std::mem::zeroed() };
+        info.cbSize = std::mem::size_of::<MONITORINFO>() as u32;
+        if unsafe { GetMonitorInfoW(monitor.hmonitor() as _, &mut info) } == 0 {
+            return None;
+        }
+        let work = info.rcWork;
+        return Some(PhysicalRect {
+            x: work.left,
+            y: work.top,
+            width: (work.right - work.left) as u32,
+            height: (work.bottom - work.top) as u32,
+        });
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        use objc2::{class, msg_send, runtime::AnyObject};
+        use tao::platform::macos::MonitorHandleExtMacOS;
+
+        // Cocoa puts the origin at the bottom left of the primary screen.
+        let screens: *mut AnyObject = unsafe { msg_send![class!(NSScreen), screens] };
+        let primary = ns_screen_at(screens, 0)?;
+        let primary_height = unsafe { ns_rect(primary, false) }.size.height;
+        let screen = ns_screen(monitor.native_id())?;
+        let visible = unsafe { ns_rect(screen, true) };
+        let scale = monitor.scale_factor();
+        let top = primary_height - (visible.origin.y + visible.size.height);
+        return Some(PhysicalRect {
+            x: (visible.origin.x * scale).round() as i32,
+            y: (top * scale).round() as i32,
+            width: (visible.size.width * scale).round() as u32,
+            height: (visible.size.height * scale).round() as u32,
+        });
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    {
+        use gtk::gdk::prelude::MonitorExt;
+
+        let gdk_monitor = gdk_monitor(monitor)?;
+        let work = gdk_monitor.workarea();
+        let scale = gdk_monitor.scale_factor();
+        return Some(PhysicalRect {
+            x: work.x() * scale,
+            y: work.y() * scale,
+            width: (work.width() * scale) as u32,
+            height: (work.height() * scale) as u32,
+        });
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let _ = monitor;
+        None
+    }
+}
+
+/// The GDK monitor matching `monitor`, found by its position.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+fn gdk_monitor(monitor: &tao::monitor::MonitorHandle) -> Option<gtk::gdk::Monitor> {
+    use gtk::gdk::prelude::MonitorExt;
+
+    let display = gtk::gdk::Display::default()?;
+    let position = monitor.position();
+    (0..display.n_monitors())
+        .filter_map(|i| display.monitor(i))
+        .find(|m| {
+            let geometry = m.geometry();
+            let scale = m.scale_factor();
+            geometry.x() * scale == position.x && geometry.y() * scale == position.y
+        })
+}
+
+#[cfg(target_os = "windows")]
+mod win32 {
+    use windows_sys::Win32::Devices::Display::{
+        DisplayConfigGetDeviceInfo, GetDisplayConfigBufferSizes, QueryDisplayConfig,
+        DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME, DISPLAYCONFIG_MODE_INFO, DISPLAYCONFIG_PATH_INFO,
+        DISPLAYCONFIG_SOURCE_DEVICE_NAME, QDC_ONLY_ACTIVE_PATHS,
+    };
+
+    /// Refresh rate in millihertz of the active display path whose source is GDI device `device`
+    /// (e.g. `\\.\DISPLAY1`), from `QueryDisplayConfig`.
+    pub(super) fn display_config_refresh_rate(device: &str) -> Option<u32> {
+        let (mut path_count, mut mode_count) = (0, 0);
+        if unsafe { GetDisplayConfigBufferSizes(QDC_ONLY_ACTIVE_PATHS, &mut path_count, &mut mode_count) } != 0 {
+            return None;
+        }
+        let mut paths: Vec<DISPLAYCONFIG_PATH_INFO> = vec![unsafe { std::mem::zeroed() }; path_count as usize];
+        let mut modes: Vec<DISPLAYCONFIG_MODE_INFO> = vec![unsafe { std::mem::zeroed() }; mode_count as usize];
+        let status = unsafe {
+            QueryDisplayConfig(
+                QDC_ONLY_ACTIVE_PATHS,
+                &mut path_count,
+                paths.as_mut_ptr(),
+                &mut mode_count,
+                modes.as_mut_ptr(),
+                std::ptr::null_mut(),
+            )
+        };
+        if status != 0 {
+            return None;
+        }
+        paths.truncate(path_count as usize);
+        paths.iter().find_map(|path| {
+            let mut source: DISPLAYCONFIG_SOURCE_DEVICE_NAME = unsafe { std::mem::zeroed() };
+            source.header.r#type = DISPLAYCONFIG_DEVICE_INFO_GET_SOURCE_NAME;
+            source.header.size = std::mem::size_of::<DISPLAYCONFIG_SOURCE_DEVICE_NAME>() as u32;
+            source.header.adapterId = path.sourceInfo.adapterId;
+            source.header.id = path.sourceInfo.id;
+            if unsafe { DisplayConfigGetDeviceInfo(&mut source.header) } != 0 {
+                return None;
+            }
+            let name = &source.viewGdiDeviceName;
+            let name = String::from_utf16_lossy(&name[..name.iter().position(|&c| c == 0).unwrap_or(name.len())]);
+            if name != device {
+                return None;
+            }
+            let rate = path.targetInfo.refreshRate;
+            (rate.Denominator != 0 && rate.Numerator != 0)
+                .then(|| (rate.Numerator as u64 * 1000 / rate.Denominator as u64) as u32)
+        })
+    }
+}
+
+#[cfg(target_os = "macos")]
+pub(crate) mod cocoa {
+    use std::{cell::Cell, ptr, ptr::NonNull, rc::Rc};
+
+    use block2::{Block, RcBlock};
+    use objc2::{
+        encode::{Encode, Encoding},
+        msg_send,
+        runtime::AnyObject,
+    };
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub(crate) struct CGPoint {
+        pub(crate) x: f64,
+        pub(crate) y: f64,
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub(crate) struct CGSize {
+        pub(crate) width: f64,
+        pub(crate) height: f64,
+    }
+
+    #[repr(C)]
+    #[derive(Clone, Copy)]
+    pub(crate) struct CGRect {
+        pub(crate) origin: CGPoint,
+        pub(crate) size: CGSize,
+    }
+
+    unsafe impl Encode for CGPoint {
+        const ENCODING: Encoding = Encoding::Struct("CGPoint", &[f64::ENCODING, f64::ENCODING]);
+    }
+
+    unsafe impl Encode for CGSize {
+        const ENCODING: Encoding = Encoding::Struct("CGSize", &[f64::ENCODING, f64::ENCODING]);
+    }
+
+    unsafe impl Encode for CGRect {
+        const ENCODING: Encoding = Encoding::Struct("CGRect", &[CGPoint::ENCODING, CGSize::ENCODING]);
+    }
+
+    pub(super) fn ns_screen_at(screens: *mut AnyObject, index: usize) -> Option<*mut AnyObject> {
+        if screens.is_null() {
+            return None;
+        }
+        let count: usize = unsafe { msg_send![&*screens, count] };
+        if index >= count {
+            return None;
+        }
+        Some(unsafe { msg_send![&*screens, objectAtIndex: index] })
+    }
+
+    /// The `NSScreen` showing the display with CoreGraphics id `display`.
+    pub(super) fn ns_screen(display: u32) -> Option<*mut AnyObject> {
+        use objc2::class;
+
+        let screens: *mut AnyObject = unsafe { msg_send![class!(NSScreen), screens] };
+        let key: *mut AnyObject =
+            unsafe { msg_send![class!(NSString), stringWithUTF8String: c"NSScreenNumber".as_ptr()] };
+        (0..)
+            .map_while(|i| ns_screen_at(screens, i))
+            .find(|screen| unsafe {
+                let description: *mut AnyObject = msg_send![&**screen, deviceDescription];
+                let number: *mut AnyObject = msg_send![&*description, objectForKey: key];
+                if number.is_null() {
+                    return false;
+                }
+                let id: u32 = msg_send![&*number, unsignedIntValue];
+                id == display
+            })
+    }
+
+    /// `visibleFrame` (without menu bar and dock) or `frame` of `screen`, in points.
+    ///
+    /// # Safety
+    /// `screen` must be a live `NSScreen`.
+    pub(super) unsafe fn ns_rect(screen: *mut AnyObject, visible: bool) -> CGRect {
+        unsafe {
+            if visible {
+                msg_send![&*screen, visibleFrame]
+            } else {
+                msg_send![&*screen, frame]
+            }
+        }
+    }
+
+    /// Adds a local `NSEvent` monitor for the event types in `mask` and removes it when
+    /// `ns_window` closes. Call on the main thread.
+    pub(crate) fn add_window_event_monitor(
+        ns_window: *mut AnyObject,
+        mask: u64,
+        handler: &Block<dyn Fn(NonNull<AnyObject>) -> *mut AnyObject>,
+    ) {
+        use objc2::class;
+
+        unsafe {
+            let monitor: *mut AnyObject =
+                msg_send![class!(NSEvent), addLocalMonitorForEventsMatchingMask: mask, handler: handler];
+            if monitor.is_null() {
+                return;
+            }
+            let monitor: *mut AnyObject = msg_send![&*monitor, retain];
+            // The observer token, retained until the window closes.
+            let observer = Rc::new(Cell::new(ptr::null_mut::<AnyObject>()));
+            let on_close = RcBlock::new({
+                let observer = observer.clone();
+                move |_: NonNull<AnyObject>| {
+                    let token = observer.replace(ptr::null_mut());
+                    if token.is_null() {
+                        return;
+                    }
+                    let center: *mut AnyObject = msg_send![class!(NSNotificationCenter), defaultCenter];
+                    let _: () = msg_send![class!(NSEvent), removeMonitor: monitor];
+                    let _: () = msg_send![&*monitor, release];
+                    let _: () = msg_send![&*center, removeObserver: token];
+                    let _: () = msg_send![&*token, release];
+                }
+            });
+            let center: *mut AnyObject = msg_send![class!(NSNotificationCenter), defaultCenter];
+            let name: *mut AnyObject =
+                msg_send![class!(NSString), stringWithUTF8String: c"NSWindowWillCloseNotification".as_ptr()];
+            let token: *mut AnyObject = msg_send![
+                &*center,
+                addObserverForName: name,
+                object: ns_window,
+                queue: ptr::null_mut::<AnyObject>(),
+                usingBlock: &*on_close
+            ];
+            observer.set(msg_send![&*token, retain]);
+        }
+    }
+}
@@ -0,0 +1,98 @@
+//! `RunConfig::windows_message_hook`.
+//!
+//! The event loop has a single message hook, which also translates menu bar accelerators
+//! before the application's hook sees the message.
+//!
+//! tao's message hook only sees messages that pass through the thread's message queue. Messages
+//! sent straight to a window procedure, such as `WM_POWERBROADCAST`, `WM_DEVICECHANGE` or
+//! `WM_SETTINGCHANGE`, never do, so each window is also subclassed to forward those.
+
+/// Forwards the messages sent to `window` to the hook, if one is installed (Windows only).
+pub(crate) fn attach(window: &tao::window::Window) {
+    #[cfg(target_os = "windows")]
+    win32::attach(window);
+
+    #[cfg(not(target_os = "windows"))]
+    let _ = window;
+}
+
+#[cfg(target_os = "windows")]
+pub(crate) use win32::install;
+
+#[cfg(target_os = "windows")]
+mod win32 {
+    use std::{
+        cell::Cell,
+        sync::{Arc, Mutex},
+    };
+
+    use tao::platform::windows::{EventLoopBuilderExtWindows, WindowExtWindows};
+    use windows_sys::Win32::{
+        Foundation::{HWND, LPARAM, LRESULT, WPARAM},
+        UI::{
+            Shell::{DefSubclassProc, RemoveWindowSubclass, SetWindowSubclass},
+            WindowsAndMessaging::{MSG, WM_NCDESTROY},
+        },
+    };
+
+    use crate::{LoopMessage, WindowsMessageHook};
+
+    const SUBCLASS_ID: usize = 0x686f_6f6b;
+
+    static HOOK: Mutex<Option<Arc<dyn WindowsMessageHook>>> = Mutex::new(None);
+
+    thread_local! {
+        /// Queued message the hook declined; tao dispatches it to the window procedure next,
+        /// where it must not be reported again.
+        static DECLINED: Cell<Option<(usize, u32, WPARAM, LPARAM)>> = const { Cell::new(None) };
+    }
+
+    /// Installs the event loop's message hook: menu bar accelerators first, then `hook`.
+    pub(crate) fn install(
+        builder: &mut tao::event_loop::EventLoopBuilder<LoopMessage>,
+        hook: Option<Arc<dyn WindowsMessageHook>>,
+    ) {
+        *HOOK.lock().unwrap() = hook.clone();
+        builder.with_msg_hook(move |msg| {
+            // Safety: tao passes a pointer to the MSG being dispatched.
+            let msg = unsafe { &*(msg as *const MSG) };
+            if crate::menu::translate_accelerator(msg) {
+                return true;
+            }
+            let Some(hook) = &hook else {
+                return false;
+            };
+            let handled = hook.on_message(msg.hwnd as u64, msg.message, msg.wParam as u64, msg.lParam as i64);
+            DECLINED.set((!handled).then_some((msg.hwnd as usize, msg.message, msg.wParam, msg.lParam)));
+            handled
+        });
+    }
+
+    pub(super) fn attach(window: &tao::window::Window) {
+        if HOOK.lock().unwrap().is_some() {
+            unsafe { SetWindowSubclass(window.hwnd() as HWND, Some(hook_proc), SUBCLASS_ID, 0) };
+        }
+    }
+
+    unsafe extern "system" fn hook_proc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+        _id: usize,
+        _data: usize,
+    ) -> LRESULT {
+        if msg == WM_NCDESTROY {
+            unsafe { RemoveWindowSubclass(hwnd, Some(hook_proc), SUBCLASS_ID) };
+        } else if DECLINED.get() == Some((hwnd as usize, msg, wparam, lparam)) {
+            DECLINED.set(None);
+        } else {
+            // Not held during the call: the hook may create windows or send messages itself.
+            let hook = HOOK.lock().unwrap().clone();
+            if hook.is_some_and(|hook| hook.on_message(hwnd as u64, msg, wparam as u64, lparam as i64)) {
+                return 0;
+            }
+        }
+        unsafe { DefSubclassProc(hwnd, msg, wparam, lparam) }
+    }
+}
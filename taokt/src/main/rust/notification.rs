@@ -0,0 +1,299 @@
+//! Desktop notifications: toasts on Windows, `NSUserNotification` on macOS and the freedesktop
+//! notification service on Linux, reached over the shared session bus of `crate::dbus`.
+//!
+//! Clicks and action buttons come back through the event loop as
+//! `TaoEvent::NotificationActivated`, carrying the `window_id` the notification was shown for.
+
+use crate::TaoError;
+
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct NotificationAction {
+    /// Reported in `TaoEvent::NotificationActivated` when the button is clicked.
+    pub id: String,
+    pub label: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct Notification {
+    /// Echoed back in `TaoEvent::NotificationActivated`.
+    pub id: String,
+    pub title: String,
+    pub body: String,
+    /// Image file shown next to the text.
+    #[uniffi(default = None)]
+    pub icon_path: Option<String>,
+    /// Buttons; macOS shows the first one, or a drop-down of all of them.
+    #[uniffi(default = [])]
+    pub actions: Vec<NotificationAction>,
+    /// Window to focus or otherwise act on when the notification is clicked.
+    #[uniffi(default = None)]
+    pub window_id: Option<u64>,
+    /// AppUserModelID on Windows, bundle identifier on macOS. Unpackaged Windows apps
+    /// default to PowerShell's id, which lets them show toasts without registration.
+    #[uniffi(default = None)]
+    pub app_id: Option<String>,
+}
+
+/// Maps the button a platform reported, by id or label, to its action id.
+///
+/// `None` means the body of the notification was clicked.
+pub(crate) fn action_id(actions: &[NotificationAction], key: &str) -> Option<String> {
+    actions
+        .iter()
+        .find(|action| action.id == key)
+        .or_else(|| actions.iter().find(|action| action.label == key))
+        .map(|action| action.id.clone())
+}
+
+/// Shows `notification`; `on_activate` runs on any thread with the action id, or `None` for a
+/// click on the notification itself.
+pub(crate) fn show(
+    notification: Notification,
+    on_activate: impl Fn(Option<String>) + Send + 'static,
+) -> Result<(), TaoError> {
+    #[cfg(target_os = "windows")]
+    {
+        return toast::show(notification, on_activate);
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        return appkit::show(notification, on_activate);
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    {
+        return freedesktop::show(notification, on_activate);
+    }
+
+    #[allow(unreachable_code)]
+    {
+        let _ = (notification, on_activate);
+        Err(TaoError::Unsupported)
+    }
+}
+
+#[cfg(target_os = "windows")]
+mod toast {
+    use std::path::Path;
+
+    use tauri_winrt_notification::{IconCrop, Toast};
+
+    use super::{action_id, Notification};
+    use crate::TaoError;
+
+    pub(super) fn show(
+        notification: Notification,
+        on_activate: impl Fn(Option<String>) + Send + 'static,
+    ) -> Result<(), TaoError> {
+        let app_id = notification.app_id.as_deref().unwrap_or(Toast::POWERSHELL_APP_ID);
+        let mut toast = Toast::new(app_id).title(&notification.title).text1(&notification.body);
+        if let Some(icon_path) = &notification.icon_path {
+            toast = toast.icon(Path::new(icon_path), IconCrop::Square, "");
+        }
+        for action in &notification.actions {
+            toast = toast.add_button(&action.label, &action.id);
+        }
+        let actions = notification.actions.clone();
+        toast
+            .on_activated(move |key| {
+                on_activate(key.and_then(|key| action_id(&actions, &key)));
+                Ok(())
+            })
+            .show()
+            .map_err(|e| TaoError::message(e.to_string()))
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod appkit {
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Once,
+    };
+
+    use mac_notification_sys::{MainButton, NotificationResponse};
+
+    use super::{action_id, Notification};
+    use crate::TaoError;
+
+    /// Notifications whose click is awaited at a time, each on a thread of its own.
+    const MAX_WAITING: usize = 8;
+
+    static WAITING: AtomicUsize = AtomicUsize::new(0);
+
+    /// Shows `notification`; with `wait`, blocks until it is dismissed or clicked.
+    fn send(notification: &Notification, wait: bool) -> Result<NotificationResponse, TaoError> {
+        let labels: Vec<&str> = notification.actions.iter().map(|action| action.label.as_str()).collect();
+        let mut native = mac_notification_sys::Notification::new();
+        native.title(&notification.title).message(&notification.body).wait_for_click(wait);
+        if let Some(icon_path) = &notification.icon_path {
+            native.content_image(icon_path);
+        }
+        match labels.as_slice() {
+            _ if !wait => {}
+            [] => {}
+            [label] => {
+                native.main_button(MainButton::SingleAction(label));
+            }
+            _ => {
+                native.main_button(MainButton::DropdownActions("Actions", &labels));
+            }
+        }
+        native.send().map_err(|e| TaoError::message(e.to_string()))
+    }
+
+    pub(super) fn show(
+        notification: Notification,
+        on_activate: impl Fn(Option<String>) + Send + 'static,
+    ) -> Result<(), TaoError> {
+        static APPLICATION: Once = Once::new();
+        if let Some(app_id) = &notification.app_id {
+            APPLICATION.call_once(|| {
+                let _ = mac_notification_sys::set_application(app_id);
+            });
+        }
+        if WAITING.fetch_add(1, Ordering::SeqCst) >= MAX_WAITING {
+            // Past the limit the notification is shown without buttons, and clicks are not reported.
+            WAITING.fetch_sub(1, Ordering::SeqCst);
+            return send(&notification, false).map(|_| ());
+        }
+        let thread = std::thread::Builder::new().name("taokt-notification".to_string());
+        let spawned = thread.spawn(move || {
+            match send(&notification, true) {
+                Ok(NotificationResponse::Click) => on_activate(None),
+                Ok(NotificationResponse::ActionButton(label)) => on_activate(action_id(&notification.actions, &label)),
+                _ => {}
+            }
+            WAITING.fetch_sub(1, Ordering::SeqCst);
+        });
+        if let Err(e) = spawned {
+            WAITING.fetch_sub(1, Ordering::SeqCst);
+            return Err(e.into());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+mod freedesktop {
+    use std::{
+        collections::HashMap,
+        sync::{Arc, Mutex, Once},
+    };
+
+    use gtk::{
+        gio::DBusSignalFlags,
+        glib::{ToVariant, Variant},
+    };
+
+    use super::{action_id, Notification, NotificationAction};
+    use crate::TaoError;
+
+    const SERVICE: &str = "org.freedesktop.Notifications";
+    const PATH: &str = "/org/freedesktop/Notifications";
+    /// Action key the notification service reports for a click on the body.
+    const DEFAULT_ACTION: &str = "default";
+    /// Prefix of the keys of action buttons, which keeps ids such as `default` or `__closed`
+    /// apart from the keys the service reports itself.
+    const ACTION_PREFIX: &str = "taokt-action:";
+
+    type OnActivate = Arc<dyn Fn(Option<String>) + Send + Sync>;
+
+    /// Notifications still on screen, by the id the service gave them.
+    static SHOWN: Mutex<Vec<(u32, Vec<NotificationAction>, OnActivate)>> = Mutex::new(Vec::new());
+
+    /// Subscribes once to the service's signals on the shared session connection.
+    fn listen() -> Result<(), TaoError> {
+        static SUBSCRIBE: Once = Once::new();
+        let connection = crate::dbus::session()?;
+        SUBSCRIBE.call_once(|| {
+            connection.signal_subscribe(
+                Some(SERVICE),
+                Some(SERVICE),
+                None,
+                Some(PATH),
+                None,
+                DBusSignalFlags::NONE,
+                |_, _, _, _, signal, parameters| {
+                    let Some(id) = parameters.child_value(0).get::<u32>() else {
+                        return;
+                    };
+                    let mut shown = SHOWN.lock().unwrap();
+                    let Some(index) = shown.iter().position(|(shown, _, _)| *shown == id) else {
+                        return;
+                    };
+                    match signal {
+                        "ActionInvoked" => {
+                            let (_, actions, on_activate) = &shown[index];
+                            let key = parameters.child_value(1).str().map(str::to_string).unwrap_or_default();
+                            let action = if key == DEFAULT_ACTION {
+                                Some(None)
+                            } else {
+                                key.strip_prefix(ACTION_PREFIX).map(|id| action_id(actions, id))
+                            };
+                            let on_activate = on_activate.clone();
+                            drop(shown);
+                            if let Some(action) = action {
+                                on_activate(action);
+                            }
+                        }
+                        "NotificationClosed" => {
+                            shown.remove(index);
+                        }
+                        _ => {}
+                    }
+                },
+            );
+        });
+        Ok(())
+    }
+
+    pub(super) fn show(
+        notification: Notification,
+        on_activate: impl Fn(Option<String>) + Send + 'static,
+    ) -> Result<(), TaoError> {
+        listen()?;
+        let mut actions = vec![DEFAULT_ACTION.to_string(), String::new()];
+        for action in &notification.actions {
+            actions.push(format!("{ACTION_PREFIX}{}", action.id));
+            actions.push(action.label.clone());
+        }
+        let app_name = std::env::current_exe()
+            .ok()
+            .and_then(|exe| exe.file_stem().map(|name| name.to_string_lossy().into_owned()))
+            .unwrap_or_default();
+        let hints: HashMap<String, Variant> = HashMap::new();
+        let args = (
+            app_name,
+            0u32,
+            notification.icon_path.as_deref().unwrap_or(""),
+            notification.title.as_str(),
+            notification.body.as_str(),
+            actions,
+            hints,
+            -1i32,
+        );
+        let reply = crate::dbus::call(SERVICE, PATH, SERVICE, "Notify", Some(&args.to_variant()))?;
+        let id = reply
+            .child_value(0)
+            .get::<u32>()
+            .ok_or_else(|| TaoError::message("The notification service returned no id"))?;
+        let on_activate = Mutex::new(on_activate);
+        let on_activate: OnActivate = Arc::new(move |action| (on_activate.lock().unwrap())(action));
+        SHOWN.lock().unwrap().push((id, notification.actions, on_activate));
+        Ok(())
+    }
+}
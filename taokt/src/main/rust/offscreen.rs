@@ -0,0 +1,219 @@
+//! Window-less render targets for thumbnails, headless export and rendering tests.
+
+use std::sync::Mutex;
+
+use crate::{
+    wgpu_surface::{clear, wgpu_error},
+    GraphicsBackend, TaoError,
+};
+
+/// Pixel format of offscreen targets and of `OffscreenSurface::read_pixels`.
+const FORMAT: wgpu::TextureFormat = wgpu::TextureFormat::Rgba8Unorm;
+const BYTES_PER_PIXEL: u32 = 4;
+
+/// Row pitch of a texture-to-buffer copy, which wgpu requires to be 256-byte aligned.
+fn padded_bytes_per_row(width: u32) -> u32 {
+    let unpadded = width * BYTES_PER_PIXEL;
+    let align = wgpu::COPY_BYTES_PER_ROW_ALIGNMENT;
+    unpadded.div_ceil(align) * align
+}
+
+/// Drops the row padding of a copied image, leaving tightly packed rows.
+fn unpad_rows(padded: &[u8], width: u32, height: u32) -> Vec<u8> {
+    let row = (width * BYTES_PER_PIXEL) as usize;
+    let pitch = padded_bytes_per_row(width) as usize;
+    let mut pixels = Vec::with_capacity(row * height as usize);
+    for y in 0..height as usize {
+        pixels.extend_from_slice(&padded[y * pitch..y * pitch + row]);
+    }
+    pixels
+}
+
+fn backends(backend: GraphicsBackend) -> wgpu::Backends {
+    match backend {
+        GraphicsBackend::Metal => wgpu::Backends::METAL,
+        GraphicsBackend::Vulkan => wgpu::Backends::VULKAN,
+        GraphicsBackend::DirectX12 => wgpu::Backends::DX12,
+        GraphicsBackend::OpenGL => wgpu::Backends::GL,
+    }
+}
+
+/// An RGBA8 render target that does not need a window or a display.
+#[derive(uniffi::Object)]
+pub struct OffscreenSurface {
+    backend: GraphicsBackend,
+    adapter: wgpu::Adapter,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    texture: Mutex<wgpu::Texture>,
+}
+
+impl OffscreenSurface {
+    pub(crate) fn new(width: u32, height: u32, backend: GraphicsBackend) -> Result<Self, TaoError> {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
+            backends: backends(backend),
+            ..Default::default()
+        });
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default()))
+            .map_err(wgpu_error)?;
+        let (device, queue) =
+            pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default())).map_err(wgpu_error)?;
+        let texture = create_texture(&device, width, height);
+        Ok(Self {
+            backend,
+            adapter,
+            device,
+            queue,
+            texture: Mutex::new(texture),
+        })
+    }
+}
+
+fn create_texture(device: &wgpu::Device, width: u32, height: u32) -> wgpu::Texture {
+    device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("taokt offscreen"),
+        size: wgpu::Extent3d {
+            width: width.max(1),
+            height: height.max(1),
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: FORMAT,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    })
+}
+
+#[uniffi::export]
+impl OffscreenSurface {
+    pub fn width(&self) -> u32 {
+        self.texture.lock().unwrap().width()
+    }
+
+    pub fn height(&self) -> u32 {
+        self.texture.lock().unwrap().height()
+    }
+
+    pub fn backend(&self) -> GraphicsBackend {
+        self.backend
+    }
+
+    /// Name and backend of the adapter in use, e.g. for diagnostics.
+    pub fn adapter_info(&self) -> String {
+        let info = self.adapter.get_info();
+        format!("{} ({:?})", info.name, info.backend)
+    }
+
+    /// Replaces the target with one of the new size; the contents are discarded.
+    pub fn resize(&self, width: u32, height: u32) {
+        *self.texture.lock().unwrap() = create_texture(&self.device, width, height);
+    }
+
+    /// Clears the target to the given color (components in 0.0..=1.0).
+    pub fn clear(&self, red: f64, green: f64, blue: f64, alpha: f64) {
+        let texture = self.texture.lock().unwrap();
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        clear(
+            &self.device,
+            &self.queue,
+            &view,
+            wgpu::Color {
+                r: red,
+                g: green,
+                b: blue,
+                a: alpha,
+            },
+        );
+    }
+
+    /// Copies the target back to the CPU as tightly packed RGBA8 rows, top row first.
+    pub fn read_pixels(&self) -> Result<Vec<u8>, TaoError> {
+        let texture = self.texture.lock().unwrap();
+        let (width, height) = (texture.width(), texture.height());
+        let buffer = self.device.create_buffer(&wgpu::BufferDescriptor {
+            label: Some("taokt readback"),
+            size: (padded_bytes_per_row(width) * height) as u64,
+            usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+            mapped_at_creation: false,
+        });
+
+        let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+            label: Some("taokt readback"),
+        });
+        encoder.copy_texture_to_buffer(
+            texture.as_image_copy(),
+            wgpu::TexelCopyBufferInfo {
+                buffer: &buffer,
+                layout: wgpu::TexelCopyBufferLayout {
+                    offset: 0,
+                    bytes_per_row: Some(padded_bytes_per_row(width)),
+                    rows_per_image: Some(height),
+                },
+            },
+            texture.size(),
+        );
+        self.queue.submit(Some(encoder.finish()));
+
+        let slice = buffer.slice(..);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        slice.map_async(wgpu::MapMode::Read, move |result| {
+            let _ = sender.send(result);
+        });
+        self.device.poll(wgpu::PollType::Wait).map_err(wgpu_error)?;
+        receiver
+            .recv()
+            .map_err(wgpu_error)?
+            .map_err(wgpu_error)?;
+
+        let pixels = unpad_rows(&slice.get_mapped_range(), width, height);
+        buffer.unmap();
+        Ok(pixels)
+    }
+}
+
+#[uniffi::export]
+impl crate::Graphics {
+    /// Creates a window-less render target on `backend`, e.g. for thumbnails or CI tests.
+    pub fn create_offscreen(
+        &self,
+        width: u32,
+        height: u32,
+        backend: GraphicsBackend,
+    ) -> Result<std::sync::Arc<OffscreenSurface>, TaoError> {
+        OffscreenSurface::new(width, height, backend).map(std::sync::Arc::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rows_are_padded_to_copy_alignment() {
+        assert_eq!(padded_bytes_per_row(64), 256);
+        assert_eq!(padded_bytes_per_row(65), 512);
+        assert_eq!(padded_bytes_per_row(1), 256);
+    }
+
+    #[test]
+    fn test_unpad_rows() {
+        let width = 2;
+        let pitch = padded_bytes_per_row(width) as usize;
+        let mut padded = vec![0u8; pitch * 2];
+        padded[..8].copy_from_slice(&[1, 2, 3, 4, 5, 6, 7, 8]);
+        padded[pitch..pitch + 8].copy_from_slice(&[9, 10, 11, 12, 13, 14, 15, 16]);
+        assert_eq!(unpad_rows(&padded, width, 2), (1..=16).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn test_clear_and_read_back() {
+        let surface = OffscreenSurface::new(3, 2, GraphicsBackend::default_for_platform()).unwrap();
+        surface.clear(1.0, 0.0, 0.0, 1.0);
+        let pixels = surface.read_pixels().unwrap();
+        assert_eq!(pixels.len(), 3 * 2 * 4);
+        assert_eq!(&pixels[..4], &[255, 0, 0, 255]);
+    }
+}
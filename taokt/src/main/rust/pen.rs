@@ -0,0 +1,358 @@
+//! Pen and stylus input.
+//!
+//! tao turns pens into mouse or touch input and drops pressure, tilt and eraser state. TaoKt
+//! reads them from the native events (`WM_POINTER*` on Windows, tablet `NSEvent`s on macOS,
+//! GDK pen and eraser devices on Linux) into a per-window queue that the event loop drains
+//! as `TaoWindowEvent::PenInput`.
+
+use crate::{events::NativeEventQueue, PhysicalPositionF64};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum PenPhase {
+    /// The tip touched the surface.
+    Down,
+    /// The pen moved, touching the surface or hovering.
+    Move,
+    /// The tip left the surface.
+    Up,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, uniffi::Record)]
+pub struct PenInput {
+    /// Identifies the pen for the duration of a stroke.
+    pub pointer_id: u64,
+    pub phase: PenPhase,
+    /// Position in the window's client area, in physical pixels.
+    pub position: PhysicalPositionF64,
+    /// Tip pressure from 0.0 to 1.0; 0.0 while hovering.
+    pub pressure: f64,
+    /// Tilt towards the positive x axis in degrees, from -90 to 90.
+    pub tilt_x: f64,
+    /// Tilt towards the positive y axis in degrees, from -90 to 90.
+    pub tilt_y: f64,
+    /// Clockwise rotation around the pen's axis in degrees, from 0 to 359.
+    pub twist: f64,
+    /// The eraser end is in use.
+    pub eraser: bool,
+    /// The pen is held upside down (reported separately from `eraser` on Windows only).
+    pub inverted: bool,
+    /// The barrel button is pressed.
+    pub barrel_button: bool,
+}
+
+/// Maps a normalized tilt (-1.0 ..= 1.0) to degrees.
+#[cfg(any(
+    target_os = "macos",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    test
+))]
+pub(crate) fn tilt_degrees(normalized: f64) -> f64 {
+    normalized.clamp(-1.0, 1.0) * 90.0
+}
+
+/// Normalizes a rotation in degrees to 0 ..< 360.
+pub(crate) fn twist_degrees(degrees: f64) -> f64 {
+    degrees.rem_euclid(360.0)
+}
+
+/// Starts capturing pen events for `window` into `queue`. Call on the event loop thread.
+pub(crate) fn attach(window: &tao::window::Window, queue: &NativeEventQueue) {
+    #[cfg(target_os = "windows")]
+    win32::attach(window, queue);
+
+    #[cfg(target_os = "macos")]
+    appkit::attach(window, queue);
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    gdk::attach(window, queue);
+
+    #[cfg(not(any(
+        target_os = "windows",
+        target_os = "macos",
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )))]
+    let _ = (window, queue);
+}
+
+#[cfg(target_os = "windows")]
+mod win32 {
+    use std::sync::Arc;
+
+    use tao::platform::windows::WindowExtWindows;
+    use windows_sys::Win32::{
+        Foundation::{HWND, LPARAM, LRESULT, POINT, WPARAM},
+        Graphics::Gdi::ScreenToClient,
+        UI::{
+            Input::Pointer::{GetPointerPenInfo, GetPointerType, POINTER_PEN_INFO},
+            Shell::{DefSubclassProc, RemoveWindowSubclass, SetWindowSubclass},
+            WindowsAndMessaging::{
+                PEN_FLAG_BARREL, PEN_FLAG_ERASER, PEN_FLAG_INVERTED, PT_PEN, WM_NCDESTROY, WM_POINTERDOWN,
+                WM_POINTERUP, WM_POINTERUPDATE,
+            },
+        },
+    };
+
+    use super::{twist_degrees, PenInput, PenPhase};
+    use crate::{events::NativeEventQueue, PhysicalPositionF64, TaoWindowEvent};
+
+    const SUBCLASS_ID: usize = 0x7065_6e00;
+
+    pub(super) fn attach(window: &tao::window::Window, queue: &NativeEventQueue) {
+        let queue = Box::into_raw(Box::new(Arc::downgrade(queue)));
+        unsafe { SetWindowSubclass(window.hwnd() as _, Some(pen_proc), SUBCLASS_ID, queue as usize) };
+    }
+
+    unsafe extern "system" fn pen_proc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+        _id: usize,
+        queue: usize,
+    ) -> LRESULT {
+        let queue = queue as *mut std::sync::Weak<std::sync::Mutex<Vec<TaoWindowEvent>>>;
+        let phase = match msg {
+            WM_POINTERDOWN => Some(PenPhase::Down),
+            WM_POINTERUPDATE => Some(PenPhase::Move),
+            WM_POINTERUP => Some(PenPhase::Up),
+            WM_NCDESTROY => {
+                unsafe {
+                    RemoveWindowSubclass(hwnd, Some(pen_proc), SUBCLASS_ID);
+                    drop(Box::from_raw(queue));
+                }
+                None
+            }
+            _ => None,
+        };
+        if let Some(phase) = phase {
+            let pointer_id = (wparam & 0xffff) as u32;
+            if let Some(input) = unsafe { pen_input(hwnd, pointer_id, phase) } {
+                if let Some(queue) = unsafe { (*queue).upgrade() } {
+                    queue.lock().unwrap().push(TaoWindowEvent::PenInput { input });
+                }
+            }
+        }
+        unsafe { DefSubclassProc(hwnd, msg, wparam, lparam) }
+    }
+
+    unsafe fn pen_input(hwnd: HWND, pointer_id: u32, phase: PenPhase) -> Option<PenInput> {
+        let mut kind = 0;
+        if unsafe { GetPointerType(pointer_id, &mut kind) } == 0 || kind != PT_PEN {
+            return None;
+        }
+        let mut info: POINTER_PEN_INFO = unsafe { std::mem::zeroed() };
+        if unsafe { GetPointerPenInfo(pointer_id, &mut info) } == 0 {
+            return None;
+        }
+        let mut point: POINT = info.pointerInfo.ptPixelLocation;
+        unsafe { ScreenToClient(hwnd, &mut point) };
+        let pressure = if phase == PenPhase::Up { 0 } else { info.pressure };
+        Some(PenInput {
+            pointer_id: pointer_id as u64,
+            phase,
+            position: PhysicalPositionF64 {
+                x: point.x as f64,
+                y: point.y as f64,
+            },
+            pressure: pressure as f64 / 1024.0,
+            tilt_x: info.tiltX as f64,
+            tilt_y: info.tiltY as f64,
+            twist: twist_degrees(info.rotation as f64),
+            eraser: info.penFlags & PEN_FLAG_ERASER != 0,
+            inverted: info.penFlags & PEN_FLAG_INVERTED != 0,
+            barrel_button: info.penFlags & PEN_FLAG_BARREL != 0,
+        })
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod appkit {
+    use std::{
+        ptr::NonNull,
+        sync::{
+            atomic::{AtomicBool, Ordering},
+            Arc,
+        },
+    };
+
+    use block2::RcBlock;
+    use objc2::{msg_send, runtime::AnyObject};
+    use tao::platform::macos::WindowExtMacOS;
+
+    use super::{tilt_degrees, twist_degrees, PenInput, PenPhase};
+    use crate::{
+        events::NativeEventQueue,
+        monitor::cocoa::{add_window_event_monitor, CGPoint, CGRect},
+        PhysicalPositionF64, TaoWindowEvent,
+    };
+
+    const LEFT_MOUSE_DOWN: usize = 1;
+    const LEFT_MOUSE_UP: usize = 2;
+    const MOUSE_MOVED: usize = 5;
+    const LEFT_MOUSE_DRAGGED: usize = 6;
+    const TABLET_POINT: usize = 23;
+    const TABLET_PROXIMITY: usize = 24;
+    const SUBTYPE_TABLET_POINT: i16 = 1;
+    const POINTING_DEVICE_ERASER: usize = 3;
+    /// `NSEventButtonMaskPenLowerSide | NSEventButtonMaskPenUpperSide`.
+    const BARREL_BUTTONS: usize = 0b110;
+
+    pub(super) fn attach(window: &tao::window::Window, queue: &NativeEventQueue) {
+        let ns_window = window.ns_window() as usize;
+        let window_number: isize = unsafe { msg_send![&*(ns_window as *mut AnyObject), windowNumber] };
+        let queue = Arc::downgrade(queue);
+        let eraser = AtomicBool::new(false);
+        let mask = [LEFT_MOUSE_DOWN, LEFT_MOUSE_UP, MOUSE_MOVED, LEFT_MOUSE_DRAGGED, TABLET_POINT, TABLET_PROXIMITY]
+            .iter()
+            .fold(0u64, |mask, kind| mask | 1 << kind);
+
+        let handler = RcBlock::new(move |event: NonNull<AnyObject>| -> *mut AnyObject {
+            let event_ptr = event.as_ptr();
+            let event = unsafe { event.as_ref() };
+            let number: isize = unsafe { msg_send![event, windowNumber] };
+            let Some(queue) = queue.upgrade().filter(|_| number == window_number) else {
+                return event_ptr;
+            };
+            let kind: usize = unsafe { msg_send![event, r#type] };
+            if kind == TABLET_PROXIMITY {
+                let device: usize = unsafe { msg_send![event, pointingDeviceType] };
+                eraser.store(device == POINTING_DEVICE_ERASER, Ordering::Relaxed);
+                return event_ptr;
+            }
+            let phase = match kind {
+                LEFT_MOUSE_DOWN => PenPhase::Down,
+                LEFT_MOUSE_UP => PenPhase::Up,
+                _ => PenPhase::Move,
+            };
+            let subtype: i16 = unsafe { msg_send![event, subtype] };
+            if kind != TABLET_POINT && subtype != SUBTYPE_TABLET_POINT {
+                return event_ptr;
+            }
+
+            let location: CGPoint = unsafe { msg_send![event, locationInWindow] };
+            let ns_window = ns_window as *mut AnyObject;
+            let (height, scale) = unsafe {
+                let view: *mut AnyObject = msg_send![&*ns_window, contentView];
+                let frame: CGRect = msg_send![&*view, frame];
+                let scale: f64 = msg_send![&*ns_window, backingScaleFactor];
+                (frame.size.height, scale)
+            };
+            let pressure: f32 = unsafe { msg_send![event, pressure] };
+            let tilt: CGPoint = unsafe { msg_send![event, tilt] };
+            let rotation: f32 = unsafe { msg_send![event, rotation] };
+            let buttons: usize = unsafe { msg_send![event, buttonMask] };
+            let device: usize = unsafe { msg_send![event, deviceID] };
+            let eraser = eraser.load(Ordering::Relaxed);
+            let input = PenInput {
+                pointer_id: device as u64,
+                phase,
+                position: PhysicalPositionF64 {
+                    x: location.x * scale,
+                    y: (height - location.y) * scale,
+                },
+                pressure: if phase == PenPhase::Up { 0.0 } else { pressure as f64 },
+                // AppKit's y tilt points up; flip it to match window coordinates.
+                tilt_x: tilt_degrees(tilt.x),
+                tilt_y: tilt_degrees(-tilt.y),
+                twist: twist_degrees(rotation as f64),
+                eraser,
+                inverted: eraser,
+                barrel_button: buttons & BARREL_BUTTONS != 0,
+            };
+            queue.lock().unwrap().push(TaoWindowEvent::PenInput { input });
+            event_ptr
+        });
+        add_window_event_monitor(ns_window as *mut AnyObject, mask, &handler);
+    }
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+mod gdk {
+    use std::sync::Arc;
+
+    use gtk::{
+        gdk::{AxisUse, EventType, InputSource, ModifierType},
+        glib::{object::ObjectType, Propagation},
+        prelude::{DeviceExt, WidgetExt},
+    };
+    use tao::platform::unix::WindowExtUnix;
+
+    use super::{tilt_degrees, twist_degrees, PenInput, PenPhase};
+    use crate::{events::NativeEventQueue, PhysicalPositionF64, TaoWindowEvent};
+
+    pub(super) fn attach(window: &tao::window::Window, queue: &NativeEventQueue) {
+        let gtk_window = window.gtk_window();
+        let capture = {
+            let queue = Arc::downgrade(queue);
+            move |widget: &gtk::ApplicationWindow, event: &gtk::gdk::Event| {
+                let Some(queue) = queue.upgrade() else {
+                    return Propagation::Proceed;
+                };
+                if let Some(input) = pen_input(event, widget.scale_factor() as f64) {
+                    queue.lock().unwrap().push(TaoWindowEvent::PenInput { input });
+                }
+                Propagation::Proceed
+            }
+        };
+        let on_motion = capture.clone();
+        gtk_window.connect_motion_notify_event(move |widget, event| on_motion(widget, event));
+        let on_press = capture.clone();
+        gtk_window.connect_button_press_event(move |widget, event| on_press(widget, event));
+        gtk_window.connect_button_release_event(move |widget, event| capture(widget, event));
+    }
+
+    fn pen_input(event: &gtk::gdk::Event, scale: f64) -> Option<PenInput> {
+        let device = event.source_device()?;
+        let source = device.source();
+        if !matches!(source, InputSource::Pen | InputSource::Eraser) {
+            return None;
+        }
+        let phase = match event.event_type() {
+            EventType::ButtonPress => PenPhase::Down,
+            EventType::ButtonRelease => PenPhase::Up,
+            EventType::MotionNotify => PenPhase::Move,
+            _ => return None,
+        };
+        let (x, y) = event.coords()?;
+        let axis = |axis| event.axis(axis).unwrap_or(0.0);
+        let barrel = event
+            .state()
+            .is_some_and(|state| state.intersects(ModifierType::BUTTON2_MASK | ModifierType::BUTTON3_MASK));
+        Some(PenInput {
+            pointer_id: device.as_ptr() as usize as u64,
+            phase,
+            position: PhysicalPositionF64 {
+                x: x * scale,
+                y: y * scale,
+            },
+            pressure: if phase == PenPhase::Up { 0.0 } else { axis(AxisUse::Pressure).clamp(0.0, 1.0) },
+            tilt_x: tilt_degrees(axis(AxisUse::Xtilt)),
+            tilt_y: tilt_degrees(axis(AxisUse::Ytilt)),
+            // GDK reports the rotation axis as a fraction of a full turn.
+            twist: twist_degrees(axis(AxisUse::Rotation) * 360.0),
+            eraser: source == InputSource::Eraser,
+            inverted: source == InputSource::Eraser,
+            barrel_button: barrel,
+        })
+    }
+}
@@ -0,0 +1,35 @@
+//! Background polling for system state that has no change notification.
+
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+use crate::TaoError;
+
+/// Calls `read` every `interval` on a new thread named `name` and hands each successful reading
+/// to `on_reading`, until `stop` is set or `on_reading` returns false.
+pub(crate) fn poll<T>(
+    name: &str,
+    interval: Duration,
+    stop: Arc<AtomicBool>,
+    read: impl Fn() -> Result<T, TaoError> + Send + 'static,
+    mut on_reading: impl FnMut(T) -> bool + Send + 'static,
+) {
+    let thread = std::thread::Builder::new().name(name.to_string());
+    thread
+        .spawn(move || {
+            while !stop.load(Ordering::Relaxed) {
+                if let Ok(reading) = read() {
+                    if !on_reading(reading) {
+                        break;
+                    }
+                }
+                std::thread::sleep(interval);
+            }
+        })
+        .expect("failed to spawn polling thread");
+}
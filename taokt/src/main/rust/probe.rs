@@ -0,0 +1,272 @@
+//! Runtime detection of the graphics backends usable on this machine.
+
+use std::sync::OnceLock;
+
+use crate::GraphicsBackend;
+
+/// Result of probing one backend at runtime.
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct BackendCapability {
+    pub backend: GraphicsBackend,
+    pub available: bool,
+    /// What was found (library, feature level, GPU family) or why the backend is unavailable.
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct BackendSelection {
+    /// `None` if none of the candidates is available.
+    pub backend: Option<GraphicsBackend>,
+    /// Human-readable explanation of the choice, for logs and diagnostics.
+    pub reason: String,
+}
+
+const ALL_BACKENDS: [GraphicsBackend; 4] = [
+    GraphicsBackend::Metal,
+    GraphicsBackend::Vulkan,
+    GraphicsBackend::DirectX12,
+    GraphicsBackend::OpenGL,
+];
+
+/// Entry point for runtime graphics capability checks.
+///
+/// Probing loads system libraries, so the results are computed once per process.
+#[derive(uniffi::Object)]
+pub struct Graphics {
+    capabilities: OnceLock<Vec<BackendCapability>>,
+}
+
+#[uniffi::export]
+impl Graphics {
+    #[uniffi::constructor]
+    pub fn new() -> Self {
+        Self {
+            capabilities: OnceLock::new(),
+        }
+    }
+
+    /// Checks every backend, unlike the compile-time `GraphicsBackend::is_supported`.
+    ///
+    /// The OpenGL version needs a current context, so it is reported once the process created a
+    /// `GlContext`.
+    pub fn probe(&self) -> Vec<BackendCapability> {
+        let mut capabilities = self
+            .capabilities
+            .get_or_init(|| ALL_BACKENDS.iter().map(|b| probe_backend(*b)).collect())
+            .clone();
+        for capability in &mut capabilities {
+            if capability.backend == GraphicsBackend::OpenGL && capability.available {
+                capability.detail = match gl_version() {
+                    Some(version) => format!("{}, GL_VERSION {version}", capability.detail),
+                    None => format!("{}, version not known before a GlContext is created", capability.detail),
+                };
+            }
+        }
+        capabilities
+    }
+
+    /// Picks the first available backend from `preferences`.
+    ///
+    /// An empty list means the platform default first, then the remaining backends.
+    pub fn select_backend(&self, preferences: Vec<GraphicsBackend>) -> BackendSelection {
+        select(&self.probe(), &preferences)
+    }
+}
+
+impl Default for Graphics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub(crate) fn select(capabilities: &[BackendCapability], preferences: &[GraphicsBackend]) -> BackendSelection {
+    let order: Vec<GraphicsBackend> = if preferences.is_empty() {
+        let default = GraphicsBackend::default_for_platform();
+        std::iter::once(default)
+            .chain(ALL_BACKENDS.iter().copied().filter(|b| *b != default))
+            .collect()
+    } else {
+        preferences.to_vec()
+    };
+
+    let mut skipped = Vec::new();
+    for backend in order {
+        match capabilities.iter().find(|c| c.backend == backend) {
+            Some(capability) if capability.available => {
+                let mut reason = format!("{backend:?}: {}", capability.detail);
+                if !skipped.is_empty() {
+                    reason.push_str(&format!(" (skipped {})", skipped.join("; ")));
+                }
+                return BackendSelection {
+                    backend: Some(backend),
+                    reason,
+                };
+            }
+            Some(capability) => skipped.push(format!("{backend:?}: {}", capability.detail)),
+            None => skipped.push(format!("{backend:?}: not probed")),
+        }
+    }
+
+    BackendSelection {
+        backend: None,
+        reason: format!("No available backend ({})", skipped.join("; ")),
+    }
+}
+
+fn capability(backend: GraphicsBackend, result: Result<String, String>) -> BackendCapability {
+    let (available, detail) = match result {
+        Ok(detail) => (true, detail),
+        Err(detail) => (false, detail),
+    };
+    BackendCapability {
+        backend,
+        available,
+        detail,
+    }
+}
+
+fn probe_backend(backend: GraphicsBackend) -> BackendCapability {
+    if !backend.is_supported() {
+        return capability(backend, Err("not supported on this platform".to_string()));
+    }
+    let result = match backend {
+        GraphicsBackend::Vulkan => probe_vulkan(),
+        GraphicsBackend::DirectX12 => probe_dx12(),
+        GraphicsBackend::Metal => probe_metal(),
+        GraphicsBackend::OpenGL => probe_opengl(),
+    };
+    capability(backend, result)
+}
+
+/// Returns the name of the first library in `names` that can be loaded.
+fn find_library(names: &[&str]) -> Option<String> {
+    names.iter().find_map(|name| {
+        // Safety: only system graphics libraries are loaded, and unloaded right away.
+        unsafe { libloading::Library::new(name) }.ok().map(|_| name.to_string())
+    })
+}
+
+fn probe_vulkan() -> Result<String, String> {
+    #[cfg(target_os = "windows")]
+    let names = ["vulkan-1.dll"];
+    #[cfg(target_os = "macos")]
+    let names = ["libvulkan.1.dylib", "libMoltenVK.dylib"];
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    let names = ["libvulkan.so.1", "libvulkan.so"];
+
+    find_library(&names)
+        .map(|name| format!("loader {name} found"))
+        .ok_or_else(|| "Vulkan loader not found".to_string())
+}
+
+#[cfg(target_os = "windows")]
+fn probe_dx12() -> Result<String, String> {
+    #[repr(C)]
+    struct Guid(u32, u16, u16, [u8; 8]);
+    type CreateDevice =
+        unsafe extern "system" fn(*mut std::ffi::c_void, i32, *const Guid, *mut *mut std::ffi::c_void) -> i32;
+
+    const IID_ID3D12_DEVICE: Guid = Guid(
+        0x189819f1,
+        0x1db6,
+        0x4b57,
+        [0xbe, 0x54, 0x18, 0x21, 0x33, 0x9b, 0x85, 0xf7],
+    );
+    const FEATURE_LEVELS: [(i32, &str); 4] = [(0xc100, "12_1"), (0xc000, "12_0"), (0xb100, "11_1"), (0xb000, "11_0")];
+
+    // Safety: d3d12.dll exports D3D12CreateDevice with this signature; passing a null
+    // output pointer only checks support without creating a device.
+    unsafe {
+        let library = libloading::Library::new("d3d12.dll").map_err(|_| "d3d12.dll not found".to_string())?;
+        let create: libloading::Symbol<CreateDevice> = library
+            .get(b"D3D12CreateDevice\0")
+            .map_err(|_| "D3D12CreateDevice missing".to_string())?;
+        for (level, name) in FEATURE_LEVELS {
+            if create(std::ptr::null_mut(), level, &IID_ID3D12_DEVICE, std::ptr::null_mut()) >= 0 {
+                return Ok(format!("feature level {name}"));
+            }
+        }
+    }
+    Err("no adapter supports feature level 11_0".to_string())
+}
+
+#[cfg(not(target_os = "windows"))]
+fn probe_dx12() -> Result<String, String> {
+    Err("not supported on this platform".to_string())
+}
+
+#[cfg(target_os = "macos")]
+fn probe_metal() -> Result<String, String> {
+    use objc2::{msg_send, runtime::AnyObject};
+
+    #[link(name = "Metal", kind = "framework")]
+    unsafe extern "C" {
+        fn MTLCreateSystemDefaultDevice() -> *mut AnyObject;
+    }
+
+    // MTLGPUFamily values, newest first.
+    const FAMILIES: [(isize, &str); 6] = [
+        (1009, "Apple9"),
+        (1008, "Apple8"),
+        (1007, "Apple7"),
+        (2002, "Mac2"),
+        (3003, "Metal3"),
+        (3001, "Common1"),
+    ];
+
+    // Safety: the device returned by the create function is owned here and released below.
+    unsafe {
+        let device = MTLCreateSystemDefaultDevice();
+        if device.is_null() {
+            return Err("no Metal device".to_string());
+        }
+        let family = FAMILIES
+            .iter()
+            .find(|(family, _)| {
+                let supported: bool = msg_send![&*device, supportsFamily: *family];
+                supported
+            })
+            .map(|(_, name)| *name);
+        let _: () = msg_send![&*device, release];
+        Ok(match family {
+            Some(name) => format!("GPU family {name}"),
+            None => "Metal device present".to_string(),
+        })
+    }
+}
+
+#[cfg(target_os = "ios")]
+fn probe_metal() -> Result<String, String> {
+    Ok("Metal is always present on iOS".to_string())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "ios")))]
+fn probe_metal() -> Result<String, String> {
+    Err("not supported on this platform".to_string())
+}
+
+#[cfg(feature = "opengl")]
+fn gl_version() -> Option<&'static str> {
+    crate::gl::probed_version()
+}
+
+#[cfg(not(feature = "opengl"))]
+fn gl_version() -> Option<&'static str> {
+    None
+}
+
+/// Checks for the GL library; `Graphics::probe` adds the version once a context reported it.
+fn probe_opengl() -> Result<String, String> {
+    #[cfg(target_os = "windows")]
+    let names = ["opengl32.dll"];
+    #[cfg(target_os = "macos")]
+    let names = ["/System/Library/Frameworks/OpenGL.framework/OpenGL"];
+    #[cfg(target_os = "android")]
+    let names = ["libGLESv2.so", "libEGL.so"];
+    #[cfg(not(any(target_os = "windows", target_os = "macos", target_os = "android")))]
+    let names = ["libGL.so.1", "libEGL.so.1", "libGLESv2.so.2"];
+
+    find_library(&names)
+        .map(|name| format!("{name} found"))
+        .ok_or_else(|| "no OpenGL library found".to_string())
+}
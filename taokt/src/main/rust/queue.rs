@@ -0,0 +1,124 @@
+use std::{
+    collections::VecDeque,
+    sync::{Condvar, Mutex},
+    thread::ThreadId,
+};
+
+use crate::{TaoError, TaoUserEvent};
+
+/// What `EventLoopProxy::send_event` does when the user-event queue is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum UserEventOverflow {
+    /// Block the sending thread until the event loop drains the queue.
+    Block,
+    /// Discard the oldest undelivered event to make room for the new one.
+    DropOldest,
+    /// Reject the new event with an error.
+    Error,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Record)]
+pub struct UserEventQueueConfig {
+    /// Maximum number of undelivered user events. Zero is treated as one.
+    pub capacity: u32,
+    pub overflow: UserEventOverflow,
+}
+
+struct QueueState {
+    events: VecDeque<TaoUserEvent>,
+    dropped: u64,
+    closed: bool,
+}
+
+/// User events waiting to be delivered by the event loop.
+///
+/// The tao channel only carries a wake-up message; the events themselves live
+/// here so their number can be bounded.
+pub(crate) struct UserEventQueue {
+    limit: Option<UserEventQueueConfig>,
+    loop_thread: ThreadId,
+    state: Mutex<QueueState>,
+    drained: Condvar,
+}
+
+impl UserEventQueue {
+    /// Creates a queue owned by the calling (event loop) thread. `None` means unbounded.
+    pub(crate) fn new(limit: Option<UserEventQueueConfig>) -> Self {
+        Self {
+            limit,
+            loop_thread: std::thread::current().id(),
+            state: Mutex::new(QueueState {
+                events: VecDeque::new(),
+                dropped: 0,
+                closed: false,
+            }),
+            drained: Condvar::new(),
+        }
+    }
+
+    /// Queues `event`, applying the overflow policy.
+    ///
+    /// Returns `true` when the queue was empty, i.e. the event loop has to be woken up.
+    pub(crate) fn push(&self, event: TaoUserEvent) -> Result<bool, TaoError> {
+        let mut state = self.state.lock().unwrap();
+        if state.closed {
+            return Err(TaoError::message("Event loop closed"));
+        }
+
+        if let Some(limit) = self.limit {
+            let capacity = limit.capacity.max(1) as usize;
+            match limit.overflow {
+                UserEventOverflow::Block => {
+                    if state.events.len() >= capacity && std::thread::current().id() == self.loop_thread {
+                        return Err(TaoError::message(
+                            "User event queue is full (blocking on the event loop thread would deadlock)",
+                        ));
+                    }
+                    while state.events.len() >= capacity && !state.closed {
+                        state = self.drained.wait(state).unwrap();
+                    }
+                    if state.closed {
+                        return Err(TaoError::message("Event loop closed"));
+                    }
+                }
+                UserEventOverflow::DropOldest => {
+                    while state.events.len() >= capacity {
+                        state.events.pop_front();
+                        state.dropped += 1;
+                    }
+                }
+                UserEventOverflow::Error => {
+                    if state.events.len() >= capacity {
+                        state.dropped += 1;
+                        return Err(TaoError::message("User event queue is full"));
+                    }
+                }
+            }
+        }
+
+        let was_empty = state.events.is_empty();
+        state.events.push_back(event);
+        Ok(was_empty)
+    }
+
+    /// Takes every queued event and releases blocked senders.
+    pub(crate) fn drain(&self) -> Vec<TaoUserEvent> {
+        let mut state = self.state.lock().unwrap();
+        let events = state.events.drain(..).collect();
+        self.drained.notify_all();
+        events
+    }
+
+    /// Number of events discarded or rejected because the queue was full.
+    pub(crate) fn dropped(&self) -> u64 {
+        self.state.lock().unwrap().dropped
+    }
+
+    /// Rejects further events and wakes up any blocked sender.
+    pub(crate) fn close(&self) {
+        let mut state = self.state.lock().unwrap();
+        state.closed = true;
+        state.events.clear();
+        self.drained.notify_all();
+    }
+}
@@ -0,0 +1,82 @@
+//! Cancellable Cmd+Q on macOS.
+//!
+//! AppKit asks the application delegate (`applicationShouldTerminate:`) before quitting from
+//! the menu, the Dock or a logout. While interception is on, TaoKt answers "cancel" and sends
+//! `TaoEvent::QuitRequested`; the application calls `App::request_exit` to quit after all.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::{LoopProxy, TaoError};
+
+static INTERCEPT: AtomicBool = AtomicBool::new(false);
+
+/// Hooks the application delegate. Call once the event loop exists.
+pub(crate) fn install(proxy: &LoopProxy) {
+    #[cfg(target_os = "macos")]
+    appkit::install(proxy.clone());
+
+    #[cfg(not(target_os = "macos"))]
+    let _ = proxy;
+}
+
+pub(crate) fn set_intercept(intercept: bool) -> Result<(), TaoError> {
+    if !cfg!(target_os = "macos") {
+        return Err(TaoError::Unsupported);
+    }
+    INTERCEPT.store(intercept, Ordering::SeqCst);
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+mod appkit {
+    use std::sync::{atomic::Ordering, Mutex, OnceLock};
+
+    use objc2::{
+        class, ffi, msg_send,
+        runtime::{AnyClass, AnyObject, Imp, Sel},
+        sel,
+    };
+
+    use super::INTERCEPT;
+    use crate::{LoopMessage, LoopProxy};
+
+    const TERMINATE_CANCEL: usize = 0;
+    const TERMINATE_NOW: usize = 1;
+
+    type ShouldTerminate = unsafe extern "C-unwind" fn(*mut AnyObject, Sel, *mut AnyObject) -> usize;
+
+    static PROXY: Mutex<Option<LoopProxy>> = Mutex::new(None);
+    /// The delegate's own implementation, if it had one.
+    static PREVIOUS: OnceLock<Option<Imp>> = OnceLock::new();
+
+    unsafe extern "C-unwind" fn should_terminate(this: *mut AnyObject, cmd: Sel, sender: *mut AnyObject) -> usize {
+        if INTERCEPT.load(Ordering::SeqCst) {
+            let proxy = PROXY.lock().unwrap();
+            if proxy.as_ref().is_some_and(|proxy| proxy.send_event(LoopMessage::QuitRequested).is_ok()) {
+                return TERMINATE_CANCEL;
+            }
+        }
+        match PREVIOUS.get().copied().flatten() {
+            Some(previous) => unsafe {
+                let previous: ShouldTerminate = std::mem::transmute(previous);
+                previous(this, cmd, sender)
+            },
+            None => TERMINATE_NOW,
+        }
+    }
+
+    pub(super) fn install(proxy: LoopProxy) {
+        *PROXY.lock().unwrap() = Some(proxy);
+        PREVIOUS.get_or_init(|| unsafe {
+            let app: *mut AnyObject = msg_send![class!(NSApplication), sharedApplication];
+            let delegate: *mut AnyObject = msg_send![&*app, delegate];
+            if delegate.is_null() {
+                return None;
+            }
+            let class: *const AnyClass = msg_send![&*delegate, class];
+            let imp: Imp = std::mem::transmute(should_terminate as ShouldTerminate);
+            // NSApplicationTerminateReply applicationShouldTerminate:(NSApplication *)sender
+            ffi::class_replaceMethod(class as *mut AnyClass, sel!(applicationShouldTerminate:), imp, c"Q@:@".as_ptr())
+        });
+    }
+}
@@ -0,0 +1,324 @@
+//! Logout and shutdown interception.
+//!
+//! Hold a `SessionEndBlock` while there is unsaved work: Windows then lists the application
+//! with the block reason when the user signs out or shuts down, and Linux desktops ask before
+//! logging out. `TaoEvent::SessionEnding` tells the application to save its work.
+
+use std::sync::Mutex;
+
+use crate::{LoopProxy, TaoError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum SessionEndReason {
+    Logout,
+    /// Shutdown or restart.
+    Shutdown,
+    /// The platform did not say (Linux).
+    Unknown,
+}
+
+/// Reasons of the live blocks, by id; the newest one is shown to the user.
+static BLOCKS: Mutex<Vec<(u64, String)>> = Mutex::new(Vec::new());
+
+fn current_reason() -> Option<String> {
+    BLOCKS.lock().unwrap().last().map(|(_, reason)| reason.clone())
+}
+
+/// Asks the OS to hold off ending the session until released or dropped.
+#[derive(uniffi::Object)]
+pub struct SessionEndBlock {
+    id: u64,
+    released: Mutex<bool>,
+    /// Carries block changes to the event loop, which owns the windows holding the reason.
+    #[cfg(target_os = "windows")]
+    proxy: LoopProxy,
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    inhibit: crate::InhibitHandle,
+}
+
+impl SessionEndBlock {
+    /// Call on the event loop thread.
+    pub(crate) fn new(reason: String, proxy: LoopProxy) -> Result<Self, TaoError> {
+        use std::sync::atomic::{AtomicU64, Ordering};
+        static NEXT_ID: AtomicU64 = AtomicU64::new(1);
+
+        #[cfg(not(any(
+            target_os = "windows",
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        )))]
+        {
+            let _ = (reason, proxy);
+            return Err(TaoError::Unsupported);
+        }
+
+        #[allow(unreachable_code)]
+        {
+            #[cfg(not(target_os = "windows"))]
+            let _ = proxy;
+            #[cfg(any(
+                target_os = "linux",
+                target_os = "dragonfly",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd"
+            ))]
+            let inhibit = crate::InhibitHandle::logout(&reason)?;
+            let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+            BLOCKS.lock().unwrap().push((id, reason));
+            #[cfg(target_os = "windows")]
+            let _ = proxy.send_event(crate::LoopMessage::SessionBlocksChanged);
+            Ok(Self {
+                id,
+                released: Mutex::new(false),
+                #[cfg(target_os = "windows")]
+                proxy,
+                #[cfg(any(
+                    target_os = "linux",
+                    target_os = "dragonfly",
+                    target_os = "freebsd",
+                    target_os = "netbsd",
+                    target_os = "openbsd"
+                ))]
+                inhibit,
+            })
+        }
+    }
+}
+
+#[uniffi::export]
+impl SessionEndBlock {
+    /// Lets the session end again. Later calls do nothing. Callable from any thread.
+    pub fn release(&self) {
+        let mut released = self.released.lock().unwrap();
+        if std::mem::replace(&mut *released, true) {
+            return;
+        }
+        BLOCKS.lock().unwrap().retain(|(id, _)| *id != self.id);
+        // Nothing holds the reason any more once the loop is gone.
+        #[cfg(target_os = "windows")]
+        let _ = self.proxy.send_event(crate::LoopMessage::SessionBlocksChanged);
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        self.inhibit.release();
+    }
+}
+
+impl Drop for SessionEndBlock {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+/// Gives the windows the reason of the newest live block. Called by the event loop.
+pub(crate) fn apply_blocks() {
+    #[cfg(target_os = "windows")]
+    win32::apply(current_reason().as_deref());
+}
+
+/// Reports session ends for `window` to `proxy` (Windows only; one notification per session end).
+pub(crate) fn attach(window: &tao::window::Window, proxy: &LoopProxy) {
+    #[cfg(target_os = "windows")]
+    win32::attach(window, proxy);
+
+    #[cfg(not(target_os = "windows"))]
+    let _ = (window, proxy);
+}
+
+/// Starts watching for session ends that are not tied to a window (Linux only).
+pub(crate) fn watch(proxy: &LoopProxy) {
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    portal::watch(proxy.clone());
+
+    #[cfg(not(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    )))]
+    let _ = proxy;
+}
+
+#[cfg(target_os = "windows")]
+mod win32 {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    };
+
+    use tao::platform::windows::WindowExtWindows;
+    use windows_sys::Win32::{
+        Foundation::{HWND, LPARAM, LRESULT, WPARAM},
+        System::Shutdown::{ShutdownBlockReasonCreate, ShutdownBlockReasonDestroy},
+        UI::{
+            Shell::{DefSubclassProc, RemoveWindowSubclass, SetWindowSubclass},
+            WindowsAndMessaging::{ENDSESSION_LOGOFF, WM_ENDSESSION, WM_NCDESTROY, WM_QUERYENDSESSION},
+        },
+    };
+
+    use super::{current_reason, SessionEndReason};
+    use crate::{LoopMessage, LoopProxy};
+
+    const SUBCLASS_ID: usize = 0x7365_7300;
+
+    /// Top-level windows that carry the block reason.
+    static WINDOWS: Mutex<Vec<usize>> = Mutex::new(Vec::new());
+    /// Every window gets `WM_QUERYENDSESSION`; only the first one is reported.
+    static QUERY_PENDING: AtomicBool = AtomicBool::new(false);
+
+    fn set_reason(hwnd: HWND, reason: Option<&str>) {
+        unsafe {
+            match reason {
+                Some(reason) => {
+                    let reason: Vec<u16> = reason.encode_utf16().chain(std::iter::once(0)).collect();
+                    ShutdownBlockReasonCreate(hwnd, reason.as_ptr());
+                }
+                None => {
+                    ShutdownBlockReasonDestroy(hwnd);
+                }
+            }
+        }
+    }
+
+    pub(super) fn apply(reason: Option<&str>) {
+        for &hwnd in WINDOWS.lock().unwrap().iter() {
+            set_reason(hwnd as HWND, reason);
+        }
+    }
+
+    pub(super) fn attach(window: &tao::window::Window, proxy: &LoopProxy) {
+        let hwnd = window.hwnd() as HWND;
+        WINDOWS.lock().unwrap().push(hwnd as usize);
+        if let Some(reason) = current_reason() {
+            set_reason(hwnd, Some(&reason));
+        }
+        let proxy = Box::into_raw(Box::new(proxy.clone()));
+        unsafe { SetWindowSubclass(hwnd, Some(session_proc), SUBCLASS_ID, proxy as usize) };
+    }
+
+    unsafe extern "system" fn session_proc(
+        hwnd: HWND,
+        msg: u32,
+        wparam: WPARAM,
+        lparam: LPARAM,
+        _id: usize,
+        proxy: usize,
+    ) -> LRESULT {
+        let proxy = proxy as *mut LoopProxy;
+        match msg {
+            WM_QUERYENDSESSION => {
+                if !QUERY_PENDING.swap(true, Ordering::SeqCst) {
+                    let reason = if lparam as u32 & ENDSESSION_LOGOFF != 0 {
+                        SessionEndReason::Logout
+                    } else {
+                        SessionEndReason::Shutdown
+                    };
+                    let _ = unsafe { &*proxy }.send_event(LoopMessage::SessionEnding(reason));
+                }
+                if current_reason().is_some() {
+                    return 0;
+                }
+            }
+            WM_ENDSESSION => QUERY_PENDING.store(false, Ordering::SeqCst),
+            WM_NCDESTROY => unsafe {
+                WINDOWS.lock().unwrap().retain(|&window| window != hwnd as usize);
+                RemoveWindowSubclass(hwnd, Some(session_proc), SUBCLASS_ID);
+                drop(Box::from_raw(proxy));
+            },
+            _ => {}
+        }
+        unsafe { DefSubclassProc(hwnd, msg, wparam, lparam) }
+    }
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+mod portal {
+    use std::collections::HashMap;
+
+    use gtk::{
+        gio::{self, DBusCallFlags, DBusSignalFlags},
+        glib::{variant::ObjectPath, ToVariant, Variant},
+    };
+
+    use super::SessionEndReason;
+    use crate::{LoopMessage, LoopProxy};
+
+    const PORTAL: &str = "org.freedesktop.portal.Desktop";
+    const PORTAL_PATH: &str = "/org/freedesktop/portal/desktop";
+    const INHIBIT: &str = "org.freedesktop.portal.Inhibit";
+    /// `session-state` value while the session manager asks applications whether it may end.
+    const QUERY_END: u32 = 3;
+
+    fn call(connection: &gio::DBusConnection, method: &str, parameters: Variant) {
+        connection.call(
+            Some(PORTAL),
+            PORTAL_PATH,
+            INHIBIT,
+            method,
+            Some(&parameters),
+            None,
+            DBusCallFlags::NONE,
+            -1,
+            gio::Cancellable::NONE,
+            |_| {},
+        );
+    }
+
+    pub(super) fn watch(proxy: LoopProxy) {
+        let Ok(connection) = crate::dbus::session() else {
+            return;
+        };
+        connection.signal_subscribe(
+            Some(PORTAL),
+            Some(INHIBIT),
+            Some("StateChanged"),
+            None,
+            None,
+            DBusSignalFlags::NONE,
+            move |connection, _, _, _, _, parameters| {
+                let state = parameters
+                    .child_value(1)
+                    .lookup_value("session-state", None)
+                    .and_then(|state| state.get::<u32>());
+                if state != Some(QUERY_END) {
+                    return;
+                }
+                let _ = proxy.send_event(LoopMessage::SessionEnding(SessionEndReason::Unknown));
+                // Logout is held back by inhibitors, not by a late answer.
+                let session = parameters.child_value(0).str().map(str::to_string);
+                if let Some(session) = session.and_then(|session| ObjectPath::try_from(session).ok()) {
+                    call(connection, "QueryEndResponse", (session,).to_variant());
+                }
+            },
+        );
+        let options: HashMap<String, Variant> = HashMap::new();
+        call(&connection, "CreateMonitor", ("", options).to_variant());
+    }
+}
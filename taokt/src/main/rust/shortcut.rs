@@ -0,0 +1,84 @@
+//! System-wide keyboard shortcuts backed by global-hotkey.
+//!
+//! Shortcuts fire while the application is unfocused and are delivered as
+//! `TaoEvent::GlobalShortcut`. On Linux they require X11 (or XWayland for X11 clients).
+
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+use std::{collections::HashMap, sync::Mutex};
+
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+use global_hotkey::{hotkey::HotKey, GlobalHotKeyManager};
+
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+use crate::{thread::ThreadBound, Accelerator, TaoError};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum ShortcutState {
+    Pressed,
+    Released,
+}
+
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+impl From<global_hotkey::HotKeyState> for ShortcutState {
+    fn from(value: global_hotkey::HotKeyState) -> Self {
+        match value {
+            global_hotkey::HotKeyState::Pressed => ShortcutState::Pressed,
+            global_hotkey::HotKeyState::Released => ShortcutState::Released,
+        }
+    }
+}
+
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+impl From<global_hotkey::Error> for TaoError {
+    fn from(value: global_hotkey::Error) -> Self {
+        TaoError::message(value.to_string())
+    }
+}
+
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+/// Registers system-wide shortcuts. Create and use it on the event loop thread.
+///
+/// Shortcuts stay registered until unregistered or the manager is dropped.
+#[derive(uniffi::Object)]
+pub struct GlobalShortcutManager {
+    inner: ThreadBound<GlobalHotKeyManager>,
+    registered: Mutex<HashMap<u32, HotKey>>,
+}
+
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+#[uniffi::export]
+impl GlobalShortcutManager {
+    #[uniffi::constructor]
+    pub fn new() -> Result<Self, TaoError> {
+        Ok(Self {
+            inner: ThreadBound::new(GlobalHotKeyManager::new()?),
+            registered: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Registers `accelerator` in the `Accelerator` syntax, e.g. `"CmdOrCtrl+Shift+Space"`, and
+    /// returns the id reported in `TaoEvent::GlobalShortcut`.
+    ///
+    /// Fails if another application already owns the shortcut.
+    pub fn register(&self, accelerator: String) -> Result<u32, TaoError> {
+        let hotkey = Accelerator::parse(accelerator)?.to_hotkey()?;
+        self.inner.get()?.register(hotkey)?;
+        self.registered.lock().unwrap().insert(hotkey.id(), hotkey);
+        Ok(hotkey.id())
+    }
+
+    /// Unregisters a shortcut returned by `register`. Unknown ids are ignored.
+    pub fn unregister(&self, id: u32) -> Result<(), TaoError> {
+        let manager = self.inner.get()?;
+        if let Some(hotkey) = self.registered.lock().unwrap().remove(&id) {
+            manager.unregister(hotkey)?;
+        }
+        Ok(())
+    }
+
+    pub fn unregister_all(&self) -> Result<(), TaoError> {
+        let manager = self.inner.get()?;
+        let hotkeys: Vec<HotKey> = self.registered.lock().unwrap().drain().map(|(_, hotkey)| hotkey).collect();
+        Ok(manager.unregister_all(&hotkeys)?)
+    }
+}
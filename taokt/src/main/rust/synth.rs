@@ -0,0 +1,82 @@
+//! Synthetic input for UI tests.
+//!
+//! Events are built the way tao would report them and injected into the event loop, so they
+//! reach the handler without touching the OS input queue. They carry device id 0, which no
+//! real device gets.
+
+use crate::{ElementState, Key, KeyCode, KeyEvent, KeyLocation, MouseButton, PhysicalPositionF64, TaoWindowEvent};
+
+pub(crate) const SYNTHETIC_DEVICE_ID: u64 = 0;
+
+/// The logical key and text a US layout produces for `code`.
+fn logical_key(code: &KeyCode) -> (Key, Option<String>) {
+    let character = match code {
+        KeyCode::Space => " ",
+        KeyCode::KeyA => "a",
+        KeyCode::KeyD => "d",
+        KeyCode::KeyL => "l",
+        KeyCode::KeyM => "m",
+        KeyCode::KeyV => "v",
+        KeyCode::Other { value } => return (Key::Other { value: value.clone() }, None),
+    };
+    (
+        Key::Character {
+            value: character.to_string(),
+        },
+        Some(character.to_string()),
+    )
+}
+
+/// A `KeyboardInput` event for `code`.
+pub(crate) fn key_event(code: KeyCode, state: ElementState) -> TaoWindowEvent {
+    let (logical_key, text) = logical_key(&code);
+    TaoWindowEvent::KeyboardInput {
+        device_id: SYNTHETIC_DEVICE_ID,
+        event: KeyEvent {
+            physical_key: code,
+            logical_key,
+            text: text.filter(|_| state == ElementState::Pressed),
+            location: KeyLocation::Standard,
+            state,
+            repeat: false,
+            scancode: None,
+        },
+    }
+}
+
+/// A press and release per character of `text`, without a physical key.
+pub(crate) fn text_events(text: &str) -> Vec<TaoWindowEvent> {
+    text.chars()
+        .flat_map(|c| {
+            [ElementState::Pressed, ElementState::Released].map(|state| TaoWindowEvent::KeyboardInput {
+                device_id: SYNTHETIC_DEVICE_ID,
+                event: KeyEvent {
+                    physical_key: KeyCode::Other {
+                        value: "Unidentified".to_string(),
+                    },
+                    logical_key: Key::Character { value: c.to_string() },
+                    text: (state == ElementState::Pressed).then(|| c.to_string()),
+                    location: KeyLocation::Standard,
+                    state,
+                    repeat: false,
+                    scancode: None,
+                },
+            })
+        })
+        .collect()
+}
+
+/// Moving to `position`, then pressing and releasing `button` once.
+pub(crate) fn click_events(position: PhysicalPositionF64, button: MouseButton) -> Vec<TaoWindowEvent> {
+    let mut events = vec![TaoWindowEvent::CursorMoved {
+        device_id: SYNTHETIC_DEVICE_ID,
+        position,
+    }];
+    events.extend([ElementState::Pressed, ElementState::Released].map(|state| TaoWindowEvent::MouseInput {
+        device_id: SYNTHETIC_DEVICE_ID,
+        state,
+        button,
+        click_count: 1,
+    }));
+    events
+}
@@ -337,3 +337,1473 @@ mod mouse_button_tests {
         }
     }
 }
+
+#[cfg(test)]
+mod queue_tests {
+    use crate::queue::*;
+    use crate::TaoUserEvent;
+
+    fn message(value: &str) -> TaoUserEvent {
+        TaoUserEvent::Message {
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_unbounded_queue_signals_wake_once() {
+        let queue = UserEventQueue::new(None);
+        assert!(queue.push(message("a")).unwrap());
+        assert!(!queue.push(message("b")).unwrap());
+        assert_eq!(queue.drain(), vec![message("a"), message("b")]);
+        assert!(queue.push(message("c")).unwrap());
+    }
+
+    #[test]
+    fn test_drop_oldest_keeps_newest_events() {
+        let queue = UserEventQueue::new(Some(UserEventQueueConfig {
+            capacity: 2,
+            overflow: UserEventOverflow::DropOldest,
+        }));
+        for value in ["a", "b", "c"] {
+            queue.push(message(value)).unwrap();
+        }
+        assert_eq!(queue.drain(), vec![message("b"), message("c")]);
+        assert_eq!(queue.dropped(), 1);
+    }
+
+    #[test]
+    fn test_error_policy_rejects_when_full() {
+        let queue = UserEventQueue::new(Some(UserEventQueueConfig {
+            capacity: 1,
+            overflow: UserEventOverflow::Error,
+        }));
+        queue.push(message("a")).unwrap();
+        assert!(queue.push(message("b")).is_err());
+        assert_eq!(queue.drain(), vec![message("a")]);
+    }
+
+    #[test]
+    fn test_block_policy_on_loop_thread_errors_instead_of_deadlocking() {
+        let queue = UserEventQueue::new(Some(UserEventQueueConfig {
+            capacity: 1,
+            overflow: UserEventOverflow::Block,
+        }));
+        queue.push(message("a")).unwrap();
+        assert!(queue.push(message("b")).is_err());
+    }
+
+    #[test]
+    fn test_closed_queue_rejects_events() {
+        let queue = UserEventQueue::new(None);
+        queue.close();
+        assert!(queue.push(message("a")).is_err());
+    }
+}
+
+#[cfg(test)]
+mod thread_tests {
+    use std::sync::Arc;
+
+    use crate::thread::ThreadBound;
+
+    #[test]
+    fn test_thread_bound_rejects_other_threads() {
+        let bound = Arc::new(ThreadBound::new(7));
+        assert_eq!(*bound.get().unwrap(), 7);
+        let remote = Arc::clone(&bound);
+        assert!(std::thread::spawn(move || remote.get().is_err()).join().unwrap());
+    }
+
+    #[test]
+    fn test_thread_bound_leaks_when_dropped_off_thread() {
+        let value = Arc::new(());
+        let bound = ThreadBound::new(Arc::clone(&value));
+        std::thread::spawn(move || drop(bound)).join().unwrap();
+        assert_eq!(Arc::strong_count(&value), 2);
+    }
+}
+
+#[cfg(test)]
+mod icon_tests {
+    use crate::icon::*;
+
+    #[test]
+    fn test_select_icon_size_prefers_smallest_larger_variant() {
+        let sizes = [16, 64, 32, 128];
+        assert_eq!(select_icon_size(&sizes, 32), Some(2));
+        assert_eq!(select_icon_size(&sizes, 40), Some(1));
+        assert_eq!(select_icon_size(&sizes, 8), Some(0));
+    }
+
+    #[test]
+    fn test_select_icon_size_falls_back_to_largest() {
+        assert_eq!(select_icon_size(&[16, 48, 32], 96), Some(1));
+        assert_eq!(select_icon_size(&[], 32), None);
+    }
+
+    #[test]
+    fn test_icon_set_desired_size_follows_scale() {
+        let set = IconSet::new(32);
+        assert_eq!(set.desired_size(1.0), 32);
+        assert_eq!(set.desired_size(1.5), 48);
+        assert_eq!(set.desired_size(2.0), 64);
+    }
+
+    #[test]
+    fn test_icon_set_best_for_scale() {
+        let set = IconSet::new(16);
+        set.add_rgba(vec![0; 16 * 16 * 4], 16, 16).unwrap();
+        set.add_rgba(vec![0; 32 * 32 * 4], 32, 32).unwrap();
+        assert!(set.best_for_scale(2.0).is_some());
+        assert!(IconSet::new(16).best_for_scale(1.0).is_none());
+    }
+
+    fn icns_entry(kind: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+        let mut entry = kind.to_vec();
+        entry.extend_from_slice(&(payload.len() as u32 + 8).to_be_bytes());
+        entry.extend_from_slice(payload);
+        entry
+    }
+
+    fn png_header(width: u32) -> Vec<u8> {
+        let mut png = b"\x89PNG\r\n\x1a\n\0\0\0\x0dIHDR".to_vec();
+        png.extend_from_slice(&width.to_be_bytes());
+        png.extend_from_slice(&width.to_be_bytes());
+        png
+    }
+
+    #[test]
+    fn test_largest_icns_png_picks_widest_png_entry() {
+        let mut body = icns_entry(b"is32", &[0; 16]);
+        body.extend(icns_entry(b"ic07", &png_header(128)));
+        body.extend(icns_entry(b"ic10", &png_header(1024)));
+        body.extend(icns_entry(b"ic08", &png_header(256)));
+        let mut icns = b"icns".to_vec();
+        icns.extend_from_slice(&(body.len() as u32 + 8).to_be_bytes());
+        icns.extend(body);
+
+        let png = largest_icns_png(&icns).unwrap();
+        assert_eq!(png, png_header(1024).as_slice());
+    }
+
+    #[test]
+    fn test_largest_icns_png_ignores_other_formats() {
+        assert_eq!(largest_icns_png(&png_header(32)), None);
+        assert_eq!(largest_icns_png(b"icns\0\0\0\x08"), None);
+    }
+
+    #[test]
+    fn test_packed_rgba_drops_row_padding_and_fills_alpha() {
+        // 2x2 RGB rows padded to 8 bytes.
+        let pixels = [1, 2, 3, 4, 5, 6, 0, 0, 7, 8, 9, 10, 11, 12, 0, 0];
+        assert_eq!(
+            packed_rgba(&pixels, 2, 2, 8, 3),
+            vec![1, 2, 3, 255, 4, 5, 6, 255, 7, 8, 9, 255, 10, 11, 12, 255]
+        );
+    }
+
+    #[test]
+    fn test_packed_rgba_keeps_alpha_channel() {
+        let pixels = [1, 2, 3, 4, 5, 6, 7, 8];
+        assert_eq!(packed_rgba(&pixels, 2, 1, 8, 4), pixels.to_vec());
+    }
+
+    #[test]
+    fn test_circular_mask_clears_corners_and_keeps_center() {
+        let image = image::RgbaImage::from_pixel(8, 8, image::Rgba([10, 20, 30, 255]));
+        let masked = circular_mask(&image);
+        assert_eq!(masked.get_pixel(0, 0)[3], 0);
+        assert_eq!(masked.get_pixel(7, 7)[3], 0);
+        assert_eq!(masked.get_pixel(4, 4), &image::Rgba([10, 20, 30, 255]));
+    }
+
+    #[test]
+    fn test_pad_centers_image_on_transparent_canvas() {
+        let image = image::RgbaImage::from_pixel(2, 2, image::Rgba([1, 2, 3, 255]));
+        let padded = pad(&image, 3);
+        assert_eq!(padded.dimensions(), (8, 8));
+        assert_eq!(padded.get_pixel(2, 2)[3], 0);
+        assert_eq!(padded.get_pixel(3, 3), &image::Rgba([1, 2, 3, 255]));
+        assert_eq!(padded.get_pixel(4, 4), &image::Rgba([1, 2, 3, 255]));
+        assert_eq!(padded.get_pixel(5, 5)[3], 0);
+    }
+
+    #[test]
+    fn test_render_badge_draws_text_inside_disc() {
+        let badge = render_badge("3", 16);
+        assert_eq!(badge.dimensions(), (16, 16));
+        assert_eq!(badge.get_pixel(0, 0)[3], 0);
+        let white = badge.pixels().filter(|p| p.0 == [255, 255, 255, 255]).count();
+        assert!(white > 0);
+    }
+
+    #[test]
+    fn test_render_badge_skips_unknown_characters() {
+        assert_eq!(render_badge("x", 16), render_badge("", 16));
+        assert_ne!(render_badge("x1", 16), render_badge("", 16));
+    }
+}
+
+#[cfg(test)]
+mod intern_tests {
+    use crate::intern::*;
+
+    #[derive(Debug)]
+    #[allow(dead_code)]
+    enum Sample {
+        Unit,
+        Tuple(u32),
+        Struct { value: u32 },
+    }
+
+    #[test]
+    fn test_describe_uses_type_and_variant_name() {
+        assert_eq!(describe::<Sample>("Unit"), "Sample::Unit");
+        assert_eq!(describe::<Sample>("Tuple(3)"), "Sample::Tuple");
+        assert_eq!(describe::<Sample>("Struct { value: 3 }"), "Sample::Struct");
+    }
+
+    #[test]
+    fn test_intern_is_stable_per_variant() {
+        let first = intern_variant(&Sample::Tuple(1));
+        assert_eq!(intern_variant(&Sample::Tuple(2)), first);
+        assert_ne!(intern_variant(&Sample::Struct { value: 1 }), first);
+        assert_eq!(describe_other(first).as_deref(), Some("Sample::Tuple"));
+        assert_eq!(describe_other(u32::MAX), None);
+    }
+}
+
+#[cfg(test)]
+mod mouse_button_mapping_tests {
+    use crate::types::*;
+
+    #[test]
+    fn test_back_forward_round_trip() {
+        for button in [MouseButton::Back, MouseButton::Forward, MouseButton::Left, MouseButton::Middle] {
+            let native: tao::event::MouseButton = button.into();
+            let converted: MouseButton = native.into();
+            #[cfg(any(
+                target_os = "windows",
+                target_os = "macos",
+                target_os = "linux",
+                target_os = "freebsd"
+            ))]
+            assert_eq!(converted, button);
+            let _ = converted;
+        }
+    }
+
+    #[test]
+    fn test_unmapped_other_buttons_stay_other() {
+        let converted: MouseButton = tao::event::MouseButton::Other(42).into();
+        assert_eq!(converted, MouseButton::Other { value: 42 });
+    }
+}
+
+#[cfg(test)]
+mod click_tests {
+    use std::time::{Duration, Instant};
+
+    use crate::click::ClickTracker;
+    use crate::types::*;
+    use crate::TaoWindowEvent;
+
+    fn input(state: ElementState) -> TaoWindowEvent {
+        TaoWindowEvent::MouseInput {
+            device_id: 1,
+            state,
+            button: MouseButton::Left,
+            click_count: 1,
+        }
+    }
+
+    fn count(event: &TaoWindowEvent) -> u32 {
+        match event {
+            TaoWindowEvent::MouseInput { click_count, .. } => *click_count,
+            _ => panic!("Expected MouseInput"),
+        }
+    }
+
+    fn press(tracker: &mut ClickTracker, window_id: u64, at: Instant) -> u32 {
+        let mut event = input(ElementState::Pressed);
+        tracker.track(window_id, &mut event, at);
+        count(&event)
+    }
+
+    #[test]
+    fn test_quick_presses_increment_click_count() {
+        let mut tracker = ClickTracker::new(Duration::from_millis(500), 4.0);
+        let start = Instant::now();
+        assert_eq!(press(&mut tracker, 1, start), 1);
+        assert_eq!(press(&mut tracker, 1, start + Duration::from_millis(200)), 2);
+        assert_eq!(press(&mut tracker, 1, start + Duration::from_millis(400)), 3);
+
+        let mut release = input(ElementState::Released);
+        tracker.track(1, &mut release, start + Duration::from_millis(450));
+        assert_eq!(count(&release), 3);
+    }
+
+    #[test]
+    fn test_slow_or_distant_presses_reset_click_count() {
+        let mut tracker = ClickTracker::new(Duration::from_millis(500), 4.0);
+        let start = Instant::now();
+        assert_eq!(press(&mut tracker, 1, start), 1);
+        assert_eq!(press(&mut tracker, 1, start + Duration::from_millis(900)), 1);
+
+        let mut moved = TaoWindowEvent::CursorMoved {
+            device_id: 1,
+            position: PhysicalPositionF64 { x: 50.0, y: 0.0 },
+        };
+        tracker.track(1, &mut moved, start + Duration::from_millis(950));
+        assert_eq!(press(&mut tracker, 1, start + Duration::from_millis(1000)), 1);
+        assert_eq!(press(&mut tracker, 2, start + Duration::from_millis(1100)), 1);
+    }
+}
+
+#[cfg(test)]
+mod deep_link_tests {
+    use crate::deep_link::*;
+
+    #[test]
+    fn test_scheme_validation() {
+        assert!(is_valid_scheme("myapp"));
+        assert!(is_valid_scheme("my-app+v2.beta"));
+        assert!(!is_valid_scheme(""));
+        assert!(!is_valid_scheme("1app"));
+        assert!(!is_valid_scheme("my app"));
+        assert!(!is_valid_scheme("myapp://"));
+    }
+
+    #[test]
+    fn test_urls_with_schemes_filters_arguments() {
+        let args = vec![
+            "--flag".to_string(),
+            "myapp://login-callback?code=1".to_string(),
+            "MyApp:open".to_string(),
+            "other://x".to_string(),
+            "myapp".to_string(),
+        ];
+        let urls = urls_with_schemes(args, &["myapp".to_string()]);
+        assert_eq!(urls, vec!["myapp://login-callback?code=1", "MyApp:open"]);
+    }
+
+    #[test]
+    fn test_urls_message_round_trip() {
+        let urls = vec!["myapp://a".to_string(), "myapp://b?c=d".to_string()];
+        assert_eq!(decode_urls_message(&encode_urls_message(&urls)), Some(urls));
+        assert_eq!(decode_urls_message(b"myapp://a"), None);
+    }
+
+    #[test]
+    fn test_desktop_exec_quote_escapes_special_characters() {
+        assert_eq!(desktop_exec_quote("/opt/My App/app"), "\"/opt/My App/app\"");
+        assert_eq!(desktop_exec_quote("/a\"b$c`d\\e%f"), "\"/a\\\"b\\$c\\`d\\\\e%%f\"");
+        assert_eq!(desktop_string("a\\b"), "a\\\\b");
+    }
+}
+
+#[cfg(test)]
+mod control_flow_tests {
+    use crate::types::*;
+
+    #[test]
+    fn test_exit_with_code_conversion() {
+        let native = ControlFlow::ExitWithCode { code: 3 }.to_tao();
+        assert!(matches!(native, Some(tao::event_loop::ControlFlow::ExitWithCode(3))));
+        assert!(ControlFlow::Keep.to_tao().is_none());
+    }
+}
+
+#[cfg(test)]
+mod event_mask_tests {
+    use crate::events::*;
+    use crate::types::*;
+
+    #[test]
+    fn test_default_mask_keeps_everything() {
+        let mask = EventMask::default();
+        assert!(!mask.suppresses(&TaoEvent::RedrawEventsCleared));
+        assert!(!mask.suppresses(&TaoEvent::Other { code: 0 }));
+    }
+
+    #[test]
+    fn test_mask_suppresses_selected_classes() {
+        let mask = EventMask {
+            cursor_moved: true,
+            other: true,
+            ..EventMask::default()
+        };
+        let moved = TaoEvent::WindowEvent {
+            window_id: 1,
+            event: TaoWindowEvent::CursorMoved {
+                device_id: 0,
+                position: PhysicalPositionF64 { x: 1.0, y: 2.0 },
+            },
+        };
+        let other = TaoEvent::DeviceEvent {
+            device_id: 0,
+            event: TaoDeviceEvent::Other { code: 7 },
+        };
+        assert!(mask.suppresses(&moved));
+        assert!(mask.suppresses(&other));
+        assert!(!mask.suppresses(&TaoEvent::RedrawEventsCleared));
+        assert!(!mask.suppresses(&TaoEvent::MainEventsCleared));
+    }
+}
+
+#[cfg(test)]
+mod coalesce_tests {
+    use crate::coalesce::MotionCoalescer;
+    use crate::events::*;
+    use crate::types::*;
+
+    fn moved(x: f64) -> TaoEvent {
+        TaoEvent::WindowEvent {
+            window_id: 1,
+            event: TaoWindowEvent::CursorMoved {
+                device_id: 1,
+                position: PhysicalPositionF64 { x, y: 0.0 },
+            },
+        }
+    }
+
+    fn motion(dx: f64) -> TaoEvent {
+        TaoEvent::DeviceEvent {
+            device_id: 1,
+            event: TaoDeviceEvent::MouseMotion { delta_x: dx, delta_y: 1.0 },
+        }
+    }
+
+    #[test]
+    fn test_motion_is_merged_until_next_event() {
+        let mut coalescer = MotionCoalescer::default();
+        for x in 1..=3 {
+            assert!(coalescer.push(motion(2.0), true).is_empty());
+            assert!(coalescer.push(moved(x as f64), true).is_empty());
+        }
+
+        let ready = coalescer.push(TaoEvent::MainEventsCleared, true);
+        assert_eq!(
+            ready,
+            vec![
+                TaoEvent::DeviceEvent {
+                    device_id: 1,
+                    event: TaoDeviceEvent::MouseMotion { delta_x: 6.0, delta_y: 3.0 },
+                },
+                moved(3.0),
+                TaoEvent::MainEventsCleared,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_disabled_coalescing_passes_through() {
+        let mut coalescer = MotionCoalescer::default();
+        assert_eq!(coalescer.push(moved(1.0), false), vec![moved(1.0)]);
+    }
+}
+
+#[cfg(test)]
+mod timer_tests {
+    use std::time::{Duration, Instant};
+
+    use crate::timer::Timers;
+
+    #[test]
+    fn test_one_shot_timer_fires_once() {
+        let start = Instant::now();
+        let mut timers = Timers::new();
+        let id = timers.add(Duration::from_millis(10), false, start);
+
+        assert!(timers.take_due(start).is_empty());
+        assert_eq!(timers.next_deadline(), Some(start + Duration::from_millis(10)));
+        assert_eq!(timers.take_due(start + Duration::from_millis(10)), vec![id]);
+        assert!(timers.next_deadline().is_none());
+        assert!(!timers.cancel(id));
+    }
+
+    #[test]
+    fn test_repeating_timer_is_rescheduled_in_order() {
+        let start = Instant::now();
+        let mut timers = Timers::new();
+        let slow = timers.add(Duration::from_millis(20), true, start);
+        let fast = timers.add(Duration::from_millis(5), true, start);
+
+        let now = start + Duration::from_millis(25);
+        assert_eq!(timers.take_due(now), vec![fast, slow]);
+        assert_eq!(timers.next_deadline(), Some(now + Duration::from_millis(5)));
+
+        assert!(timers.cancel(fast));
+        assert_eq!(timers.next_deadline(), Some(start + Duration::from_millis(40)));
+    }
+}
+
+#[cfg(test)]
+mod frame_tests {
+    use std::time::{Duration, Instant};
+
+    use crate::frame::{FrameGroups, FramePacer, TimerResolution};
+
+    #[test]
+    fn test_first_frame_is_immediate() {
+        let now = Instant::now();
+        let pacer = FramePacer::new(Some(50));
+        assert!(pacer.is_due(now));
+        assert_eq!(pacer.next_frame(now), now);
+    }
+
+    #[test]
+    fn test_frames_follow_target_fps() {
+        let start = Instant::now();
+        let mut pacer = FramePacer::new(Some(50));
+        pacer.start_frame(start);
+
+        let early = start + Duration::from_millis(5);
+        assert!(!pacer.is_due(early));
+        assert_eq!(pacer.next_frame(early), start + Duration::from_millis(20));
+
+        // Waking slightly late keeps the original cadence.
+        let late = start + Duration::from_millis(22);
+        assert!(pacer.is_due(late));
+        pacer.start_frame(late);
+        assert_eq!(pacer.next_frame(late), start + Duration::from_millis(40));
+    }
+
+    #[test]
+    fn test_refresh_rate_only_applies_without_target_fps() {
+        let mut pacer = FramePacer::new(None);
+        pacer.set_refresh_rate(144_000);
+        assert_eq!(pacer.interval(), Duration::from_secs_f64(1.0 / 144.0));
+
+        let mut fixed = FramePacer::new(Some(50));
+        fixed.set_refresh_rate(144_000);
+        assert_eq!(fixed.interval(), Duration::from_millis(20));
+    }
+
+    #[test]
+    fn test_align_follows_vblank_phase() {
+        let start = Instant::now() + Duration::from_secs(1);
+        let mut pacer = FramePacer::new(None);
+        pacer.set_refresh_rate(50_000);
+        pacer.start_frame(start);
+        pacer.align(start, start - Duration::from_millis(5));
+        assert_eq!(pacer.next_frame(start), start + Duration::from_millis(15));
+
+        // A stale vblank, or a fixed target, leaves the cadence alone.
+        pacer.start_frame(start + Duration::from_millis(15));
+        pacer.align(start + Duration::from_millis(15), start - Duration::from_millis(30));
+        assert_eq!(pacer.next_frame(start), start + Duration::from_millis(35));
+        let mut fixed = FramePacer::new(Some(50));
+        fixed.start_frame(start);
+        fixed.align(start, start - Duration::from_millis(5));
+        assert_eq!(fixed.next_frame(start), start + Duration::from_millis(20));
+    }
+
+    #[test]
+    fn test_frame_groups_pace_each_monitor_at_its_rate() {
+        let start = Instant::now();
+        let monitors = [("a".to_string(), 50_000), ("b".to_string(), 100_000)];
+        let mut groups = FrameGroups::new(None);
+        let mut due = groups.start_due(start, &monitors);
+        due.sort();
+        assert_eq!(due, ["a", "b"]);
+
+        let later = start + Duration::from_millis(12);
+        assert_eq!(groups.start_due(later, &monitors), ["b"]);
+        assert_eq!(groups.next_frame(later, ["a", "b"]), Some(start + Duration::from_millis(20)));
+        // A monitor without a pacer yet may start right away.
+        assert_eq!(groups.next_frame(later, ["c"]), Some(later));
+    }
+
+    #[test]
+    fn test_frame_groups_forget_monitors_without_windows() {
+        let start = Instant::now();
+        let mut groups = FrameGroups::new(Some(50));
+        groups.start_due(start, &[("a".to_string(), 0)]);
+        assert_eq!(groups.start_due(start, &[("b".to_string(), 0)]), ["b"]);
+        // "a" starts over with an immediate frame.
+        assert_eq!(groups.start_due(start, &[("a".to_string(), 0)]), ["a"]);
+    }
+
+    #[test]
+    fn test_timer_resolution_is_raised_only_for_wait_until() {
+        use tao::event_loop::ControlFlow;
+
+        let mut resolution = TimerResolution::new(true);
+        resolution.update(&ControlFlow::WaitUntil(Instant::now()));
+        assert!(resolution.raised());
+        resolution.update(&ControlFlow::Wait);
+        assert!(!resolution.raised());
+
+        let mut disabled = TimerResolution::new(false);
+        disabled.update(&ControlFlow::WaitUntil(Instant::now()));
+        assert!(!disabled.raised());
+    }
+}
+
+#[cfg(test)]
+mod frame_clock_tests {
+    use std::time::{Duration, Instant};
+
+    use crate::frame::FrameClock;
+
+    #[test]
+    fn test_frame_clock_tracks_delta_and_index() {
+        let start = Instant::now();
+        let interval = Duration::from_millis(16);
+        let mut clock = FrameClock::new(start);
+
+        let first = clock.tick(start + Duration::from_millis(10), interval);
+        assert_eq!(first.frame_index, 0);
+        assert_eq!(first.delta_seconds, 0.0);
+        assert!((first.timestamp_seconds - 0.010).abs() < 1e-9);
+
+        let second = clock.tick(start + Duration::from_millis(30), interval);
+        assert_eq!(second.frame_index, 1);
+        assert!((second.delta_seconds - 0.020).abs() < 1e-9);
+        assert!((second.refresh_interval_seconds - 0.016).abs() < 1e-9);
+    }
+}
+
+#[cfg(test)]
+mod metrics_tests {
+    use std::time::{Duration, Instant};
+
+    use crate::events::*;
+    use crate::metrics::{event_key, Metrics};
+
+    #[test]
+    fn test_metrics_group_by_event_type() {
+        let mut metrics = Metrics::new(true);
+        let key = event_key(&TaoEvent::MainEventsCleared);
+        metrics.record_delivery(key, Duration::from_micros(40));
+        metrics.record_delivery(key, Duration::from_micros(3_000));
+        metrics.record_suppressed();
+        metrics.record_coalesced(4);
+
+        let snapshot = metrics.snapshot(Instant::now(), 2);
+        assert_eq!(snapshot.events_delivered, 2);
+        assert_eq!(snapshot.suppressed_events, 1);
+        assert_eq!(snapshot.coalesced_events, 4);
+        assert_eq!(snapshot.dropped_user_events, 2);
+
+        let stats = &snapshot.event_types[0];
+        assert_eq!(stats.event_type, "TaoEvent::MainEventsCleared");
+        assert_eq!(stats.count, 2);
+        assert_eq!(stats.max_callback_micros, 3_000);
+        assert_eq!(stats.latency_histogram.len(), snapshot.latency_bucket_bounds_micros.len() + 1);
+        assert_eq!(stats.latency_histogram[0], 1);
+        assert_eq!(stats.latency_histogram[6], 1);
+    }
+
+    #[test]
+    fn test_window_events_use_inner_variant() {
+        let key = event_key(&TaoEvent::WindowEvent {
+            window_id: 1,
+            event: TaoWindowEvent::CloseRequested,
+        });
+        assert_eq!(crate::describe_other(key).as_deref(), Some("TaoWindowEvent::CloseRequested"));
+    }
+}
+
+#[cfg(test)]
+mod event_window_tests {
+    use crate::events::*;
+
+    #[test]
+    fn test_window_id_of_events() {
+        let event = TaoEvent::WindowEvent {
+            window_id: 3,
+            event: TaoWindowEvent::CloseRequested,
+        };
+        assert_eq!(event.window_id(), Some(3));
+        let redraw = TaoEvent::RedrawRequested {
+            window_id: 4,
+            dirty_rects: Vec::new(),
+        };
+        assert_eq!(redraw.window_id(), Some(4));
+        assert_eq!(TaoEvent::MainEventsCleared.window_id(), None);
+    }
+}
+
+#[cfg(all(test, feature = "headless"))]
+mod headless_tests {
+    use std::sync::{Arc, Mutex};
+
+    use crate::*;
+
+    struct Recorder(Arc<Mutex<Vec<TaoEvent>>>);
+
+    impl TaoEventHandler for Recorder {
+        fn handle_event(&self, event: TaoEvent, _app: Arc<App>) -> ControlFlow {
+            let close = matches!(
+                event,
+                TaoEvent::WindowEvent {
+                    event: TaoWindowEvent::CloseRequested,
+                    ..
+                }
+            );
+            self.0.lock().unwrap().push(event);
+            if close { ControlFlow::ExitWithCode { code: 2 } } else { ControlFlow::Keep }
+        }
+    }
+
+    #[test]
+    fn test_injected_events_are_delivered_in_order() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let event_loop = TestEventLoop::new(RunConfig::default());
+        let app = event_loop.app();
+
+        assert_eq!(event_loop.step(Box::new(Recorder(events.clone()))), None);
+        app.inject_window_event(7, TaoWindowEvent::CloseRequested).unwrap();
+        assert_eq!(event_loop.step(Box::new(Recorder(events.clone()))), Some(2));
+
+        let events = events.lock().unwrap();
+        assert_eq!(events[0], TaoEvent::NewEvents { cause: TaoStartCause::Init });
+        assert!(events.contains(&TaoEvent::WindowEvent {
+            window_id: 7,
+            event: TaoWindowEvent::CloseRequested,
+        }));
+    }
+}
+
+#[cfg(test)]
+mod probe_tests {
+    use crate::graphics::GraphicsBackend;
+    use crate::probe::{select, BackendCapability};
+
+    fn capability(backend: GraphicsBackend, available: bool) -> BackendCapability {
+        BackendCapability {
+            backend,
+            available,
+            detail: if available { "ok".into() } else { "missing".into() },
+        }
+    }
+
+    #[test]
+    fn test_select_skips_unavailable_preferences() {
+        let capabilities = [
+            capability(GraphicsBackend::Vulkan, false),
+            capability(GraphicsBackend::OpenGL, true),
+        ];
+        let selection = select(&capabilities, &[GraphicsBackend::Vulkan, GraphicsBackend::OpenGL]);
+        assert_eq!(selection.backend, Some(GraphicsBackend::OpenGL));
+        assert!(selection.reason.contains("skipped Vulkan: missing"));
+    }
+
+    #[test]
+    fn test_select_reports_when_nothing_matches() {
+        let capabilities = [capability(GraphicsBackend::Metal, false)];
+        let selection = select(&capabilities, &[GraphicsBackend::Metal]);
+        assert_eq!(selection.backend, None);
+        assert!(selection.reason.starts_with("No available backend"));
+    }
+}
+
+#[cfg(test)]
+mod damage_tests {
+    use crate::{damage::DirtyRegion, PhysicalRect};
+
+    fn rect(x: i32, y: i32, width: u32, height: u32) -> PhysicalRect {
+        PhysicalRect { x, y, width, height }
+    }
+
+    #[test]
+    fn test_contained_rects_are_merged() {
+        let mut region = DirtyRegion::default();
+        region.add(rect(10, 10, 5, 5));
+        region.add(rect(0, 0, 100, 20));
+        region.add(rect(20, 5, 10, 10));
+        region.add(rect(0, 50, 0, 10));
+        assert_eq!(region.take(), vec![rect(0, 0, 100, 20)]);
+        assert!(region.take().is_empty());
+    }
+
+    #[test]
+    fn test_full_redraw_wins() {
+        let mut region = DirtyRegion::default();
+        region.add(rect(0, 0, 10, 10));
+        region.invalidate_all();
+        region.add(rect(20, 20, 10, 10));
+        assert!(region.take().is_empty());
+
+        region.add(rect(20, 20, 10, 10));
+        assert_eq!(region.take(), vec![rect(20, 20, 10, 10)]);
+    }
+
+    #[test]
+    fn test_many_rects_collapse_to_bounds() {
+        let mut region = DirtyRegion::default();
+        for i in 0..20 {
+            region.add(rect(i * 10, 0, 5, 5));
+        }
+        assert_eq!(region.take(), vec![rect(0, 0, 195, 5)]);
+    }
+}
+
+#[cfg(test)]
+mod monitor_tests {
+    use crate::{
+        monitor::{pick_best_mode, pick_current_mode, ModeSummary, ScaleTracker},
+        PhysicalSizeU32,
+    };
+
+    fn mode(width: u32, height: u32, bit_depth: u16, refresh_rate_hz: u16) -> ModeSummary {
+        ModeSummary {
+            size: PhysicalSizeU32 { width, height },
+            bit_depth,
+            refresh_rate_hz,
+        }
+    }
+
+    #[test]
+    fn test_current_mode_matches_size_and_rate() {
+        let modes = [
+            mode(2560, 1440, 32, 144),
+            mode(2560, 1440, 24, 60),
+            mode(2560, 1440, 32, 60),
+            mode(1920, 1080, 32, 60),
+        ];
+        let size = PhysicalSizeU32 {
+            width: 2560,
+            height: 1440,
+        };
+        // 59.94 Hz rounds to the 60 Hz mode.
+        assert_eq!(pick_current_mode(&modes, size, Some(59_940)), Some(2));
+        assert_eq!(pick_current_mode(&modes, size, None), Some(0));
+        assert_eq!(pick_current_mode(&modes, size, Some(75_000)), None);
+    }
+
+    #[test]
+    fn test_best_mode_ranks_size_then_refresh_then_depth() {
+        let modes = [
+            mode(1920, 1080, 24, 60),
+            mode(2560, 1440, 24, 144),
+            mode(2560, 1440, 32, 60),
+            mode(2560, 1440, 24, 60),
+            mode(1920, 1200, 32, 120),
+        ];
+        let size = |width, height| PhysicalSizeU32 { width, height };
+        assert_eq!(pick_best_mode(&modes, size(2560, 1440), None), Some(1));
+        assert_eq!(pick_best_mode(&modes, size(2560, 1440), Some(60)), Some(2));
+        // No exact match: the closest size wins over a better refresh rate.
+        assert_eq!(pick_best_mode(&modes, size(1920, 1090), Some(120)), Some(0));
+        assert_eq!(pick_best_mode(&[], size(800, 600), None), None);
+    }
+
+    #[test]
+    fn test_scale_tracker_reports_changes_after_first_sighting() {
+        let start = std::time::Instant::now();
+        let mut tracker = ScaleTracker::default();
+        assert!(tracker.is_due(start));
+
+        let first = tracker.update(start, vec![("A".to_string(), 1.0, 'a'), ("B".to_string(), 2.0, 'b')]);
+        assert!(first.is_empty());
+        assert!(!tracker.is_due(start));
+
+        let later = start + std::time::Duration::from_secs(1);
+        assert!(tracker.is_due(later));
+        let changed = tracker.update(later, vec![("A".to_string(), 1.5, 'a'), ("B".to_string(), 2.0, 'b')]);
+        assert_eq!(changed, vec![('a', 1.0)]);
+    }
+
+    #[test]
+    fn test_scale_tracker_forgets_removed_monitors() {
+        let now = std::time::Instant::now();
+        let mut tracker = ScaleTracker::default();
+        tracker.update(now, vec![("A".to_string(), 1.0, ())]);
+        tracker.update(now, Vec::new());
+        assert!(tracker.update(now, vec![("A".to_string(), 2.0, ())]).is_empty());
+    }
+
+    #[test]
+    fn test_scale_tracker_schedules_next_check() {
+        let now = std::time::Instant::now();
+        let mut tracker = ScaleTracker::default();
+        assert_eq!(tracker.next_check(), None);
+        tracker.update(now, Vec::<(String, f64, ())>::new());
+        assert_eq!(tracker.next_check(), Some(now + std::time::Duration::from_secs(1)));
+    }
+}
+
+#[cfg(test)]
+mod dpi_tests {
+    use crate::{
+        logical_to_physical_position, logical_to_physical_size, physical_to_logical_size, LogicalPosition, LogicalSize,
+        PhysicalPositionI32, PhysicalSizeU32,
+    };
+
+    #[test]
+    fn test_sizes_round_like_tao() {
+        let logical = LogicalSize {
+            width: 100.5,
+            height: 33.3,
+        };
+        let physical = logical_to_physical_size(logical, 1.5).unwrap();
+        assert_eq!(
+            physical,
+            PhysicalSizeU32 {
+                width: 151,
+                height: 50
+            }
+        );
+        assert_eq!(logical.to_physical(1.5).unwrap(), physical);
+
+        let back = physical_to_logical_size(PhysicalSizeU32 { width: 300, height: 150 }, 1.5).unwrap();
+        assert_eq!(
+            back,
+            LogicalSize {
+                width: 200.0,
+                height: 100.0
+            }
+        );
+    }
+
+    #[test]
+    fn test_positions_round_trip() {
+        let position = LogicalPosition { x: -10.0, y: 20.0 };
+        let physical = logical_to_physical_position(position, 1.25).unwrap();
+        assert_eq!(physical, PhysicalPositionI32 { x: -13, y: 25 });
+        assert_eq!(physical.to_logical(1.25).unwrap(), LogicalPosition { x: -10.4, y: 20.0 });
+    }
+
+    #[test]
+    fn test_invalid_scale_factor_is_an_error() {
+        let size = LogicalSize {
+            width: 1.0,
+            height: 1.0,
+        };
+        assert!(logical_to_physical_size(size, 0.0).is_err());
+        assert!(logical_to_physical_size(size, f64::NAN).is_err());
+    }
+}
+
+#[cfg(test)]
+mod cursor_name_tests {
+    use crate::cursor::{macos_cursor_selector, windows_cursor_id};
+
+    #[test]
+    fn test_windows_cursor_id_accepts_bare_and_prefixed_names() {
+        assert_eq!(windows_cursor_id("IDC_HAND"), Some(32649));
+        assert_eq!(windows_cursor_id("pin"), Some(32671));
+        assert_eq!(windows_cursor_id("idc_sizeall"), Some(32646));
+        assert_eq!(windows_cursor_id("zoom-in"), None);
+    }
+
+    #[test]
+    fn test_macos_cursor_names_are_an_allowlist() {
+        assert_eq!(macos_cursor_selector("dragCopyCursor"), Some(c"dragCopyCursor"));
+        assert_eq!(macos_cursor_selector("_windowResizeNorthEastCursor"), None);
+        assert_eq!(macos_cursor_selector("release"), None);
+    }
+}
+
+#[cfg(test)]
+mod grab_tests {
+    use crate::{
+        grab::{CursorGrab, CursorGrabMode, GrabEmulation, GrabInput},
+        PhysicalPositionF64, PhysicalSizeU32,
+    };
+
+    const SIZE: PhysicalSizeU32 = PhysicalSizeU32 {
+        width: 100,
+        height: 50,
+    };
+
+    fn moved(x: f64, y: f64) -> GrabInput {
+        GrabInput::Moved(PhysicalPositionF64 { x, y })
+    }
+
+    #[test]
+    fn test_native_grab_never_warps() {
+        let mut grab = CursorGrab::new(CursorGrabMode::None, GrabEmulation::Native);
+        assert!(!grab.is_emulated());
+        assert_eq!(grab.correction(moved(-10.0, 500.0), SIZE), None);
+        assert_eq!(grab.correction(GrabInput::Left, SIZE), None);
+    }
+
+    #[test]
+    fn test_lock_warps_back_to_first_position() {
+        let mut grab = CursorGrab::new(CursorGrabMode::Locked, GrabEmulation::Lock);
+        assert_eq!(grab.correction(moved(10.0, 20.0), SIZE), None);
+        assert_eq!(
+            grab.correction(moved(15.0, 22.0), SIZE),
+            Some(PhysicalPositionF64 { x: 10.0, y: 20.0 })
+        );
+        assert_eq!(grab.correction(moved(10.0, 20.0), SIZE), None);
+    }
+
+    #[test]
+    fn test_confine_clamps_into_client_area() {
+        let mut grab = CursorGrab::new(CursorGrabMode::Confined, GrabEmulation::Confine);
+        assert_eq!(grab.correction(moved(40.0, 30.0), SIZE), None);
+        assert_eq!(
+            grab.correction(moved(140.0, -5.0), SIZE),
+            Some(PhysicalPositionF64 { x: 99.0, y: 0.0 })
+        );
+        assert_eq!(
+            grab.correction(GrabInput::Left, SIZE),
+            Some(PhysicalPositionF64 { x: 99.0, y: 0.0 })
+        );
+    }
+}
+
+#[cfg(test)]
+mod synth_tests {
+    use crate::{
+        synth::{click_events, key_event, text_events, SYNTHETIC_DEVICE_ID},
+        ElementState, Key, KeyCode, MouseButton, PhysicalPositionF64, TaoWindowEvent,
+    };
+
+    #[test]
+    fn test_key_event_has_text_only_when_pressed() {
+        let pressed = key_event(KeyCode::KeyA, ElementState::Pressed);
+        let TaoWindowEvent::KeyboardInput { event: pressed, .. } = pressed else {
+            panic!("expected KeyboardInput");
+        };
+        assert_eq!(pressed.logical_key, Key::Character { value: "a".into() });
+        assert_eq!(pressed.text.as_deref(), Some("a"));
+
+        let released = key_event(KeyCode::KeyA, ElementState::Released);
+        let TaoWindowEvent::KeyboardInput { event: released, .. } = released else {
+            panic!("expected KeyboardInput");
+        };
+        assert_eq!(released.text, None);
+    }
+
+    #[test]
+    fn test_text_events_press_and_release_each_character() {
+        let events = text_events("hé");
+        assert_eq!(events.len(), 4);
+        let texts: Vec<_> = events
+            .iter()
+            .map(|event| match event {
+                TaoWindowEvent::KeyboardInput { event, .. } => event.text.clone(),
+                _ => panic!("expected KeyboardInput"),
+            })
+            .collect();
+        assert_eq!(texts, vec![Some("h".into()), None, Some("é".into()), None]);
+    }
+
+    #[test]
+    fn test_click_events_move_then_press_and_release() {
+        let position = PhysicalPositionF64 { x: 5.0, y: 7.0 };
+        let events = click_events(position, MouseButton::Left);
+        assert_eq!(
+            events,
+            vec![
+                TaoWindowEvent::CursorMoved {
+                    device_id: SYNTHETIC_DEVICE_ID,
+                    position,
+                },
+                TaoWindowEvent::MouseInput {
+                    device_id: SYNTHETIC_DEVICE_ID,
+                    state: ElementState::Pressed,
+                    button: MouseButton::Left,
+                    click_count: 1,
+                },
+                TaoWindowEvent::MouseInput {
+                    device_id: SYNTHETIC_DEVICE_ID,
+                    state: ElementState::Released,
+                    button: MouseButton::Left,
+                    click_count: 1,
+                },
+            ]
+        );
+    }
+}
+
+#[cfg(test)]
+mod device_tests {
+    use crate::{
+        devices::{ids_from_device_path, kind_from_hid_usage, parse_proc_input_devices},
+        InputDeviceKind,
+    };
+
+    const PROC_INPUT_DEVICES: &str = "\
+I: Bus=0011 Vendor=0001 Product=0001 Version=ab41
+N: Name=\"AT Translated Set 2 keyboard\"
+H: Handlers=sysrq kbd leds event3
+B: PROP=0
+B: EV=120013
+
+I: Bus=0019 Vendor=0000 Product=0001 Version=0000
+N: Name=\"Power Button\"
+H: Handlers=kbd event0
+B: PROP=0
+B: EV=3
+
+I: Bus=0003 Vendor=046d Product=c52b Version=0111
+N: Name=\"Logitech USB Receiver\"
+H: Handlers=mouse0 event5
+B: EV=17
+
+I: Bus=0018 Vendor=04f3 Product=2a1c Version=0100
+N: Name=\"ELAN Touchscreen\"
+H: Handlers=mouse1 event7
+B: PROP=2
+B: EV=b
+B: ABS=3273800000000003
+
+I: Bus=0000 Vendor=0000 Product=0000 Version=0000
+N: Name=\"No event node\"
+H: Handlers=js0
+";
+
+    #[test]
+    fn test_parse_proc_input_devices_classifies_devices() {
+        let devices = parse_proc_input_devices(PROC_INPUT_DEVICES);
+        let summary: Vec<_> = devices.iter().map(|d| (d.id.as_str(), d.kind)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("/dev/input/event3", InputDeviceKind::Keyboard),
+                ("/dev/input/event0", InputDeviceKind::Other),
+                ("/dev/input/event5", InputDeviceKind::Mouse),
+                ("/dev/input/event7", InputDeviceKind::Touch),
+            ]
+        );
+        assert_eq!(devices[2].name, "Logitech USB Receiver");
+        assert_eq!(devices[2].vendor_id, Some(0x046d));
+        assert_eq!(devices[2].product_id, Some(0xc52b));
+    }
+
+    #[test]
+    fn test_ids_from_device_path() {
+        let path = r"\\?\HID#VID_046D&PID_C52B&MI_00#7&1a2b3c&0&0000#{884b96c3-56ef-11d1-bc8c-00a0c91405dd}";
+        assert_eq!(ids_from_device_path(path), (Some(0x046d), Some(0xc52b)));
+        assert_eq!(ids_from_device_path(r"\\?\ACPI#PNP0303#4&1d401fb5&0"), (None, None));
+    }
+
+    #[test]
+    fn test_kind_from_hid_usage() {
+        assert_eq!(kind_from_hid_usage(0x01, 0x06), InputDeviceKind::Keyboard);
+        assert_eq!(kind_from_hid_usage(0x01, 0x02), InputDeviceKind::Mouse);
+        assert_eq!(kind_from_hid_usage(0x0d, 0x04), InputDeviceKind::Touch);
+        assert_eq!(kind_from_hid_usage(0x0c, 0x01), InputDeviceKind::Other);
+    }
+}
+
+#[cfg(test)]
+mod pen_tests {
+    use crate::pen::{tilt_degrees, twist_degrees};
+
+    #[test]
+    fn test_tilt_degrees_clamps_normalized_tilt() {
+        assert_eq!(tilt_degrees(0.5), 45.0);
+        assert_eq!(tilt_degrees(-2.0), -90.0);
+    }
+
+    #[test]
+    fn test_twist_degrees_wraps_into_one_turn() {
+        assert_eq!(twist_degrees(370.0), 10.0);
+        assert_eq!(twist_degrees(-90.0), 270.0);
+    }
+}
+
+#[cfg(test)]
+mod gesture_tests {
+    use crate::{gesture::PinchTracker, GesturePhase};
+
+    #[test]
+    fn test_pinch_tracker_turns_cumulative_scale_into_deltas() {
+        let mut tracker = PinchTracker::default();
+        assert_eq!(tracker.delta(GesturePhase::Began, 1.0), 0.0);
+        assert!((tracker.delta(GesturePhase::Changed, 1.1) - 0.1).abs() < 1e-9);
+        assert!((tracker.delta(GesturePhase::Changed, 1.21) - 0.1).abs() < 1e-9);
+        assert!((tracker.delta(GesturePhase::Began, 0.5) + 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_pinch_tracker_ignores_invalid_scale() {
+        let mut tracker = PinchTracker::default();
+        assert_eq!(tracker.delta(GesturePhase::Changed, 0.0), 0.0);
+        assert_eq!(tracker.delta(GesturePhase::Changed, f64::NAN), 0.0);
+    }
+}
+
+#[cfg(test)]
+mod jump_list_tests {
+    use crate::jump_list::{task_argument, task_in_args};
+
+    #[test]
+    fn test_task_argument_quotes_id() {
+        assert_eq!(task_argument("new-window").unwrap(), "\"--jump-list-task=new-window\"");
+        assert!(task_argument("").is_err());
+        assert!(task_argument("a\"b").is_err());
+    }
+
+    #[test]
+    fn test_task_argument_doubles_trailing_backslashes() {
+        assert_eq!(task_argument("a\\b").unwrap(), "\"--jump-list-task=a\\b\"");
+        assert_eq!(task_argument("dir\\").unwrap(), "\"--jump-list-task=dir\\\\\"");
+    }
+
+    #[test]
+    fn test_task_in_args_finds_id() {
+        let args = vec!["--verbose".to_string(), "--jump-list-task=open recent".to_string()];
+        assert_eq!(task_in_args(args), Some("open recent".to_string()));
+        assert_eq!(task_in_args(vec!["--jump-list-task=".to_string()]), None);
+        assert_eq!(task_in_args(Vec::new()), None);
+    }
+}
+
+#[cfg(all(test, not(any(target_os = "android", target_os = "ios"))))]
+mod tray_tests {
+    use crate::tray::check_rgba_frame;
+
+    #[test]
+    fn test_check_rgba_frame_requires_matching_length() {
+        assert!(check_rgba_frame(&[0; 16], 2, 2).is_ok());
+        assert!(check_rgba_frame(&[0; 15], 2, 2).is_err());
+        assert!(check_rgba_frame(&[], 0, 0).is_err());
+    }
+}
+
+#[cfg(all(test, not(any(target_os = "android", target_os = "ios"))))]
+mod shortcut_tests {
+    use crate::ShortcutState;
+
+    #[test]
+    fn test_shortcut_state_from_hotkey_state() {
+        assert_eq!(ShortcutState::from(global_hotkey::HotKeyState::Pressed), ShortcutState::Pressed);
+        assert_eq!(ShortcutState::from(global_hotkey::HotKeyState::Released), ShortcutState::Released);
+    }
+}
+
+#[cfg(test)]
+mod accelerator_tests {
+    use crate::{Accelerator, KeyCode, ModifiersState};
+
+    fn modifiers(shift: bool, control: bool, alt: bool, super_key: bool) -> ModifiersState {
+        ModifiersState {
+            shift,
+            control,
+            alt,
+            super_key,
+        }
+    }
+
+    #[test]
+    fn test_cmd_or_ctrl_depends_on_platform() {
+        let mac = Accelerator::parse_for("CmdOrCtrl+Shift+P", true).unwrap();
+        assert_eq!(mac.modifiers(), modifiers(true, false, false, true));
+        let other = Accelerator::parse_for("cmdorctrl+shift+p", false).unwrap();
+        assert_eq!(other.modifiers(), modifiers(true, true, false, false));
+        assert_eq!(other.key_code(), "KeyP");
+    }
+
+    #[test]
+    fn test_parse_key_names() {
+        let code = |text: &str| Accelerator::parse_for(text, false).map(|a| a.key_code()).ok();
+        assert_eq!(code("Alt+F4").as_deref(), Some("F4"));
+        assert_eq!(code("Ctrl+1").as_deref(), Some("Digit1"));
+        assert_eq!(code("Ctrl+KeyZ").as_deref(), Some("KeyZ"));
+        assert_eq!(code("Shift+Up").as_deref(), Some("ArrowUp"));
+        assert_eq!(code("Ctrl+,").as_deref(), Some("Comma"));
+        assert_eq!(code("Ctrl++").as_deref(), Some("Equal"));
+        assert_eq!(code("F25"), None);
+        assert_eq!(code("Ctrl+"), None);
+        assert_eq!(code("Hyper+A"), None);
+    }
+
+    #[test]
+    fn test_display_round_trips() {
+        let accelerator = Accelerator::parse_for("Option+Cmd+Enter", true).unwrap();
+        assert_eq!(accelerator.to_string(), "Alt+Super+Enter");
+        assert_eq!(Accelerator::parse_for(&accelerator.to_string(), false).unwrap(), accelerator);
+    }
+
+    #[test]
+    fn test_labels() {
+        let accelerator = Accelerator::parse_for("Ctrl+Alt+Shift+Cmd+Left", true).unwrap();
+        assert_eq!(accelerator.label_for(true), "⌃⌥⇧⌘←");
+        assert_eq!(Accelerator::parse_for("Ctrl+Shift+P", false).unwrap().label_for(false), "Ctrl+Shift+P");
+    }
+
+    #[test]
+    fn test_matches_requires_exact_modifiers() {
+        let accelerator = Accelerator::parse_for("Ctrl+V", false).unwrap();
+        assert!(accelerator.matches(KeyCode::KeyV, modifiers(false, true, false, false)));
+        assert!(!accelerator.matches(KeyCode::KeyV, modifiers(true, true, false, false)));
+        let other = KeyCode::Other {
+            value: "KeyV".to_string(),
+        };
+        assert!(accelerator.matches(other, modifiers(false, true, false, false)));
+    }
+}
+
+#[cfg(all(test, not(any(target_os = "android", target_os = "ios"))))]
+mod dialog_tests {
+    use crate::{MessageBoxResult, MessageButtons};
+
+    #[test]
+    fn test_custom_buttons_keep_labels() {
+        let buttons = MessageButtons::YesNoCancelCustom {
+            yes: "Save".to_string(),
+            no: "Don't Save".to_string(),
+            cancel: "Cancel".to_string(),
+        };
+        assert!(matches!(
+            rfd::MessageButtons::from(buttons),
+            rfd::MessageButtons::YesNoCancelCustom(yes, no, _) if yes == "Save" && no == "Don't Save"
+        ));
+        assert_eq!(
+            MessageBoxResult::from(rfd::MessageDialogResult::Custom("Save".to_string())),
+            MessageBoxResult::Custom {
+                label: "Save".to_string()
+            }
+        );
+    }
+}
+
+#[cfg(test)]
+mod notification_tests {
+    use crate::{notification::action_id, NotificationAction};
+
+    #[test]
+    fn test_action_id_matches_id_or_label() {
+        let actions = vec![
+            NotificationAction {
+                id: "reply".to_string(),
+                label: "Reply".to_string(),
+            },
+            NotificationAction {
+                id: "mute".to_string(),
+                label: "Mute".to_string(),
+            },
+        ];
+        assert_eq!(action_id(&actions, "mute").as_deref(), Some("mute"));
+        assert_eq!(action_id(&actions, "Reply").as_deref(), Some("reply"));
+        assert_eq!(action_id(&actions, "default"), None);
+    }
+}
+
+#[cfg(all(test, not(any(target_os = "android", target_os = "ios"))))]
+mod drag_source_tests {
+    use crate::{drag_source::drag_image_png, Icon};
+
+    #[test]
+    fn test_drag_image_png_encodes_icon() {
+        let icon = Icon::from_rgba(vec![255; 2 * 3 * 4], 2, 3).unwrap();
+        let png = drag_image_png(Some(&icon)).unwrap();
+        let decoded = image::load_from_memory(&png).unwrap();
+        assert_eq!((decoded.width(), decoded.height()), (2, 3));
+    }
+
+    #[test]
+    fn test_drag_image_png_defaults_to_transparent_pixel() {
+        let decoded = image::load_from_memory(&drag_image_png(None).unwrap()).unwrap().to_rgba8();
+        assert_eq!(decoded.as_raw(), &vec![0; 4]);
+    }
+}
+
+#[cfg(test)]
+mod inhibit_tests {
+    use crate::{inhibit::portal_flags, SleepInhibition};
+
+    #[test]
+    fn test_display_inhibition_also_blocks_suspend() {
+        assert_eq!(portal_flags(SleepInhibition::System), 4);
+        assert_eq!(portal_flags(SleepInhibition::Display), 12);
+    }
+}
+
+#[cfg(test)]
+mod idle_tests {
+    use crate::idle::IdleTracker;
+
+    #[test]
+    fn test_idle_tracker_reports_crossings_once() {
+        let mut tracker = IdleTracker::new(60);
+        assert_eq!(tracker.update(10.0), None);
+        assert_eq!(tracker.update(60.0), Some(true));
+        assert_eq!(tracker.update(120.0), None);
+        assert_eq!(tracker.update(0.5), Some(false));
+        assert_eq!(tracker.update(1.5), None);
+    }
+}
+
+#[cfg(test)]
+mod accessibility_tests {
+    use crate::{accessibility::update, AccessibilityPreferences};
+
+    #[test]
+    fn test_first_reading_sets_baseline_only() {
+        let mut last = None;
+        let reduced = AccessibilityPreferences {
+            reduce_motion: true,
+            ..Default::default()
+        };
+        assert_eq!(update(&mut last, reduced), None);
+        assert_eq!(update(&mut last, reduced), None);
+        assert_eq!(update(&mut last, AccessibilityPreferences::default()), Some(AccessibilityPreferences::default()));
+    }
+}
+
+#[cfg(test)]
+mod insets_tests {
+    use crate::{PhysicalInsets, WindowInsets};
+
+    fn insets(left: u32, top: u32, right: u32, bottom: u32) -> PhysicalInsets {
+        PhysicalInsets {
+            left,
+            top,
+            right,
+            bottom,
+        }
+    }
+
+    #[test]
+    fn test_safe_area_combines_bars_and_cutout_but_not_ime() {
+        let window = WindowInsets::new(
+            insets(0, 63, 0, 0),
+            insets(0, 0, 0, 48),
+            insets(0, 80, 0, 0),
+            insets(0, 0, 0, 900),
+        );
+        assert_eq!(window.safe_area, insets(0, 80, 0, 48));
+        assert_eq!(window.ime.bottom, 900);
+    }
+}
+
+#[cfg(test)]
+mod color_scheme_tests {
+    use crate::color_scheme::theme_from_color_scheme;
+    use crate::Theme;
+
+    #[test]
+    fn test_portal_color_scheme_maps_to_theme() {
+        assert_eq!(theme_from_color_scheme(0), None);
+        assert_eq!(theme_from_color_scheme(1), Some(Theme::Dark));
+        assert_eq!(theme_from_color_scheme(2), Some(Theme::Light));
+        assert_eq!(theme_from_color_scheme(7), None);
+    }
+}
+
+#[cfg(test)]
+mod ipc_tests {
+    use crate::ipc::is_valid_channel;
+
+    #[test]
+    fn test_channel_names_are_safe_for_paths() {
+        assert!(is_valid_channel("com.example.app"));
+        assert!(is_valid_channel("my_app-2"));
+        assert!(!is_valid_channel(""));
+        assert!(!is_valid_channel(".hidden"));
+        assert!(!is_valid_channel("../escape"));
+        assert!(!is_valid_channel(&"a".repeat(65)));
+    }
+}
+
+#[cfg(all(test, target_os = "macos"))]
+mod display_link_tests {
+    use crate::display_link::host_time_to_nanos;
+
+    #[test]
+    fn test_host_time_converts_with_timebase() {
+        assert_eq!(host_time_to_nanos(1_000, (1, 1)), 1_000);
+        // Apple silicon: 125/3 ns per tick.
+        assert_eq!(host_time_to_nanos(24_000_000, (125, 3)), 1_000_000_000);
+    }
+}
@@ -0,0 +1,178 @@
+//! Naming and prioritizing the thread that drives the event loop, and values tied to a thread.
+
+use std::{mem::ManuallyDrop, thread::ThreadId};
+
+use crate::TaoError;
+
+/// Scheduling priority hint for the event loop thread.
+///
+/// Raising the priority may require extra privileges; failures are ignored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum ThreadPriority {
+    Low,
+    Normal,
+    High,
+    Highest,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct ThreadConfig {
+    /// Thread name shown by debuggers and profilers. Truncated to 15 bytes on Linux.
+    #[uniffi(default = None)]
+    pub name: Option<String>,
+    #[uniffi(default = None)]
+    pub priority: Option<ThreadPriority>,
+}
+
+/// Applies `config` to the calling thread on a best-effort basis.
+pub(crate) fn configure_current_thread(config: &ThreadConfig) {
+    if let Some(name) = &config.name {
+        set_current_thread_name(name);
+    }
+    if let Some(priority) = config.priority {
+        set_current_thread_priority(priority);
+    }
+}
+
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "android"
+))]
+fn set_current_thread_name(name: &str) {
+    let mut end = name.len().min(15);
+    while !name.is_char_boundary(end) {
+        end -= 1;
+    }
+    if let Ok(name) = std::ffi::CString::new(&name[..end]) {
+        unsafe {
+            libc::pthread_setname_np(libc::pthread_self(), name.as_ptr());
+        }
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+fn set_current_thread_name(name: &str) {
+    if let Ok(name) = std::ffi::CString::new(name) {
+        unsafe {
+            libc::pthread_setname_np(name.as_ptr());
+        }
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn set_current_thread_name(name: &str) {
+    use windows_sys::Win32::System::Threading::{GetCurrentThread, SetThreadDescription};
+    let wide: Vec<u16> = name.encode_utf16().chain(std::iter::once(0)).collect();
+    unsafe {
+        SetThreadDescription(GetCurrentThread(), wide.as_ptr());
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "android"))]
+fn set_current_thread_priority(priority: ThreadPriority) {
+    let nice = match priority {
+        ThreadPriority::Low => 10,
+        ThreadPriority::Normal => 0,
+        ThreadPriority::High => -5,
+        ThreadPriority::Highest => -10,
+    };
+    unsafe {
+        // On Linux the nice value of a thread id only affects that thread.
+        libc::setpriority(libc::PRIO_PROCESS, libc::gettid() as libc::id_t, nice);
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "ios"))]
+fn set_current_thread_priority(priority: ThreadPriority) {
+    use libc::qos_class_t;
+    let class = match priority {
+        ThreadPriority::Low => qos_class_t::QOS_CLASS_UTILITY,
+        ThreadPriority::Normal => qos_class_t::QOS_CLASS_DEFAULT,
+        ThreadPriority::High => qos_class_t::QOS_CLASS_USER_INITIATED,
+        ThreadPriority::Highest => qos_class_t::QOS_CLASS_USER_INTERACTIVE,
+    };
+    unsafe {
+        libc::pthread_set_qos_class_self_np(class, 0);
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn set_current_thread_priority(priority: ThreadPriority) {
+    use windows_sys::Win32::System::Threading::{
+        GetCurrentThread, SetThreadPriority, THREAD_PRIORITY_ABOVE_NORMAL, THREAD_PRIORITY_BELOW_NORMAL,
+        THREAD_PRIORITY_HIGHEST, THREAD_PRIORITY_NORMAL,
+    };
+    let level = match priority {
+        ThreadPriority::Low => THREAD_PRIORITY_BELOW_NORMAL,
+        ThreadPriority::Normal => THREAD_PRIORITY_NORMAL,
+        ThreadPriority::High => THREAD_PRIORITY_ABOVE_NORMAL,
+        ThreadPriority::Highest => THREAD_PRIORITY_HIGHEST,
+    };
+    unsafe {
+        SetThreadPriority(GetCurrentThread(), level);
+    }
+}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "windows"
+)))]
+fn set_current_thread_name(_name: &str) {}
+
+#[cfg(not(any(
+    target_os = "linux",
+    target_os = "android",
+    target_os = "macos",
+    target_os = "ios",
+    target_os = "windows"
+)))]
+fn set_current_thread_priority(_priority: ThreadPriority) {}
+
+/// A value that may only be used on the thread that created it.
+///
+/// Dropping it on another thread (e.g. from a Kotlin cleaner) leaks the value instead of
+/// touching it from the wrong thread.
+pub(crate) struct ThreadBound<T> {
+    value: ManuallyDrop<T>,
+    thread: ThreadId,
+}
+
+// Safety: the value is only accessed, and only dropped, on the thread that created it.
+unsafe impl<T> Send for ThreadBound<T> {}
+unsafe impl<T> Sync for ThreadBound<T> {}
+
+impl<T> ThreadBound<T> {
+    pub(crate) fn new(value: T) -> Self {
+        Self {
+            value: ManuallyDrop::new(value),
+            thread: std::thread::current().id(),
+        }
+    }
+
+    pub(crate) fn get(&self) -> Result<&T, TaoError> {
+        if std::thread::current().id() != self.thread {
+            return Err(TaoError::message("This object can only be used on the thread that created it"));
+        }
+        Ok(&self.value)
+    }
+}
+
+impl<T> Drop for ThreadBound<T> {
+    fn drop(&mut self) {
+        if std::thread::current().id() == self.thread {
+            // Safety: not used again after drop.
+            unsafe { ManuallyDrop::drop(&mut self.value) };
+        }
+    }
+}
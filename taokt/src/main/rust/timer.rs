@@ -0,0 +1,76 @@
+use std::time::{Duration, Instant};
+
+struct TimerEntry {
+    id: u64,
+    deadline: Instant,
+    interval: Duration,
+    repeating: bool,
+}
+
+/// Timers scheduled with `App::set_timer`, fired from the event loop.
+pub(crate) struct Timers {
+    next_id: u64,
+    entries: Vec<TimerEntry>,
+}
+
+impl Timers {
+    pub(crate) fn new() -> Self {
+        Self {
+            next_id: 1,
+            entries: Vec::new(),
+        }
+    }
+
+    pub(crate) fn add(&mut self, interval: Duration, repeating: bool, now: Instant) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        // A zero interval would make a repeating timer spin the loop.
+        let interval = interval.max(Duration::from_millis(1));
+        self.entries.push(TimerEntry {
+            id,
+            deadline: now + interval,
+            interval,
+            repeating,
+        });
+        id
+    }
+
+    pub(crate) fn cancel(&mut self, id: u64) -> bool {
+        let before = self.entries.len();
+        self.entries.retain(|entry| entry.id != id);
+        self.entries.len() != before
+    }
+
+    /// Returns the ids of expired timers ordered by deadline and reschedules repeating ones.
+    ///
+    /// A repeating timer fires at most once per call; missed ticks are not made up for.
+    pub(crate) fn take_due(&mut self, now: Instant) -> Vec<u64> {
+        let mut due: Vec<(Instant, u64)> = self
+            .entries
+            .iter()
+            .filter(|entry| entry.deadline <= now)
+            .map(|entry| (entry.deadline, entry.id))
+            .collect();
+        due.sort();
+
+        self.entries.retain_mut(|entry| {
+            if entry.deadline > now {
+                return true;
+            }
+            if !entry.repeating {
+                return false;
+            }
+            entry.deadline += entry.interval;
+            if entry.deadline <= now {
+                entry.deadline = now + entry.interval;
+            }
+            true
+        });
+
+        due.into_iter().map(|(_, id)| id).collect()
+    }
+
+    pub(crate) fn next_deadline(&self) -> Option<Instant> {
+        self.entries.iter().map(|entry| entry.deadline).min()
+    }
+}
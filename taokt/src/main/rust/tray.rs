@@ -0,0 +1,135 @@
+//! System tray (notification area, menu bar extra, StatusNotifierItem) icons backed by tray-icon.
+//!
+//! Like menus, tray icons belong to the event loop thread. Every property can be changed while
+//! the icon is shown; animate it by swapping frames from an `App::set_timer` callback.
+
+use std::sync::Arc;
+
+use crate::{thread::ThreadBound, Icon, Menu, TaoError};
+
+impl From<tray_icon::Error> for TaoError {
+    fn from(value: tray_icon::Error) -> Self {
+        TaoError::message(value.to_string())
+    }
+}
+
+/// Checks that `rgba` holds exactly `width * height` pixels.
+pub(crate) fn check_rgba_frame(rgba: &[u8], width: u32, height: u32) -> Result<(), TaoError> {
+    let expected = width as usize * height as usize * 4;
+    if width == 0 || height == 0 || rgba.len() != expected {
+        return Err(TaoError::message(format!(
+            "Expected {expected} bytes for a {width}x{height} RGBA frame, got {}",
+            rgba.len()
+        )));
+    }
+    Ok(())
+}
+
+fn tray_icon(icon: Option<Arc<Icon>>) -> Result<Option<tray_icon::Icon>, TaoError> {
+    icon.map(|icon| {
+        let (rgba, width, height) = icon
+            .rgba()
+            .ok_or_else(|| TaoError::message("Icons loaded from native resources cannot be used in the tray"))?;
+        tray_icon::Icon::from_rgba(rgba, width, height).map_err(|e| TaoError::message(e.to_string()))
+    })
+    .transpose()
+}
+
+/// Whether the desktop shows tray icons. On Linux that needs a StatusNotifierItem host (KDE, or
+/// the AppIndicator extension on GNOME); without one, fall back to e.g. a window or notification.
+#[uniffi::export]
+pub fn tray_available() -> bool {
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    return crate::dbus::name_has_owner("org.kde.StatusNotifierWatcher");
+
+    #[allow(unreachable_code)]
+    true
+}
+
+#[derive(uniffi::Object)]
+pub struct TrayIcon {
+    inner: ThreadBound<tray_icon::TrayIcon>,
+}
+
+#[uniffi::export]
+impl TrayIcon {
+    /// Adds an icon to the tray. Call on the event loop thread after the loop has started.
+    ///
+    /// Items of `menu` report activations as `TaoEvent::MenuEvent`.
+    #[uniffi::constructor]
+    pub fn new(
+        id: String,
+        icon: Option<Arc<Icon>>,
+        tooltip: Option<String>,
+        menu: Option<Arc<Menu>>,
+    ) -> Result<Self, TaoError> {
+        let mut builder = tray_icon::TrayIconBuilder::new().with_id(id);
+        if let Some(icon) = tray_icon(icon)? {
+            builder = builder.with_icon(icon);
+        }
+        if let Some(tooltip) = tooltip {
+            builder = builder.with_tooltip(tooltip);
+        }
+        if let Some(menu) = menu {
+            builder = builder.with_menu(Box::new(menu.inner.get()?.clone()));
+        }
+        Ok(Self {
+            inner: ThreadBound::new(builder.build()?),
+        })
+    }
+
+    pub fn set_icon(&self, icon: Option<Arc<Icon>>) -> Result<(), TaoError> {
+        Ok(self.inner.get()?.set_icon(tray_icon(icon)?)?)
+    }
+
+    /// Replaces the icon with raw RGBA pixels, skipping the `Icon` object for per-frame updates.
+    pub fn set_icon_rgba(&self, rgba: Vec<u8>, width: u32, height: u32) -> Result<(), TaoError> {
+        check_rgba_frame(&rgba, width, height)?;
+        let icon = tray_icon::Icon::from_rgba(rgba, width, height).map_err(|e| TaoError::message(e.to_string()))?;
+        Ok(self.inner.get()?.set_icon(Some(icon))?)
+    }
+
+    /// Renders the icon as a template image that follows the menu bar appearance (macOS only).
+    pub fn set_icon_as_template(&self, is_template: bool) -> Result<(), TaoError> {
+        #[cfg(target_os = "macos")]
+        {
+            self.inner.get()?.set_icon_as_template(is_template);
+            return Ok(());
+        }
+
+        #[allow(unreachable_code)]
+        {
+            let _ = is_template;
+            Err(TaoError::Unsupported)
+        }
+    }
+
+    pub fn set_tooltip(&self, tooltip: Option<String>) -> Result<(), TaoError> {
+        Ok(self.inner.get()?.set_tooltip(tooltip)?)
+    }
+
+    /// Text shown next to the icon (macOS menu bar, and Linux panels that support it).
+    pub fn set_title(&self, title: Option<String>) -> Result<(), TaoError> {
+        self.inner.get()?.set_title(title);
+        Ok(())
+    }
+
+    pub fn set_visible(&self, visible: bool) -> Result<(), TaoError> {
+        Ok(self.inner.get()?.set_visible(visible)?)
+    }
+
+    pub fn set_menu(&self, menu: Option<Arc<Menu>>) -> Result<(), TaoError> {
+        let menu = match menu {
+            Some(menu) => Some(Box::new(menu.inner.get()?.clone()) as Box<dyn tray_icon::menu::ContextMenu>),
+            None => None,
+        };
+        self.inner.get()?.set_menu(menu);
+        Ok(())
+    }
+}
@@ -1,7 +1,10 @@
 use std::time::Duration;
 
 use tao::{
-    dpi::{LogicalSize as TaoLogicalSize, PhysicalPosition as TaoPhysicalPosition, PhysicalSize as TaoPhysicalSize},
+    dpi::{
+        LogicalPosition as TaoLogicalPosition, LogicalSize as TaoLogicalSize, PhysicalPosition as TaoPhysicalPosition,
+        PhysicalSize as TaoPhysicalSize,
+    },
     event::ElementState as TaoElementState,
     event_loop::DeviceEventFilter as TaoDeviceEventFilter,
 };
@@ -107,6 +110,24 @@ impl From<LogicalSize> for TaoLogicalSize<f64> {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, uniffi::Record)]
+pub struct LogicalPosition {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl From<LogicalPosition> for TaoLogicalPosition<f64> {
+    fn from(value: LogicalPosition) -> Self {
+        TaoLogicalPosition::new(value.x, value.y)
+    }
+}
+
+impl From<TaoLogicalPosition<f64>> for LogicalPosition {
+    fn from(value: TaoLogicalPosition<f64>) -> Self {
+        Self { x: value.x, y: value.y }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Record)]
 pub struct PhysicalSizeU32 {
     pub width: u32,
@@ -173,6 +194,8 @@ pub enum ControlFlow {
     Exit,
     /// Wait until `now + duration_ms`.
     WaitUntil { duration_ms: u64 },
+    /// Exit the loop, reporting `code` to the caller (or as the process exit code for `run`).
+    ExitWithCode { code: i32 },
 }
 
 impl ControlFlow {
@@ -185,6 +208,7 @@ impl ControlFlow {
             ControlFlow::WaitUntil { duration_ms } => Some(tao::event_loop::ControlFlow::WaitUntil(
                 std::time::Instant::now() + Duration::from_millis(duration_ms),
             )),
+            ControlFlow::ExitWithCode { code } => Some(tao::event_loop::ControlFlow::ExitWithCode(code)),
         }
     }
 }
@@ -195,6 +219,7 @@ pub enum Key {
     ArrowLeft,
     ArrowRight,
     Character { value: String },
+    /// Any other key, by its tao name (`"Enter"`, `"Shift"`); not interned, see `describe_other`.
     Other { value: String },
 }
 
@@ -223,6 +248,8 @@ pub enum KeyCode {
     KeyL,
     KeyM,
     KeyV,
+    /// Any other key, by its tao name (`"Backspace"`, `"F1"`), as accelerators and synthesized input
+    /// use it; not interned, see `describe_other`.
     Other { value: String },
 }
 
@@ -258,6 +285,27 @@ impl From<KeyCode> for tao::keyboard::KeyCode {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum KeyLocation {
+    Standard,
+    Left,
+    Right,
+    Numpad,
+}
+
+impl From<tao::keyboard::KeyLocation> for KeyLocation {
+    fn from(value: tao::keyboard::KeyLocation) -> Self {
+        use tao::keyboard::KeyLocation as TaoKeyLocation;
+        match value {
+            TaoKeyLocation::Standard => KeyLocation::Standard,
+            TaoKeyLocation::Left => KeyLocation::Left,
+            TaoKeyLocation::Right => KeyLocation::Right,
+            TaoKeyLocation::Numpad => KeyLocation::Numpad,
+            _ => KeyLocation::Standard,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Record)]
 pub struct ModifiersState {
     pub shift: bool,
@@ -282,9 +330,37 @@ pub enum MouseButton {
     Left,
     Right,
     Middle,
+    /// The X1 ("back") side button.
+    Back,
+    /// The X2 ("forward") side button.
+    Forward,
     Other { value: u16 },
 }
 
+/// Native `MouseButton::Other` numbers tao reports for the back and forward buttons.
+#[cfg(target_os = "windows")]
+const NATIVE_BACK_FORWARD: Option<(u16, u16)> = Some((1, 2));
+#[cfg(target_os = "macos")]
+const NATIVE_BACK_FORWARD: Option<(u16, u16)> = Some((3, 4));
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+const NATIVE_BACK_FORWARD: Option<(u16, u16)> = Some((8, 9));
+#[cfg(not(any(
+    target_os = "windows",
+    target_os = "macos",
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+)))]
+const NATIVE_BACK_FORWARD: Option<(u16, u16)> = None;
+
 impl From<tao::event::MouseButton> for MouseButton {
     fn from(value: tao::event::MouseButton) -> Self {
         use tao::event::MouseButton as TaoMouseButton;
@@ -292,7 +368,11 @@ impl From<tao::event::MouseButton> for MouseButton {
             TaoMouseButton::Left => MouseButton::Left,
             TaoMouseButton::Right => MouseButton::Right,
             TaoMouseButton::Middle => MouseButton::Middle,
-            TaoMouseButton::Other(v) => MouseButton::Other { value: v },
+            TaoMouseButton::Other(v) => match NATIVE_BACK_FORWARD {
+                Some((back, _)) if v == back => MouseButton::Back,
+                Some((_, forward)) if v == forward => MouseButton::Forward,
+                _ => MouseButton::Other { value: v },
+            },
             _ => MouseButton::Other { value: 0 },
         }
     }
@@ -300,10 +380,13 @@ impl From<tao::event::MouseButton> for MouseButton {
 
 impl From<MouseButton> for tao::event::MouseButton {
     fn from(value: MouseButton) -> Self {
+        let (back, forward) = NATIVE_BACK_FORWARD.unwrap_or((u16::MAX - 1, u16::MAX));
         match value {
             MouseButton::Left => tao::event::MouseButton::Left,
             MouseButton::Right => tao::event::MouseButton::Right,
             MouseButton::Middle => tao::event::MouseButton::Middle,
+            MouseButton::Back => tao::event::MouseButton::Other(back),
+            MouseButton::Forward => tao::event::MouseButton::Other(forward),
             MouseButton::Other { value } => tao::event::MouseButton::Other(value),
         }
     }
@@ -313,7 +396,8 @@ impl From<MouseButton> for tao::event::MouseButton {
 pub enum MouseScrollDelta {
     LineDelta { x: f32, y: f32 },
     PixelDelta { x: f64, y: f64 },
-    Other { value: String },
+    /// A variant without a dedicated mapping; see `describe_other`.
+    Other { code: u32 },
 }
 
 impl From<tao::event::MouseScrollDelta> for MouseScrollDelta {
@@ -322,7 +406,7 @@ impl From<tao::event::MouseScrollDelta> for MouseScrollDelta {
             tao::event::MouseScrollDelta::LineDelta(x, y) => MouseScrollDelta::LineDelta { x, y },
             tao::event::MouseScrollDelta::PixelDelta(p) => MouseScrollDelta::PixelDelta { x: p.x, y: p.y },
             other => MouseScrollDelta::Other {
-                value: format!("{other:?}"),
+                code: crate::intern_variant(&other),
             },
         }
     }
@@ -435,6 +519,54 @@ impl From<CursorIcon> for tao::window::CursorIcon {
     }
 }
 
+/// How a macOS application appears in the Dock and app switcher.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum ActivationPolicy {
+    /// A normal application with a Dock icon and a menu bar.
+    Regular,
+    /// No Dock icon or menu bar, but windows can be activated, e.g. a menu bar extra.
+    Accessory,
+    /// No Dock icon and no windows of its own, e.g. a background agent.
+    Prohibited,
+}
+
+#[cfg(target_os = "macos")]
+impl From<ActivationPolicy> for tao::platform::macos::ActivationPolicy {
+    fn from(value: ActivationPolicy) -> Self {
+        match value {
+            ActivationPolicy::Regular => tao::platform::macos::ActivationPolicy::Regular,
+            ActivationPolicy::Accessory => tao::platform::macos::ActivationPolicy::Accessory,
+            ActivationPolicy::Prohibited => tao::platform::macos::ActivationPolicy::Prohibited,
+        }
+    }
+}
+
+/// Color of the iOS status bar text and icons.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum StatusBarStyle {
+    /// Chosen by the system from the interface style.
+    Default,
+    /// Light content, for dark backgrounds.
+    Light,
+    /// Dark content, for light backgrounds.
+    Dark,
+}
+
+/// Orientations a mobile window may rotate to; see `Window::set_orientation_lock`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
+pub enum Orientation {
+    /// Follow the device and the user's rotation setting.
+    Unlocked,
+    Portrait,
+    Landscape,
+    /// Portrait, either way up.
+    AnyPortrait,
+    /// Landscape, turned either way.
+    AnyLandscape,
+    /// Stay in the current orientation.
+    Current,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Enum)]
 pub enum ProgressState {
     None,
@@ -473,6 +605,227 @@ impl From<ProgressBarState> for tao::window::ProgressBarState {
     }
 }
 
+/// A size in either unit; window APIs accept both and leave the conversion to tao.
+#[derive(Debug, Clone, Copy, PartialEq, uniffi::Enum)]
+pub enum Size {
+    Logical { width: f64, height: f64 },
+    Physical { width: u32, height: u32 },
+}
+
+impl From<Size> for tao::dpi::Size {
+    fn from(value: Size) -> Self {
+        match value {
+            Size::Logical { width, height } => TaoLogicalSize::new(width, height).into(),
+            Size::Physical { width, height } => TaoPhysicalSize::new(width, height).into(),
+        }
+    }
+}
+
+impl From<LogicalSize> for Size {
+    fn from(value: LogicalSize) -> Self {
+        Size::Logical {
+            width: value.width,
+            height: value.height,
+        }
+    }
+}
+
+impl From<PhysicalSizeU32> for Size {
+    fn from(value: PhysicalSizeU32) -> Self {
+        Size::Physical {
+            width: value.width,
+            height: value.height,
+        }
+    }
+}
+
+/// A position in either unit; window APIs accept both and leave the conversion to tao.
+#[derive(Debug, Clone, Copy, PartialEq, uniffi::Enum)]
+pub enum Position {
+    Logical { x: f64, y: f64 },
+    Physical { x: i32, y: i32 },
+}
+
+impl From<Position> for tao::dpi::Position {
+    fn from(value: Position) -> Self {
+        match value {
+            Position::Logical { x, y } => TaoLogicalPosition::new(x, y).into(),
+            Position::Physical { x, y } => TaoPhysicalPosition::new(x, y).into(),
+        }
+    }
+}
+
+impl From<LogicalPosition> for Position {
+    fn from(value: LogicalPosition) -> Self {
+        Position::Logical { x: value.x, y: value.y }
+    }
+}
+
+impl From<PhysicalPositionI32> for Position {
+    fn from(value: PhysicalPositionI32) -> Self {
+        Position::Physical { x: value.x, y: value.y }
+    }
+}
+
+fn check_scale_factor(scale_factor: f64) -> Result<(), TaoError> {
+    if tao::dpi::validate_scale_factor(scale_factor) {
+        Ok(())
+    } else {
+        Err(TaoError::message(format!("Invalid scale factor {scale_factor}")))
+    }
+}
+
+/// Converts with tao's rounding: to physical rounds to the nearest pixel, to logical divides exactly.
+#[uniffi::export]
+pub fn logical_to_physical_size(size: LogicalSize, scale_factor: f64) -> Result<PhysicalSizeU32, TaoError> {
+    check_scale_factor(scale_factor)?;
+    Ok(TaoLogicalSize::<f64>::from(size).to_physical::<u32>(scale_factor).into())
+}
+
+#[uniffi::export]
+pub fn physical_to_logical_size(size: PhysicalSizeU32, scale_factor: f64) -> Result<LogicalSize, TaoError> {
+    check_scale_factor(scale_factor)?;
+    let size = TaoPhysicalSize::<u32>::from(size).to_logical::<f64>(scale_factor);
+    Ok(LogicalSize {
+        width: size.width,
+        height: size.height,
+    })
+}
+
+#[uniffi::export]
+pub fn logical_to_physical_position(
+    position: LogicalPosition,
+    scale_factor: f64,
+) -> Result<PhysicalPositionI32, TaoError> {
+    check_scale_factor(scale_factor)?;
+    Ok(TaoLogicalPosition::<f64>::from(position).to_physical::<i32>(scale_factor).into())
+}
+
+#[uniffi::export]
+pub fn physical_to_logical_position(
+    position: PhysicalPositionI32,
+    scale_factor: f64,
+) -> Result<LogicalPosition, TaoError> {
+    check_scale_factor(scale_factor)?;
+    Ok(TaoPhysicalPosition::<i32>::from(position).to_logical::<f64>(scale_factor).into())
+}
+
+#[uniffi::export]
+impl LogicalSize {
+    pub fn to_physical(&self, scale_factor: f64) -> Result<PhysicalSizeU32, TaoError> {
+        logical_to_physical_size(*self, scale_factor)
+    }
+}
+
+#[uniffi::export]
+impl PhysicalSizeU32 {
+    pub fn to_logical(&self, scale_factor: f64) -> Result<LogicalSize, TaoError> {
+        physical_to_logical_size(*self, scale_factor)
+    }
+}
+
+#[uniffi::export]
+impl LogicalPosition {
+    pub fn to_physical(&self, scale_factor: f64) -> Result<PhysicalPositionI32, TaoError> {
+        logical_to_physical_position(*self, scale_factor)
+    }
+}
+
+#[uniffi::export]
+impl PhysicalPositionI32 {
+    pub fn to_logical(&self, scale_factor: f64) -> Result<LogicalPosition, TaoError> {
+        physical_to_logical_position(*self, scale_factor)
+    }
+}
+
+/// A rectangle in physical pixels, relative to the window's client area.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, uniffi::Record)]
+pub struct PhysicalRect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl PhysicalRect {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    pub(crate) fn contains(&self, other: &PhysicalRect) -> bool {
+        other.x >= self.x
+            && other.y >= self.y
+            && other.x as i64 + other.width as i64 <= self.x as i64 + self.width as i64
+            && other.y as i64 + other.height as i64 <= self.y as i64 + self.height as i64
+    }
+
+    /// Smallest rectangle covering both.
+    pub(crate) fn union(&self, other: &PhysicalRect) -> PhysicalRect {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        let right = (self.x as i64 + self.width as i64).max(other.x as i64 + other.width as i64);
+        let bottom = (self.y as i64 + self.height as i64).max(other.y as i64 + other.height as i64);
+        PhysicalRect {
+            x,
+            y,
+            width: (right - x as i64) as u32,
+            height: (bottom - y as i64) as u32,
+        }
+    }
+}
+
+/// Distances from each edge of the window in physical pixels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, uniffi::Record)]
+pub struct PhysicalInsets {
+    pub left: u32,
+    pub top: u32,
+    pub right: u32,
+    pub bottom: u32,
+}
+
+impl PhysicalInsets {
+    /// The larger inset on each edge.
+    #[cfg_attr(not(target_os = "android"), allow(dead_code))]
+    pub(crate) fn max(&self, other: &PhysicalInsets) -> PhysicalInsets {
+        PhysicalInsets {
+            left: self.left.max(other.left),
+            top: self.top.max(other.top),
+            right: self.right.max(other.right),
+            bottom: self.bottom.max(other.bottom),
+        }
+    }
+}
+
+/// Parts of the window covered by system UI (Android).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, uniffi::Record)]
+pub struct WindowInsets {
+    pub status_bar: PhysicalInsets,
+    pub navigation_bar: PhysicalInsets,
+    pub display_cutout: PhysicalInsets,
+    /// The on-screen keyboard; `bottom` is its height while shown.
+    pub ime: PhysicalInsets,
+    /// Status bar, navigation bar and cutout combined: keep content and touch targets inside.
+    pub safe_area: PhysicalInsets,
+}
+
+impl WindowInsets {
+    #[cfg_attr(not(target_os = "android"), allow(dead_code))]
+    pub(crate) fn new(
+        status_bar: PhysicalInsets,
+        navigation_bar: PhysicalInsets,
+        display_cutout: PhysicalInsets,
+        ime: PhysicalInsets,
+    ) -> Self {
+        Self {
+            status_bar,
+            navigation_bar,
+            display_cutout,
+            ime,
+            safe_area: status_bar.max(&navigation_bar).max(&display_cutout),
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, uniffi::Record)]
 pub struct WindowSizeConstraints {
     pub min_width: Option<f64>,
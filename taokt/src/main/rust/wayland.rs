@@ -0,0 +1,171 @@
+//! Wayland protocol objects TaoKt binds next to GTK: fractional scaling
+//! (wp_fractional_scale_v1) and frame callbacks.
+//!
+//! GTK 3 exposes neither, so they are bound on GTK's own connection with a private event
+//! queue. GTK reads the socket; pending events are dispatched on demand.
+
+use std::ffi::c_void;
+
+use wayland_backend::client::{Backend, ObjectId};
+use wayland_client::{
+    globals::{registry_queue_init, GlobalListContents},
+    protocol::{wl_callback, wl_registry, wl_surface::WlSurface},
+    Connection, Dispatch, EventQueue, Proxy, QueueHandle,
+};
+use wayland_protocols::wp::fractional_scale::v1::client::{
+    wp_fractional_scale_manager_v1::{self, WpFractionalScaleManagerV1},
+    wp_fractional_scale_v1::{self, WpFractionalScaleV1},
+};
+
+/// Denominator of `preferred_scale` values.
+const SCALE_DENOMINATOR: f64 = 120.0;
+
+#[derive(Default)]
+struct SurfaceState {
+    /// Latest scale sent by the compositor, in 120ths.
+    preferred: Option<u32>,
+    /// Timestamps (milliseconds) of frame callbacks that fired since the last poll.
+    presented: Vec<u32>,
+}
+
+impl Dispatch<wl_registry::WlRegistry, GlobalListContents> for SurfaceState {
+    fn event(
+        _: &mut Self,
+        _: &wl_registry::WlRegistry,
+        _: wl_registry::Event,
+        _: &GlobalListContents,
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<WpFractionalScaleManagerV1, ()> for SurfaceState {
+    fn event(
+        _: &mut Self,
+        _: &WpFractionalScaleManagerV1,
+        _: wp_fractional_scale_manager_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+    }
+}
+
+impl Dispatch<WpFractionalScaleV1, ()> for SurfaceState {
+    fn event(
+        state: &mut Self,
+        _: &WpFractionalScaleV1,
+        event: wp_fractional_scale_v1::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let wp_fractional_scale_v1::Event::PreferredScale { scale } = event {
+            state.preferred = Some(scale);
+        }
+    }
+}
+
+impl Dispatch<wl_callback::WlCallback, ()> for SurfaceState {
+    fn event(
+        state: &mut Self,
+        _: &wl_callback::WlCallback,
+        event: wl_callback::Event,
+        _: &(),
+        _: &Connection,
+        _: &QueueHandle<Self>,
+    ) {
+        if let wl_callback::Event::Done { callback_data } = event {
+            state.presented.push(callback_data);
+        }
+    }
+}
+
+/// Protocol state of one window surface.
+pub(crate) struct WaylandSurface {
+    connection: Connection,
+    queue: EventQueue<SurfaceState>,
+    state: SurfaceState,
+    surface: WlSurface,
+    /// `None` when the compositor lacks wp_fractional_scale_v1.
+    fractional_scale: Option<WpFractionalScaleV1>,
+    /// Scale last returned by `take_scale_change`.
+    reported: Option<u32>,
+}
+
+impl WaylandSurface {
+    /// Binds to `surface` on GTK's connection.
+    ///
+    /// Both pointers must come from the live GTK connection of the calling thread.
+    pub(crate) fn new(display: *mut c_void, surface: *mut c_void) -> Option<Self> {
+        if display.is_null() || surface.is_null() {
+            return None;
+        }
+        let backend = unsafe { Backend::from_foreign_display(display.cast()) };
+        let connection = Connection::from_backend(backend);
+        let (globals, mut queue) = registry_queue_init::<SurfaceState>(&connection).ok()?;
+        let qh = queue.handle();
+
+        let surface_id = unsafe { ObjectId::from_ptr(WlSurface::interface(), surface.cast()) }.ok()?;
+        let surface = WlSurface::from_id(&connection, surface_id).ok()?;
+        let fractional_scale = globals
+            .bind::<WpFractionalScaleManagerV1, _, _>(&qh, 1..=1, ())
+            .ok()
+            .map(|manager| manager.get_fractional_scale(&surface, &qh, ()));
+
+        // The compositor sends the current scale right away.
+        let mut state = SurfaceState::default();
+        queue.roundtrip(&mut state).ok()?;
+        let reported = state.preferred;
+        Some(Self {
+            connection,
+            queue,
+            state,
+            surface,
+            fractional_scale,
+            reported,
+        })
+    }
+
+    fn dispatch(&mut self) {
+        let _ = self.queue.dispatch_pending(&mut self.state);
+    }
+
+    /// The compositor's preferred scale, e.g. 1.25 at 125 %.
+    pub(crate) fn scale(&mut self) -> Option<f64> {
+        self.dispatch();
+        self.state.preferred.map(|scale| scale as f64 / SCALE_DENOMINATOR)
+    }
+
+    /// Returns the preferred scale if it changed since the last call.
+    pub(crate) fn take_scale_change(&mut self) -> Option<f64> {
+        self.dispatch();
+        if self.state.preferred == self.reported {
+            return None;
+        }
+        self.reported = self.state.preferred;
+        self.reported.map(|scale| scale as f64 / SCALE_DENOMINATOR)
+    }
+
+    /// Asks for a callback when the compositor is ready for the frame after the next commit.
+    pub(crate) fn request_frame(&mut self) {
+        let qh = self.queue.handle();
+        self.surface.frame(&qh, ());
+        let _ = self.connection.flush();
+    }
+
+    /// Timestamps of frame callbacks that fired since the last call.
+    pub(crate) fn take_presented(&mut self) -> Vec<u32> {
+        self.dispatch();
+        std::mem::take(&mut self.state.presented)
+    }
+}
+
+impl Drop for WaylandSurface {
+    fn drop(&mut self) {
+        if let Some(fractional_scale) = &self.fractional_scale {
+            fractional_scale.destroy();
+        }
+    }
+}
@@ -0,0 +1,230 @@
+//! wgpu surface creation and a minimal clear-and-present renderer.
+
+use std::sync::{Arc, Mutex};
+
+use crate::{graphics::WindowHandleSource, SurfaceConfig, TaoError, Window};
+
+pub(crate) fn wgpu_error(error: impl std::fmt::Display) -> TaoError {
+    TaoError::message(error.to_string())
+}
+
+/// A configured wgpu surface for a window, with the device and queue that render to it.
+#[derive(uniffi::Object)]
+pub struct WgpuSurface {
+    surface: wgpu::Surface<'static>,
+    adapter: wgpu::Adapter,
+    device: wgpu::Device,
+    queue: wgpu::Queue,
+    config: Mutex<wgpu::SurfaceConfiguration>,
+    surface_config: SurfaceConfig,
+}
+
+impl WgpuSurface {
+    pub(crate) fn new(window: Arc<Window>, surface_config: SurfaceConfig) -> Result<Self, TaoError> {
+        let size = window.inner_size();
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let surface = instance
+            .create_surface(WindowHandleSource(window))
+            .map_err(wgpu_error)?;
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions {
+            compatible_surface: Some(&surface),
+            ..Default::default()
+        }))
+        .map_err(wgpu_error)?;
+        let (device, queue) =
+            pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default())).map_err(wgpu_error)?;
+
+        let mut config = surface
+            .get_default_config(&adapter, size.width.max(1), size.height.max(1))
+            .ok_or_else(|| TaoError::message("Surface is not supported by the adapter"))?;
+        let capabilities = surface.get_capabilities(&adapter);
+        if let Some(format) = capabilities
+            .formats
+            .iter()
+            .find(|format| format.is_srgb() == surface_config.srgb)
+        {
+            config.format = *format;
+        }
+        config.present_mode = present_mode(&surface_config, &capabilities.present_modes);
+        config.desired_maximum_frame_latency = surface_config.frame_latency();
+        surface.configure(&device, &config);
+
+        Ok(Self {
+            surface,
+            adapter,
+            device,
+            queue,
+            config: Mutex::new(config),
+            surface_config,
+        })
+    }
+}
+
+/// Picks the present mode for `config.swap_interval`; wgpu can only sync to every refresh.
+fn present_mode(config: &SurfaceConfig, supported: &[wgpu::PresentMode]) -> wgpu::PresentMode {
+    if config.swap_interval > 0 {
+        return wgpu::PresentMode::Fifo;
+    }
+    let preferred = if config.triple_buffering {
+        wgpu::PresentMode::Mailbox
+    } else {
+        wgpu::PresentMode::Immediate
+    };
+    if supported.contains(&preferred) {
+        preferred
+    } else {
+        wgpu::PresentMode::AutoNoVsync
+    }
+}
+
+#[uniffi::export]
+impl WgpuSurface {
+    /// Reconfigures the surface; call it on `Resized` events. Zero sizes are ignored.
+    pub fn resize(&self, width: u32, height: u32) {
+        if width == 0 || height == 0 {
+            return;
+        }
+        let mut config = self.config.lock().unwrap();
+        config.width = width;
+        config.height = height;
+        self.surface.configure(&self.device, &config);
+    }
+
+    /// The options the surface was created with.
+    ///
+    /// wgpu surfaces are single-sampled without depth; renderers create MSAA and
+    /// depth/stencil attachments matching `samples`, `depth_bits` and `stencil_bits`.
+    pub fn surface_config(&self) -> SurfaceConfig {
+        self.surface_config
+    }
+
+    /// Name and backend of the adapter in use, e.g. for diagnostics.
+    pub fn adapter_info(&self) -> String {
+        let info = self.adapter.get_info();
+        format!("{} ({:?})", info.name, info.backend)
+    }
+}
+
+/// Clears the surface to a solid color and presents it; a smoke test for the graphics setup.
+#[derive(uniffi::Object)]
+pub struct ClearRenderer {
+    surface: Arc<WgpuSurface>,
+}
+
+#[uniffi::export]
+impl ClearRenderer {
+    #[uniffi::constructor]
+    pub fn new(surface: Arc<WgpuSurface>) -> Self {
+        Self { surface }
+    }
+
+    /// Clears to the given color (components in 0.0..=1.0) and presents the frame.
+    pub fn render(&self, red: f64, green: f64, blue: f64, alpha: f64) -> Result<(), TaoError> {
+        let surface = &self.surface;
+        let frame = match surface.surface.get_current_texture() {
+            Ok(frame) => frame,
+            Err(wgpu::SurfaceError::Outdated | wgpu::SurfaceError::Lost) => {
+                // Reconfigure and retry once, e.g. after a resize we did not hear about.
+                surface
+                    .surface
+                    .configure(&surface.device, &surface.config.lock().unwrap());
+                surface.surface.get_current_texture().map_err(wgpu_error)?
+            }
+            Err(error) => return Err(wgpu_error(error)),
+        };
+        let view = frame.texture.create_view(&wgpu::TextureViewDescriptor::default());
+        clear(
+            &surface.device,
+            &surface.queue,
+            &view,
+            wgpu::Color {
+                r: red,
+                g: green,
+                b: blue,
+                a: alpha,
+            },
+        );
+        frame.present();
+        Ok(())
+    }
+}
+
+/// Records and submits a render pass that only clears `view`.
+pub(crate) fn clear(device: &wgpu::Device, queue: &wgpu::Queue, view: &wgpu::TextureView, color: wgpu::Color) {
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
+        label: Some("taokt clear"),
+    });
+    encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+        label: Some("taokt clear"),
+        color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+            view,
+            resolve_target: None,
+            ops: wgpu::Operations {
+                load: wgpu::LoadOp::Clear(color),
+                store: wgpu::StoreOp::Store,
+            },
+        })],
+        depth_stencil_attachment: None,
+        timestamp_writes: None,
+        occlusion_query_set: None,
+    });
+    queue.submit(Some(encoder.finish()));
+}
+
+#[uniffi::export]
+impl Window {
+    /// Creates a wgpu surface for this window using the default adapter.
+    pub fn create_wgpu_surface(self: Arc<Self>, surface_config: SurfaceConfig) -> Result<Arc<WgpuSurface>, TaoError> {
+        WgpuSurface::new(self, surface_config).map(Arc::new)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_present_mode_follows_swap_interval() {
+        let supported = [wgpu::PresentMode::Fifo, wgpu::PresentMode::Mailbox];
+        let vsync = SurfaceConfig::default();
+        assert_eq!(present_mode(&vsync, &supported), wgpu::PresentMode::Fifo);
+
+        let triple = SurfaceConfig {
+            swap_interval: 0,
+            triple_buffering: true,
+            ..vsync
+        };
+        assert_eq!(present_mode(&triple, &supported), wgpu::PresentMode::Mailbox);
+
+        let immediate = SurfaceConfig {
+            swap_interval: 0,
+            ..vsync
+        };
+        assert_eq!(present_mode(&immediate, &supported), wgpu::PresentMode::AutoNoVsync);
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn test_clear_offscreen_texture() {
+        let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor::default());
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default())).unwrap();
+        let (device, queue) = pollster::block_on(adapter.request_device(&wgpu::DeviceDescriptor::default())).unwrap();
+        let texture = device.create_texture(&wgpu::TextureDescriptor {
+            label: None,
+            size: wgpu::Extent3d {
+                width: 4,
+                height: 4,
+                depth_or_array_layers: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: wgpu::TextureDimension::D2,
+            format: wgpu::TextureFormat::Rgba8Unorm,
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            view_formats: &[],
+        });
+        let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+        clear(&device, &queue, &view, wgpu::Color::GREEN);
+        device.poll(wgpu::PollType::Wait).unwrap();
+    }
+}
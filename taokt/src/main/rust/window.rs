@@ -1,9 +1,34 @@
-use std::sync::{Arc, Mutex};
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU32, Ordering},
+        Arc, Mutex,
+    },
+    thread::ThreadId,
+    time::Instant,
+};
 
 use crate::{
-    CursorIcon, Icon, LogicalSize, Monitor, PhysicalPositionF64, PhysicalPositionI32, PhysicalSizeU32, ProgressBarState,
-    TaoError, Theme, VideoMode, WindowSizeConstraints,
+    damage::SharedDirtyRegion,
+    events::NativeEventQueue,
+    grab::{apply_grab, CursorGrab, GrabInput},
+    CursorGrabMode, CursorIcon, ElementState, Icon, IconSet, KeyCode, LoopMessage, LoopProxy, Monitor, MouseButton,
+    Orientation, PhysicalPositionF64, PhysicalPositionI32, PhysicalRect, PhysicalSizeU32, Position, ProgressBarState,
+    Size, StatusBarStyle, SurfaceColorInfo, TaoError, TaoEvent, TaoWindowEvent, Theme, VideoMode,
+    WindowSizeConstraints,
 };
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+use crate::{DragData, Menu, MessageBoxResult, MessageButtons, MessageLevel};
+
+/// How long a paced frame waits for the Wayland frame callback of the previous one, which never
+/// fires if the application did not present it.
+#[cfg(any(
+    target_os = "linux",
+    target_os = "dragonfly",
+    target_os = "freebsd",
+    target_os = "netbsd",
+    target_os = "openbsd"
+))]
+const FRAME_CALLBACK_TIMEOUT: std::time::Duration = std::time::Duration::from_millis(250);
 
 #[derive(Clone)]
 struct SendableWindowBuilder(tao::window::WindowBuilder);
@@ -49,6 +74,7 @@ fn fullscreen_from_tao(fullscreen: tao::window::Fullscreen) -> Fullscreen {
 #[derive(uniffi::Object)]
 pub struct WindowBuilder {
     inner: Mutex<SendableWindowBuilder>,
+    application_id: Mutex<Option<String>>,
 }
 
 #[uniffi::export]
@@ -57,6 +83,7 @@ impl WindowBuilder {
     pub fn new() -> Self {
         Self {
             inner: Mutex::new(SendableWindowBuilder(tao::window::WindowBuilder::new())),
+            application_id: Mutex::new(None),
         }
     }
 
@@ -65,18 +92,26 @@ impl WindowBuilder {
         inner.0 = inner.0.clone().with_title(title);
     }
 
-    pub fn set_inner_size(&self, size: LogicalSize) {
+    pub fn set_inner_size(&self, size: Size) {
         let mut inner = self.inner.lock().unwrap();
-        let size: tao::dpi::Size = tao::dpi::LogicalSize::new(size.width, size.height).into();
         inner.0 = inner.0.clone().with_inner_size(size);
     }
 
-    pub fn set_min_inner_size(&self, size: LogicalSize) {
+    pub fn set_min_inner_size(&self, size: Size) {
         let mut inner = self.inner.lock().unwrap();
-        let size: tao::dpi::Size = tao::dpi::LogicalSize::new(size.width, size.height).into();
         inner.0 = inner.0.clone().with_min_inner_size(size);
     }
 
+    pub fn set_max_inner_size(&self, size: Size) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.0 = inner.0.clone().with_max_inner_size(size);
+    }
+
+    pub fn set_position(&self, position: Position) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.0 = inner.0.clone().with_position(position);
+    }
+
     pub fn set_decorations(&self, decorations: bool) {
         let mut inner = self.inner.lock().unwrap();
         inner.0 = inner.0.clone().with_decorations(decorations);
@@ -116,6 +151,13 @@ impl WindowBuilder {
             .with_theme(theme.map(|t| tao::window::Theme::from(t)));
     }
 
+    /// Wayland `app_id` and X11 `WM_CLASS` of the window, matched against the `.desktop` file name
+    /// (without `.desktop`) for its icon and taskbar grouping. Overrides `App::set_application_id`.
+    /// Linux only; ignored elsewhere.
+    pub fn set_application_id(&self, application_id: String) {
+        *self.application_id.lock().unwrap() = Some(application_id);
+    }
+
     pub fn set_parent_window(&self, parent: Arc<Window>) -> Result<(), TaoError> {
         let parent_window = parent.inner.lock().unwrap();
 
@@ -161,12 +203,494 @@ impl WindowBuilder {
     pub(crate) fn clone_inner(&self) -> tao::window::WindowBuilder {
         self.inner.lock().unwrap().0.clone()
     }
+
+    pub(crate) fn application_id(&self) -> Option<String> {
+        self.application_id.lock().unwrap().clone()
+    }
 }
 
 #[derive(uniffi::Object)]
 pub struct Window {
     pub(crate) id: u64,
     pub(crate) inner: Mutex<tao::window::Window>,
+    /// Last maximized state reported through `MaximizedChanged`.
+    maximized: AtomicBool,
+    /// Set by `set_maximized` so the next state change is reported as programmatic.
+    maximize_requested: AtomicBool,
+    /// Icon set re-applied on scale factor and monitor changes, with the size last applied.
+    icon_set: Mutex<Option<(Arc<IconSet>, u32)>>,
+    /// Set by `request_redraw_at_next_vblank` until the event loop issues the redraw.
+    frame_requested: AtomicBool,
+    /// Areas to report with the next `RedrawRequested`.
+    dirty: SharedDirtyRegion,
+    /// Last refresh rate reported through `RefreshRateChanged`, in millihertz (0 when unknown).
+    refresh_rate: AtomicU32,
+    /// Last color capabilities reported through `ColorInfoChanged`.
+    color_info: Mutex<SurfaceColorInfo>,
+    /// Emulation state of the grab set with `set_cursor_grab`.
+    cursor_grab: Arc<Mutex<CursorGrab>>,
+    /// Pen input and gestures captured from native events.
+    native_events: NativeEventQueue,
+    /// Theme set with `set_theme`; `None` follows the system.
+    forced_theme: Mutex<Option<Theme>>,
+    /// Menu bar set with `set_menu`.
+    #[cfg(not(any(target_os = "android", target_os = "ios")))]
+    menu: Mutex<Option<Arc<Menu>>>,
+    /// Wayland protocol objects bound to the surface on first use; `None` off Wayland.
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    wayland: std::sync::OnceLock<Option<Mutex<crate::wayland::WaylandSurface>>>,
+    /// Started by `set_display_link`.
+    #[cfg(target_os = "macos")]
+    display_link: Mutex<Option<crate::display_link::DisplayLink>>,
+    /// `set_display_link` asked for a `TaoEvent::DisplayLinkFrame` at every refresh.
+    #[cfg(target_os = "macos")]
+    display_link_ticks: AtomicBool,
+    /// Requested frames start a display link unless `set_display_link(false, _)` turned it off.
+    #[cfg(target_os = "macos")]
+    display_link_auto: AtomicBool,
+    /// When the Wayland frame callback for the last paced frame was requested, until it fires.
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    frame_callback: Mutex<Option<Instant>>,
+    /// Thread running the event loop that created the window.
+    loop_thread: ThreadId,
+    /// Setter calls made from other threads, applied by the event loop in order.
+    commands: Mutex<Vec<WindowCommand>>,
+    wake: Mutex<LoopProxy>,
+}
+
+type WindowCommand = Box<dyn FnOnce(&tao::window::Window) + Send>;
+
+impl Window {
+    pub(crate) fn new(
+        id: u64,
+        window: tao::window::Window,
+        wake: LoopProxy,
+    ) -> Self {
+        let maximized = window.is_maximized();
+        let refresh_rate = window
+            .current_monitor()
+            .and_then(|m| crate::monitor::refresh_rate_millihertz(&m))
+            .unwrap_or(0);
+        let color_info = crate::graphics::surface_color_info(&window);
+        let native_events = NativeEventQueue::default();
+        crate::pen::attach(&window, &native_events);
+        crate::gesture::attach(&window, &native_events);
+        crate::session::attach(&window, &wake);
+        crate::msg_hook::attach(&window);
+        crate::graphics::watch_color_info(&window, &wake);
+        let dirty = SharedDirtyRegion::default();
+        crate::damage::attach(&window, &dirty);
+        Self {
+            id,
+            inner: Mutex::new(window),
+            maximized: AtomicBool::new(maximized),
+            maximize_requested: AtomicBool::new(false),
+            icon_set: Mutex::new(None),
+            frame_requested: AtomicBool::new(false),
+            dirty,
+            refresh_rate: AtomicU32::new(refresh_rate),
+            color_info: Mutex::new(color_info),
+            cursor_grab: Arc::new(Mutex::new(CursorGrab::default())),
+            native_events,
+            forced_theme: Mutex::new(None),
+            #[cfg(not(any(target_os = "android", target_os = "ios")))]
+            menu: Mutex::new(None),
+            #[cfg(any(
+                target_os = "linux",
+                target_os = "dragonfly",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd"
+            ))]
+            wayland: std::sync::OnceLock::new(),
+            #[cfg(target_os = "macos")]
+            display_link: Mutex::new(None),
+            #[cfg(target_os = "macos")]
+            display_link_ticks: AtomicBool::new(false),
+            #[cfg(target_os = "macos")]
+            display_link_auto: AtomicBool::new(true),
+            #[cfg(any(
+                target_os = "linux",
+                target_os = "dragonfly",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd"
+            ))]
+            frame_callback: Mutex::new(None),
+            loop_thread: std::thread::current().id(),
+            commands: Mutex::new(Vec::new()),
+            wake: Mutex::new(wake),
+        }
+    }
+
+    /// Runs `command` right away on the event loop thread, otherwise queues it for the loop.
+    ///
+    /// Several platforms (notably macOS) only allow window changes on the main thread, so
+    /// setters called from other threads take effect on the next loop iteration.
+    fn run_on_loop(&self, command: impl FnOnce(&tao::window::Window) + Send + 'static) {
+        if std::thread::current().id() == self.loop_thread {
+            command(&self.inner.lock().unwrap());
+            return;
+        }
+
+        let mut commands = self.commands.lock().unwrap();
+        if commands.is_empty() {
+            // Nothing left to apply once the loop is gone.
+            let _ = self.wake.lock().unwrap().send_event(LoopMessage::WindowCommands);
+        }
+        commands.push(Box::new(command));
+    }
+
+    /// Applies the commands queued from other threads. Called by the event loop.
+    pub(crate) fn drain_commands(&self) {
+        let commands = std::mem::take(&mut *self.commands.lock().unwrap());
+        if commands.is_empty() {
+            return;
+        }
+        let window = self.inner.lock().unwrap();
+        for command in commands {
+            command(&window);
+        }
+    }
+
+    pub(crate) fn frame_requested(&self) -> bool {
+        self.frame_requested.load(Ordering::Relaxed)
+    }
+
+    /// Refresh rate last reported through `RefreshRateChanged`, in millihertz (0 when unknown).
+    pub(crate) fn refresh_rate_millihertz(&self) -> u32 {
+        self.refresh_rate.load(Ordering::Relaxed)
+    }
+
+    /// Key of the monitor the window is on and its last known refresh rate in millihertz (0 when
+    /// unknown), which group paced frames with `RunConfig::vsync_groups`.
+    pub(crate) fn vsync_group(&self) -> (String, u32) {
+        let key = self.inner.lock().unwrap().current_monitor().map(|m| crate::monitor::monitor_key(&m));
+        (key.unwrap_or_default(), self.refresh_rate_millihertz())
+    }
+
+    /// A requested frame waits for the frame timer rather than a display link or Wayland frame
+    /// callbacks. Call on the event loop thread.
+    pub(crate) fn frame_paced_by_timer(&self) -> bool {
+        #[cfg(target_os = "macos")]
+        if self.display_link.lock().unwrap().is_some() {
+            return false;
+        }
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        if self.wayland_surface().is_some() {
+            return false;
+        }
+        self.frame_requested()
+    }
+
+    /// Starts a requested frame from the display's vblank signal instead of the frame timer. On
+    /// macOS this starts a display link, whose next tick issues the redraw. On Wayland the redraw
+    /// is issued once the frame callback for the previous paced frame fired, or after
+    /// `FRAME_CALLBACK_TIMEOUT` for a frame the application never presented; the compositor
+    /// withholds callbacks from hidden windows. Call on the event loop thread.
+    pub(crate) fn start_vblank_frame(&self, now: Instant) {
+        if !self.frame_requested() {
+            return;
+        }
+
+        #[cfg(target_os = "macos")]
+        if self.display_link_auto.load(Ordering::Relaxed) {
+            let mut link = self.display_link.lock().unwrap();
+            if link.is_none() {
+                // Without a display the frame timer keeps pacing the window.
+                *link = self.new_display_link().ok();
+            }
+        }
+
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        if let Some(surface) = self.wayland_surface() {
+            let mut callback = self.frame_callback.lock().unwrap();
+            if callback.is_some_and(|requested| now < requested + FRAME_CALLBACK_TIMEOUT) {
+                return;
+            }
+            *callback = Some(now);
+            surface.lock().unwrap().request_frame();
+            drop(callback);
+            self.start_frame();
+        }
+
+        let _ = now;
+    }
+
+    /// When `start_vblank_frame` has to run again for a frame waiting on a Wayland frame
+    /// callback: right away if none is outstanding, else when the callback times out.
+    pub(crate) fn next_vblank_frame(&self, now: Instant) -> Option<Instant> {
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        if self.frame_requested() && self.wayland_surface().is_some() {
+            let requested = *self.frame_callback.lock().unwrap();
+            return Some(requested.map_or(now, |requested| (requested + FRAME_CALLBACK_TIMEOUT).max(now)));
+        }
+
+        let _ = now;
+        None
+    }
+
+    #[cfg(target_os = "macos")]
+    fn new_display_link(&self) -> Result<crate::display_link::DisplayLink, TaoError> {
+        use tao::platform::macos::MonitorHandleExtMacOS;
+
+        let display = self
+            .inner
+            .lock()
+            .unwrap()
+            .current_monitor()
+            .map(|monitor| monitor.native_id())
+            .ok_or_else(|| TaoError::message("The window is not on a display"))?;
+        let proxy = self.wake.lock().unwrap().clone();
+        crate::display_link::DisplayLink::new(self.id, display, proxy)
+    }
+
+    /// Handles a display link tick: follows the window to its current display and issues the
+    /// requested frame, or pauses the link when there is none and no tick consumer.
+    #[cfg(target_os = "macos")]
+    pub(crate) fn display_link_frame(&self) {
+        use tao::platform::macos::MonitorHandleExtMacOS;
+
+        let idle = !self.frame_requested() && !self.reports_display_link_frames();
+        if let Some(link) = self.display_link.lock().unwrap().as_mut() {
+            if let Some(monitor) = self.inner.lock().unwrap().current_monitor() {
+                link.set_display(monitor.native_id());
+            }
+            link.frame_handled();
+            if idle {
+                link.pause();
+            }
+        }
+        self.start_frame();
+    }
+
+    /// Whether display link ticks are reported as `TaoEvent::DisplayLinkFrame`.
+    #[cfg(target_os = "macos")]
+    pub(crate) fn reports_display_link_frames(&self) -> bool {
+        self.display_link_ticks.load(Ordering::Relaxed)
+    }
+
+    /// Stops the display link when the window is destroyed, and takes the window's display out of
+    /// frame pacing.
+    pub(crate) fn destroyed(&self) {
+        self.refresh_rate.store(0, Ordering::Relaxed);
+        #[cfg(target_os = "macos")]
+        self.display_link.lock().unwrap().take();
+    }
+
+    /// Issues a redraw if one was requested for the next frame.
+    pub(crate) fn start_frame(&self) {
+        if self.frame_requested.swap(false, Ordering::Relaxed) {
+            self.dirty.lock().unwrap().invalidate_all();
+            self.inner.lock().unwrap().request_redraw();
+        }
+    }
+
+    /// Returns the areas requested since the last redraw; empty means the whole window.
+    pub(crate) fn take_dirty_rects(&self) -> Vec<PhysicalRect> {
+        self.dirty.lock().unwrap().take()
+    }
+
+    /// Makes the next redraw a full one, e.g. after a resize.
+    pub(crate) fn invalidate_all(&self) {
+        self.dirty.lock().unwrap().invalidate_all();
+    }
+
+    /// Re-applies the attached icon set if the current scale factor calls for another variant.
+    pub(crate) fn refresh_icon_set(&self) {
+        let mut icon_set = self.icon_set.lock().unwrap();
+        let Some((set, applied)) = icon_set.as_mut() else {
+            return;
+        };
+        let window = self.inner.lock().unwrap();
+        let desired = set.desired_size(window.scale_factor());
+        if *applied == desired {
+            return;
+        }
+        *applied = desired;
+        if let Some(icon) = set.best_for_scale(window.scale_factor()) {
+            window.set_window_icon(Some(icon.inner.clone()));
+        }
+    }
+
+    /// Returns a `MaximizedChanged` event if the maximized state differs from the last one reported.
+    pub(crate) fn take_maximized_change(&self) -> Option<TaoWindowEvent> {
+        let maximized = self.inner.lock().unwrap().is_maximized();
+        if self.maximized.swap(maximized, Ordering::SeqCst) == maximized {
+            return None;
+        }
+        let programmatic = self.maximize_requested.swap(false, Ordering::SeqCst);
+        Some(TaoWindowEvent::MaximizedChanged {
+            maximized,
+            user_initiated: !programmatic,
+        })
+    }
+
+    /// Returns a `RefreshRateChanged` event if the window's monitor refresh rate differs from the last one reported.
+    pub(crate) fn take_refresh_rate_change(&self) -> Option<TaoWindowEvent> {
+        let rate = self.current_refresh_rate_millihertz();
+        if self.refresh_rate.swap(rate.unwrap_or(0), Ordering::Relaxed) == rate.unwrap_or(0) {
+            return None;
+        }
+        Some(TaoWindowEvent::RefreshRateChanged {
+            refresh_rate_millihertz: rate,
+        })
+    }
+
+    /// Returns a `ColorInfoChanged` event if the display's color capabilities differ from the last ones reported.
+    pub(crate) fn take_color_info_change(&self) -> Option<TaoWindowEvent> {
+        let color_info = crate::graphics::surface_color_info(&self.inner.lock().unwrap());
+        let mut last = self.color_info.lock().unwrap();
+        if *last == color_info {
+            return None;
+        }
+        *last = color_info;
+        Some(TaoWindowEvent::ColorInfoChanged { color_info })
+    }
+
+    /// No theme was forced with `set_theme`.
+    pub(crate) fn follows_system_theme(&self) -> bool {
+        self.forced_theme.lock().unwrap().is_none()
+    }
+
+    /// Events captured from native input since the last call.
+    pub(crate) fn take_native_events(&self) -> Vec<TaoWindowEvent> {
+        std::mem::take(&mut *self.native_events.lock().unwrap())
+    }
+
+    fn inject(&self, events: Vec<TaoWindowEvent>) -> Result<(), TaoError> {
+        let wake = self.wake.lock().unwrap();
+        for event in events {
+            let event = TaoEvent::WindowEvent {
+                window_id: self.id,
+                event,
+            };
+            wake.send_event(LoopMessage::Inject(Box::new(event)))?;
+        }
+        Ok(())
+    }
+
+    /// Applies the current grab again after the native one was reset.
+    pub(crate) fn restore_cursor_grab(&self) {
+        crate::grab::restore_grab(self.cursor_grab.lock().unwrap().mode());
+    }
+
+    /// Warps the cursor back if an emulated grab is active and `input` escaped it.
+    pub(crate) fn enforce_cursor_grab(&self, input: GrabInput) {
+        let mut grab = self.cursor_grab.lock().unwrap();
+        if !grab.is_emulated() {
+            return;
+        }
+        let window = self.inner.lock().unwrap();
+        if let Some(position) = grab.correction(input, window.inner_size().into()) {
+            let _ = window.set_cursor_position(tao::dpi::PhysicalPosition::new(position.x, position.y));
+        }
+    }
+
+    /// The compositor's preferred fractional scale, if the window is on Wayland and the
+    /// compositor supports wp_fractional_scale_v1.
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    pub(crate) fn fractional_scale(&self) -> Option<f64> {
+        self.wayland_surface()?.lock().unwrap().scale()
+    }
+
+    #[cfg(any(
+        target_os = "linux",
+        target_os = "dragonfly",
+        target_os = "freebsd",
+        target_os = "netbsd",
+        target_os = "openbsd"
+    ))]
+    fn wayland_surface(&self) -> Option<&Mutex<crate::wayland::WaylandSurface>> {
+        // The objects are bound to GTK's connection, which is only used on the loop thread.
+        if std::thread::current().id() != self.loop_thread {
+            return None;
+        }
+        self.wayland
+            .get_or_init(|| {
+                use tao::platform::unix::WindowExtUnix;
+                let window = self.inner.lock().unwrap();
+                let display = window.wayland_display()?;
+                let surface = window.wayland_surface()?;
+                crate::wayland::WaylandSurface::new(display as _, surface as _).map(Mutex::new)
+            })
+            .as_ref()
+    }
+
+    /// Returns a `FractionalScaleChanged` event if the compositor's preferred scale changed.
+    pub(crate) fn take_fractional_scale_change(&self) -> Option<TaoWindowEvent> {
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        {
+            let scale_factor = self.wayland_surface()?.lock().unwrap().take_scale_change()?;
+            return Some(TaoWindowEvent::FractionalScaleChanged { scale_factor });
+        }
+
+        #[allow(unreachable_code)]
+        None
+    }
+
+    /// Timestamps of Wayland frame callbacks that fired since the last call.
+    pub(crate) fn take_presented_frames(&self) -> Vec<u32> {
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        if let Some(surface) = self.wayland.get().and_then(Option::as_ref) {
+            let presented = surface.lock().unwrap().take_presented();
+            if !presented.is_empty() {
+                // The compositor is ready for the next paced frame.
+                self.frame_callback.lock().unwrap().take();
+            }
+            return presented;
+        }
+
+        Vec::new()
+    }
 }
 
 #[uniffi::export]
@@ -201,6 +725,67 @@ impl Window {
         }
     }
 
+    /// Returns the `GtkApplicationWindow*` of the window (Linux only).
+    ///
+    /// The pointer is borrowed: it stays valid while the window is alive, and must only be
+    /// used on the event loop thread.
+    pub fn gtk_window_handle(&self) -> Result<u64, TaoError> {
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        {
+            use gtk::glib::object::ObjectType;
+            use tao::platform::unix::WindowExtUnix;
+            let window = self.inner.lock().unwrap();
+            return Ok(window.gtk_window().as_ptr() as u64);
+        }
+        #[cfg(not(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        )))]
+        {
+            return Err(TaoError::Unsupported);
+        }
+    }
+
+    /// Returns the `GtkBox*` holding the window content (Linux only), e.g. to pack a menubar
+    /// or infobar above it. Same lifetime rules as `gtk_window_handle`.
+    pub fn gtk_default_vbox_handle(&self) -> Result<u64, TaoError> {
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        {
+            use gtk::glib::object::ObjectType;
+            use tao::platform::unix::WindowExtUnix;
+            let window = self.inner.lock().unwrap();
+            return window
+                .default_vbox()
+                .map(|vbox| vbox.as_ptr() as u64)
+                .ok_or_else(|| TaoError::message("Window was built without a default vbox"));
+        }
+        #[cfg(not(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        )))]
+        {
+            return Err(TaoError::Unsupported);
+        }
+    }
+
     /// Returns the HWND handle (Windows only).
     pub fn hwnd_handle(&self) -> Result<u64, TaoError> {
         #[cfg(target_os = "windows")]
@@ -389,14 +974,126 @@ impl Window {
         WindowGraphicsExt::raw_window_handle_for_backend(self, backend)
     }
 
+    /// Returns the fractional scale and matching buffer size of the window's Wayland surface.
+    ///
+    /// Fails with `Unsupported` when the window is not on Wayland. Must be called on the event
+    /// loop thread to report fractional scales.
+    pub fn wayland_scale(&self) -> Result<crate::WaylandScale, TaoError> {
+        use crate::graphics::WindowGraphicsExt;
+        WindowGraphicsExt::wayland_scale(self)
+    }
+
+    /// Returns the bit depth, HDR state and color space of the display the window is on.
+    ///
+    /// `ColorInfoChanged` is emitted when these change, e.g. after moving to another display or, on
+    /// Windows and macOS, switching HDR in the system settings.
+    pub fn surface_color_info(&self) -> SurfaceColorInfo {
+        use crate::graphics::WindowGraphicsExt;
+        WindowGraphicsExt::surface_color_info(self)
+    }
+
+    /// Creates a CPU framebuffer for this window; see `Framebuffer::present`.
+    pub fn create_framebuffer(self: Arc<Self>) -> Result<Arc<crate::Framebuffer>, TaoError> {
+        crate::Framebuffer::new(self).map(Arc::new)
+    }
+
     pub fn request_redraw(&self) {
-        let window = self.inner.lock().unwrap();
-        window.request_redraw();
+        self.dirty.lock().unwrap().invalidate_all();
+        self.run_on_loop(|window| window.request_redraw());
+    }
+
+    /// Requests a redraw of `rect` only; see `TaoEvent::RedrawRequested::dirty_rects`.
+    ///
+    /// Areas requested before the redraw is delivered are accumulated. A full
+    /// `request_redraw` in between makes the next redraw a full one.
+    pub fn request_redraw_rect(&self, rect: PhysicalRect) {
+        self.dirty.lock().unwrap().add(rect);
+        self.run_on_loop(|window| window.request_redraw());
+    }
+
+    /// Asks the Wayland compositor for a frame callback, delivered as `TaoEvent::FramePresented`.
+    ///
+    /// Call it before presenting; the event arrives once the compositor is ready for the next
+    /// frame, so renderers can draw from it instead of from a timer. Must be called on the
+    /// event loop thread. Fails with `Unsupported` off Wayland.
+    pub fn request_frame_callback(&self) -> Result<(), TaoError> {
+        #[cfg(any(
+            target_os = "linux",
+            target_os = "dragonfly",
+            target_os = "freebsd",
+            target_os = "netbsd",
+            target_os = "openbsd"
+        ))]
+        if let Some(surface) = self.wayland_surface() {
+            surface.lock().unwrap().request_frame();
+            return Ok(());
+        }
+
+        Err(TaoError::Unsupported)
+    }
+
+    /// Requests a redraw paced to the display's refresh rate instead of an immediate one.
+    ///
+    /// The event loop sleeps until the next frame slot and then emits `RedrawRequested`, so
+    /// renderers can request every frame from their redraw handler without `ControlFlow::Poll`.
+    /// The redraw follows the display's vblank signal where there is one: a CVDisplayLink on macOS
+    /// (see `set_display_link`) and the compositor's frame callbacks on Wayland, which are also
+    /// reported as `TaoEvent::FramePresented`. Elsewhere a frame timer paces it, following the
+    /// fastest monitor showing a window (`RunConfig::target_fps` overrides it, 60 when the platform
+    /// reports none) and kept in phase with DWM's vblank on Windows. Callable from any thread.
+    pub fn request_redraw_at_next_vblank(&self) {
+        let requested = self.frame_requested.swap(true, Ordering::Relaxed);
+        if !requested && std::thread::current().id() != self.loop_thread {
+            // The loop may be waiting without a deadline; it schedules the frame once awake.
+            let _ = self.wake.lock().unwrap().send_event(LoopMessage::Resume);
+        }
+        #[cfg(target_os = "macos")]
+        if let Some(link) = self.display_link.lock().unwrap().as_mut() {
+            link.resume();
+        }
+    }
+
+    /// Controls the CVDisplayLink that paces `request_redraw_at_next_vblank` on the window's
+    /// display (macOS only). The first requested frame starts one on its own; `enabled = false`
+    /// stops it and leaves pacing to the frame timer. With `report_every_frame`, every refresh is
+    /// also reported as `TaoEvent::DisplayLinkFrame`; otherwise the link only runs while a frame
+    /// is requested. The link stops when the window is destroyed. Call on the event loop thread.
+    pub fn set_display_link(&self, enabled: bool, report_every_frame: bool) -> Result<(), TaoError> {
+        #[cfg(target_os = "macos")]
+        {
+            if std::thread::current().id() != self.loop_thread {
+                return Err(TaoError::message("set_display_link must be called on the event loop thread"));
+            }
+            self.display_link_ticks.store(enabled && report_every_frame, Ordering::Relaxed);
+            self.display_link_auto.store(enabled, Ordering::Relaxed);
+            let mut link = self.display_link.lock().unwrap();
+            if !enabled {
+                *link = None;
+            } else if let Some(link) = link.as_mut() {
+                if report_every_frame {
+                    link.resume();
+                }
+            } else {
+                *link = Some(self.new_display_link()?);
+            }
+            return Ok(());
+        }
+
+        #[allow(unreachable_code)]
+        {
+            let _ = (enabled, report_every_frame);
+            Err(TaoError::Unsupported)
+        }
     }
 
     pub fn set_title(&self, title: String) {
-        let window = self.inner.lock().unwrap();
-        window.set_title(&title);
+        self.run_on_loop(move |window| window.set_title(&title));
+    }
+
+    /// Changes the Wayland `app_id` and X11 `WM_CLASS` of the window; see
+    /// `WindowBuilder::set_application_id`. Linux only; ignored elsewhere.
+    pub fn set_application_id(&self, application_id: String) {
+        self.run_on_loop(move |window| crate::app_id::set_application_id(window, &application_id));
     }
 
     pub fn scale_factor(&self) -> f64 {
@@ -405,34 +1102,106 @@ impl Window {
     }
 
     pub fn set_cursor_icon(&self, icon: CursorIcon) {
-        let window = self.inner.lock().unwrap();
-        window.set_cursor_icon(icon.into());
-    }
+        self.run_on_loop(move |window| {
+            crate::cursor::clear_named_cursor(window);
+            window.set_cursor_icon(icon.into());
+        });
+    }
+
+    /// Shows a platform cursor by name: an `IDC_*` system cursor on Windows (e.g. `"IDC_PIN"`),
+    /// a public `NSCursor` class method on macOS (e.g. `"dragCopyCursor"`) or an Xcursor theme name
+    /// on Linux (e.g. `"zoom-in"`). Lasts until the next `set_cursor_icon`.
+    ///
+    /// On macOS the cursor may revert when the pointer leaves and re-enters the window. From
+    /// other threads, a name that the Linux cursor theme lacks is ignored instead of reported.
+    pub fn set_cursor_by_name(&self, name: String) -> Result<(), TaoError> {
+        crate::cursor::check_name(&name)?;
+        let result = Arc::new(Mutex::new(Ok(())));
+        let slot = result.clone();
+        self.run_on_loop(move |window| *slot.lock().unwrap() = crate::cursor::set_named_cursor(window, &name));
+        std::mem::replace(&mut *result.lock().unwrap(), Ok(()))
+    }
+
+    /// Confines or locks the cursor. Modes the platform lacks natively (confining on macOS and
+    /// Linux, locking on Linux) are emulated by warping the cursor back as it moves. Wayland
+    /// allows neither warps nor a native grab here, so any mode but `None` fails with
+    /// `Unsupported` there. From other threads, a failure is ignored instead of reported.
+    pub fn set_cursor_grab(&self, mode: CursorGrabMode) -> Result<(), TaoError> {
+        let grab = self.cursor_grab.clone();
+        let result = Arc::new(Mutex::new(Ok(())));
+        let slot = result.clone();
+        self.run_on_loop(move |window| {
+            *slot.lock().unwrap() =
+                apply_grab(window, mode).map(|emulation| *grab.lock().unwrap() = CursorGrab::new(mode, emulation));
+        });
+        std::mem::replace(&mut *result.lock().unwrap(), Ok(()))
+    }
+
+    /// Keeps delivering mouse moves and button releases to this window while the cursor is
+    /// outside it, e.g. for the duration of a slider or reorder drag. Release it when the drag ends;
+    /// button releases in between do not end it. The system still ends it when another window
+    /// takes the capture, e.g. on Alt+Tab.
+    ///
+    /// macOS already routes a drag to the window where it started, so this is a no-op there.
+    pub fn set_mouse_capture(&self, capture: bool) {
+        self.run_on_loop(move |window| {
+            #[cfg(target_os = "windows")]
+            {
+                use tao::platform::windows::WindowExtWindows;
+                crate::grab::capture::set(window.hwnd() as _, capture);
+            }
 
-    pub fn set_cursor_grab(&self, grab: bool) -> Result<(), TaoError> {
-        let window = self.inner.lock().unwrap();
-        window.set_cursor_grab(grab)?;
-        Ok(())
+            #[cfg(any(
+                target_os = "linux",
+                target_os = "dragonfly",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd"
+            ))]
+            {
+                use gtk::{gdk::SeatCapabilities, prelude::WidgetExt};
+                use tao::platform::unix::WindowExtUnix;
+
+                let Some(gdk_window) = window.gtk_window().window() else {
+                    return;
+                };
+                let Some(seat) = gdk_window.display().default_seat() else {
+                    return;
+                };
+                if capture {
+                    // Owner events: the window still sees events over itself as usual.
+                    let _ = seat.grab(&gdk_window, SeatCapabilities::POINTER, true, None, None, None);
+                } else {
+                    seat.ungrab();
+                }
+            }
+
+            #[cfg(not(any(
+                target_os = "windows",
+                target_os = "linux",
+                target_os = "dragonfly",
+                target_os = "freebsd",
+                target_os = "netbsd",
+                target_os = "openbsd"
+            )))]
+            let _ = (window, capture);
+        });
     }
 
     pub fn set_cursor_visible(&self, visible: bool) {
-        let window = self.inner.lock().unwrap();
-        window.set_cursor_visible(visible);
+        self.run_on_loop(move |window| window.set_cursor_visible(visible));
     }
 
     pub fn set_decorations(&self, decorations: bool) {
-        let window = self.inner.lock().unwrap();
-        window.set_decorations(decorations);
+        self.run_on_loop(move |window| window.set_decorations(decorations));
     }
 
     pub fn set_resizable(&self, resizable: bool) {
-        let window = self.inner.lock().unwrap();
-        window.set_resizable(resizable);
+        self.run_on_loop(move |window| window.set_resizable(resizable));
     }
 
     pub fn set_minimized(&self, minimized: bool) {
-        let window = self.inner.lock().unwrap();
-        window.set_minimized(minimized);
+        self.run_on_loop(move |window| window.set_minimized(minimized));
     }
 
     pub fn is_minimized(&self) -> bool {
@@ -441,28 +1210,23 @@ impl Window {
     }
 
     pub fn set_focus(&self) {
-        let window = self.inner.lock().unwrap();
-        window.set_focus();
+        self.run_on_loop(move |window| window.set_focus());
     }
 
     pub fn set_visible(&self, visible: bool) {
-        let window = self.inner.lock().unwrap();
-        window.set_visible(visible);
+        self.run_on_loop(move |window| window.set_visible(visible));
     }
 
     pub fn set_always_on_top(&self, always_on_top: bool) {
-        let window = self.inner.lock().unwrap();
-        window.set_always_on_top(always_on_top);
+        self.run_on_loop(move |window| window.set_always_on_top(always_on_top));
     }
 
     pub fn set_always_on_bottom(&self, always_on_bottom: bool) {
-        let window = self.inner.lock().unwrap();
-        window.set_always_on_bottom(always_on_bottom);
+        self.run_on_loop(move |window| window.set_always_on_bottom(always_on_bottom));
     }
 
     pub fn set_content_protection(&self, enabled: bool) {
-        let window = self.inner.lock().unwrap();
-        window.set_content_protection(enabled);
+        self.run_on_loop(move |window| window.set_content_protection(enabled));
     }
 
     pub fn is_minimizable(&self) -> bool {
@@ -471,8 +1235,7 @@ impl Window {
     }
 
     pub fn set_minimizable(&self, minimizable: bool) {
-        let window = self.inner.lock().unwrap();
-        window.set_minimizable(minimizable);
+        self.run_on_loop(move |window| window.set_minimizable(minimizable));
     }
 
     pub fn is_maximizable(&self) -> bool {
@@ -481,8 +1244,7 @@ impl Window {
     }
 
     pub fn set_maximizable(&self, maximizable: bool) {
-        let window = self.inner.lock().unwrap();
-        window.set_maximizable(maximizable);
+        self.run_on_loop(move |window| window.set_maximizable(maximizable));
     }
 
     pub fn is_closable(&self) -> bool {
@@ -491,8 +1253,7 @@ impl Window {
     }
 
     pub fn set_closable(&self, closable: bool) {
-        let window = self.inner.lock().unwrap();
-        window.set_closable(closable);
+        self.run_on_loop(move |window| window.set_closable(closable));
     }
 
     pub fn is_maximized(&self) -> bool {
@@ -501,8 +1262,10 @@ impl Window {
     }
 
     pub fn set_maximized(&self, maximized: bool) {
-        let window = self.inner.lock().unwrap();
-        window.set_maximized(maximized);
+        if self.is_maximized() != maximized {
+            self.maximize_requested.store(true, Ordering::SeqCst);
+        }
+        self.run_on_loop(move |window| window.set_maximized(maximized));
     }
 
     pub fn inner_size(&self) -> PhysicalSizeU32 {
@@ -525,44 +1288,30 @@ impl Window {
         Ok(window.inner_position()?.into())
     }
 
-    pub fn set_outer_position(&self, position: PhysicalPositionI32) {
-        let window = self.inner.lock().unwrap();
-        let position: tao::dpi::Position =
-            tao::dpi::PhysicalPosition::new(position.x, position.y).into();
-        window.set_outer_position(position);
+    pub fn set_outer_position(&self, position: Position) {
+        self.run_on_loop(move |window| window.set_outer_position(position));
     }
 
-    pub fn set_inner_size(&self, size: PhysicalSizeU32) {
-        let window = self.inner.lock().unwrap();
-        let size: tao::dpi::Size = tao::dpi::PhysicalSize::new(size.width, size.height).into();
-        window.set_inner_size(size);
+    pub fn set_inner_size(&self, size: Size) {
+        self.run_on_loop(move |window| window.set_inner_size(size));
     }
 
-    pub fn set_min_inner_size(&self, size: Option<PhysicalSizeU32>) {
-        let window = self.inner.lock().unwrap();
-        let size = size.map(|s| tao::dpi::Size::from(tao::dpi::PhysicalSize::new(s.width, s.height)));
-        window.set_min_inner_size(size);
+    pub fn set_min_inner_size(&self, size: Option<Size>) {
+        self.run_on_loop(move |window| window.set_min_inner_size(size));
     }
 
-    pub fn set_max_inner_size(&self, size: Option<PhysicalSizeU32>) {
-        let window = self.inner.lock().unwrap();
-        let size = size.map(|s| tao::dpi::Size::from(tao::dpi::PhysicalSize::new(s.width, s.height)));
-        window.set_max_inner_size(size);
+    pub fn set_max_inner_size(&self, size: Option<Size>) {
+        self.run_on_loop(move |window| window.set_max_inner_size(size));
     }
 
-    pub fn set_cursor_position(&self, position: PhysicalPositionI32) -> Result<(), TaoError> {
+    pub fn set_cursor_position(&self, position: Position) -> Result<(), TaoError> {
         let window = self.inner.lock().unwrap();
-        let position: tao::dpi::Position =
-            tao::dpi::PhysicalPosition::new(position.x, position.y).into();
         window.set_cursor_position(position)?;
         Ok(())
     }
 
-    pub fn set_ime_position(&self, position: PhysicalPositionF64) {
-        let window = self.inner.lock().unwrap();
-        let position: tao::dpi::Position =
-            tao::dpi::PhysicalPosition::new(position.x, position.y).into();
-        window.set_ime_position(position);
+    pub fn set_ime_position(&self, position: Position) {
+        self.run_on_loop(move |window| window.set_ime_position(position));
     }
 
     pub fn drag_window(&self) -> Result<(), TaoError> {
@@ -577,15 +1326,43 @@ impl Window {
     }
 
     pub fn set_fullscreen(&self, fullscreen: Option<Fullscreen>) {
-        let window = self.inner.lock().unwrap();
-        window.set_fullscreen(fullscreen.as_ref().map(|f| f.to_tao()));
+        self.run_on_loop(move |window| window.set_fullscreen(fullscreen.as_ref().map(|f| f.to_tao())));
     }
 
     pub fn set_window_icon(&self, icon: Option<Arc<Icon>>) {
+        *self.icon_set.lock().unwrap() = None;
         let window = self.inner.lock().unwrap();
         window.set_window_icon(icon.as_ref().map(|i| i.inner.clone()));
     }
 
+    /// Attaches an icon set whose best variant follows the window's scale factor.
+    pub fn set_window_icon_set(&self, icon_set: Option<Arc<IconSet>>) {
+        let mut current = self.icon_set.lock().unwrap();
+        let window = self.inner.lock().unwrap();
+        match icon_set {
+            Some(set) => {
+                let scale_factor = window.scale_factor();
+                window.set_window_icon(set.best_for_scale(scale_factor).map(|i| i.inner.clone()));
+                let applied = set.desired_size(scale_factor);
+                *current = Some((set, applied));
+            }
+            None => {
+                *current = None;
+                window.set_window_icon(None);
+            }
+        }
+    }
+
+    /// Refresh rate of the monitor the window is on, in millihertz (e.g. 59940 for 59.94 Hz).
+    ///
+    /// `RefreshRateChanged` is emitted when this changes, e.g. after moving to another display.
+    pub fn current_refresh_rate_millihertz(&self) -> Option<u32> {
+        let window = self.inner.lock().unwrap();
+        window
+            .current_monitor()
+            .and_then(|m| crate::monitor::refresh_rate_millihertz(&m))
+    }
+
     pub fn current_monitor(&self) -> Option<Arc<Monitor>> {
         let window = self.inner.lock().unwrap();
         window.current_monitor().map(|m| Arc::new(Monitor { inner: m }))
@@ -596,6 +1373,12 @@ impl Window {
         window.primary_monitor().map(|m| Arc::new(Monitor { inner: m }))
     }
 
+    /// The monitor containing the global point (`x`, `y`), in physical pixels.
+    pub fn monitor_from_point(&self, x: f64, y: f64) -> Option<Arc<Monitor>> {
+        let window = self.inner.lock().unwrap();
+        window.monitor_from_point(x, y).map(|m| Arc::new(Monitor { inner: m }))
+    }
+
     pub fn available_monitors(&self) -> Vec<Arc<Monitor>> {
         let window = self.inner.lock().unwrap();
         window
@@ -604,24 +1387,117 @@ impl Window {
             .collect()
     }
 
+    /// Hides the status bar (iOS), e.g. for fullscreen video or games.
+    pub fn set_prefers_status_bar_hidden(&self, hidden: bool) {
+        #[cfg(target_os = "ios")]
+        self.run_on_loop(move |window| {
+            use tao::platform::ios::WindowExtIOS;
+            window.set_prefers_status_bar_hidden(hidden);
+        });
+
+        #[cfg(not(target_os = "ios"))]
+        let _ = hidden;
+    }
+
+    pub fn set_status_bar_style(&self, style: StatusBarStyle) {
+        #[cfg(target_os = "ios")]
+        self.run_on_loop(move |window| crate::ios::set_status_bar_style(window, style));
+
+        #[cfg(not(target_os = "ios"))]
+        let _ = style;
+    }
+
+    /// Hides the home indicator (iOS) until the user touches the screen.
+    pub fn set_prefers_home_indicator_hidden(&self, hidden: bool) {
+        #[cfg(target_os = "ios")]
+        self.run_on_loop(move |window| {
+            use tao::platform::ios::WindowExtIOS;
+            window.set_prefers_home_indicator_hidden(hidden);
+        });
+
+        #[cfg(not(target_os = "ios"))]
+        let _ = hidden;
+    }
+
+    /// Shows the on-screen keyboard (Android, iOS); `TaoEvent::SoftKeyboardChanged` reports the
+    /// part of the window it covers. On iOS typed text arrives as `KeyboardInput` events; on
+    /// Android it depends on the input connection `TaoActivity` provides.
+    pub fn show_soft_keyboard(&self) -> Result<(), TaoError> {
+        #[cfg(target_os = "android")]
+        return crate::android::show_soft_keyboard();
+
+        #[cfg(target_os = "ios")]
+        {
+            let queue = Arc::clone(&self.native_events);
+            let proxy = self.wake.lock().unwrap().clone();
+            self.run_on_loop(move |window| crate::ios::show_soft_keyboard(window, &queue, proxy));
+            return Ok(());
+        }
+
+        #[allow(unreachable_code)]
+        Err(TaoError::Unsupported)
+    }
+
+    pub fn hide_soft_keyboard(&self) -> Result<(), TaoError> {
+        #[cfg(target_os = "android")]
+        return crate::android::hide_soft_keyboard();
+
+        #[cfg(target_os = "ios")]
+        {
+            self.run_on_loop(crate::ios::hide_soft_keyboard);
+            return Ok(());
+        }
+
+        #[allow(unreachable_code)]
+        Err(TaoError::Unsupported)
+    }
+
+    /// Keeps the screen from dimming and locking while the application is in the foreground
+    /// (iOS), e.g. during video playback. On desktop use `App::inhibit_sleep`.
+    pub fn set_idle_timer_disabled(&self, disabled: bool) {
+        #[cfg(target_os = "ios")]
+        self.run_on_loop(move |_| crate::ios::set_idle_timer_disabled(disabled));
+
+        #[cfg(not(target_os = "ios"))]
+        let _ = disabled;
+    }
+
+    /// Restricts the orientations the window may rotate to (Android), e.g. `Landscape` for a
+    /// game. Rotations are reported as `TaoEvent::ConfigurationChanged`.
+    pub fn set_orientation_lock(&self, orientation: Orientation) -> Result<(), TaoError> {
+        #[cfg(target_os = "android")]
+        return crate::android::set_orientation_lock(orientation);
+
+        #[allow(unreachable_code)]
+        {
+            let _ = orientation;
+            Err(TaoError::Unsupported)
+        }
+    }
+
     pub fn set_progress_bar(&self, state: ProgressBarState) {
-        let window = self.inner.lock().unwrap();
-        window.set_progress_bar(state.into());
+        self.run_on_loop(move |window| window.set_progress_bar(state.into()));
     }
 
     pub fn set_inner_size_constraints(&self, constraints: WindowSizeConstraints) {
-        let window = self.inner.lock().unwrap();
-        window.set_inner_size_constraints(constraints.into());
+        self.run_on_loop(move |window| window.set_inner_size_constraints(constraints.into()));
     }
 
+    /// On Linux this is the desktop's dark style preference from the settings portal when it
+    /// has one, unless a theme was set with `set_theme`.
     pub fn theme(&self) -> Theme {
+        if self.follows_system_theme() {
+            if let Some(theme) = crate::color_scheme::system_theme() {
+                return theme;
+            }
+        }
         let window = self.inner.lock().unwrap();
         window.theme().into()
     }
 
     pub fn set_theme(&self, theme: Option<Theme>) {
-        let window = self.inner.lock().unwrap();
-        window.set_theme(theme.map(|t| t.into()));
+        *self.forced_theme.lock().unwrap() = theme;
+        self.run_on_loop(move |window| window.set_theme(theme.map(|t| t.into())));
     }
 
     pub fn set_overlay_icon(&self, icon: Option<Arc<Icon>>) {
@@ -636,6 +1512,40 @@ impl Window {
         let _ = icon;
     }
 
+    /// Shows `text` (typically an unread count) as a badge, or clears it with `None`.
+    ///
+    /// Windows renders a taskbar overlay icon (digits and `+ - ! ?` only), macOS sets the dock
+    /// badge label, and Linux and iOS show the text as a launcher/app badge count if it is a number.
+    pub fn set_overlay_badge_text(&self, text: Option<String>) -> Result<(), TaoError> {
+        let text = text.filter(|text| !text.is_empty());
+
+        #[cfg(windows)]
+        {
+            let icon = match text {
+                Some(text) => {
+                    let size = (16.0 * self.scale_factor()).round() as u32;
+                    let badge = crate::icon::render_badge(&text, size);
+                    Some(Arc::new(Icon::from_rgba(badge.into_raw(), size.max(1), size.max(1))?))
+                }
+                None => None,
+            };
+            self.set_overlay_icon(icon);
+            return Ok(());
+        }
+
+        #[cfg(target_os = "macos")]
+        {
+            self.set_badge_label(text);
+            return Ok(());
+        }
+
+        #[allow(unreachable_code)]
+        {
+            self.set_badge_count(text.and_then(|text| text.trim().parse().ok()));
+            Ok(())
+        }
+    }
+
     pub fn set_badge_count(&self, count: Option<i64>) {
         #[cfg(any(
             target_os = "linux",
@@ -670,7 +1580,77 @@ impl Window {
         let _ = label;
     }
 
+    /// Injects a key press or release, as if typed on a US layout. For UI tests.
+    ///
+    /// Synthetic events go through the event loop to the handler only; the OS and other
+    /// applications never see them. Their `device_id` is 0.
+    pub fn send_key(&self, code: KeyCode, state: ElementState) -> Result<(), TaoError> {
+        self.inject(vec![crate::synth::key_event(code, state)])
+    }
+
+    /// Injects a cursor move to `position` followed by a click of `button`. For UI tests.
+    pub fn send_mouse_click(&self, position: PhysicalPositionF64, button: MouseButton) -> Result<(), TaoError> {
+        self.inject(crate::synth::click_events(position, button))
+    }
+
+    /// Injects a key press and release per character of `text`. For UI tests.
+    pub fn send_text(&self, text: String) -> Result<(), TaoError> {
+        self.inject(crate::synth::text_events(&text))
+    }
+
     pub fn debug_string(&self) -> String {
         format!("Window(id={})", self.id)
     }
 }
+
+/// Menus, drag and drop and message boxes, which mobile platforms do not have.
+#[cfg(not(any(target_os = "android", target_os = "ios")))]
+#[uniffi::export]
+impl Window {
+    /// Sets the window's menu bar, or removes it with `None`. Call on the event loop thread.
+    ///
+    /// On macOS the menu becomes the application menu shared by all windows; prefer
+    /// `App::set_app_menu` there.
+    pub fn set_menu(&self, menu: Option<Arc<Menu>>) -> Result<(), TaoError> {
+        let window = self.inner.lock().unwrap();
+        let mut current = self.menu.lock().unwrap();
+        if let Some(old) = current.take() {
+            crate::menu::detach(old.inner.get()?, &window)?;
+        }
+        if let Some(menu) = &menu {
+            crate::menu::attach(menu.inner.get()?, &window)?;
+        }
+        *current = menu;
+        Ok(())
+    }
+
+    /// Starts dragging `data` out of the window, e.g. files into Finder or Explorer, with
+    /// `drag_image` under the cursor. Call on the event loop thread while a mouse button is
+    /// pressed. The end of the drag is reported as `TaoWindowEvent::DragFinished`.
+    pub fn start_drag(self: Arc<Self>, data: DragData, drag_image: Option<Arc<Icon>>) -> Result<(), TaoError> {
+        crate::drag_source::start_drag(&self, data, drag_image, &self.native_events)
+    }
+
+    /// Shows a message box modal to this window and returns the button that closed it.
+    ///
+    /// The event loop keeps running while the box is open, so await it from a
+    /// `TaoAsyncEventHandler` or a coroutine rather than blocking the loop thread.
+    pub async fn show_message_box(
+        &self,
+        level: MessageLevel,
+        title: String,
+        text: String,
+        buttons: MessageButtons,
+    ) -> MessageBoxResult {
+        let dialog = {
+            let window = self.inner.lock().unwrap();
+            rfd::AsyncMessageDialog::new()
+                .set_level(level.into())
+                .set_title(title)
+                .set_description(text)
+                .set_buttons(buttons.into())
+                .set_parent(&*window)
+        };
+        dialog.show().await.into()
+    }
+}